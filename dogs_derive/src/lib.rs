@@ -0,0 +1,289 @@
+//! Derive macros for the dogs search framework.
+//!
+//! `#[derive(SearchSpaceCombinator)]` implements, for a search space decorator, every
+//! search space trait by forwarding it to the wrapped search space. Traits implemented
+//! by hand (because the decorator changes their behavior or wraps the node type) are
+//! listed in `#[combinator(overrides(...))]` and are not generated.
+
+#![warn(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields, Generics, Type};
+
+/// traits that can be forwarded by the derive
+const FORWARDED_TRAITS: &[&str] = &[
+    "SearchSpaceCombinator",
+    "SearchSpace",
+    "GuidedSpace",
+    "ToSolution",
+    "IteratorNeighborGeneration",
+    "PartialNeighborGeneration",
+    "CheckpointSpace",
+    "Identifiable",
+    "ParetoDominanceSpace",
+    "BiObjectiveSpace",
+    "BoundedDistanceSpace",
+    "DecisionSpace",
+    "SolutionDistanceSpace",
+    "SolutionCostSpace",
+];
+
+/**
+Implements the search space traits of a decorator by forwarding them to the wrapped space.
+
+The wrapped space is the field marked with `#[combinator(inner)]` (by default, the field `s`).
+Traits listed in `#[combinator(overrides(TraitA, TraitB))]` are implemented by hand and are
+not generated. Every generated implementation only requires the wrapped space to implement
+the same trait, so a decorator provides exactly the traits its search space provides.
+*/
+#[proc_macro_derive(SearchSpaceCombinator, attributes(combinator))]
+pub fn derive_search_space_combinator(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// decorator being derived
+struct Combinator<'a> {
+    name: &'a Ident,
+    generics: &'a Generics,
+    inner_field: &'a Ident,
+    inner_ty: &'a Type,
+}
+
+impl Combinator<'_> {
+    /// implementation of `trait_ty` for the decorator, with additional type parameters
+    fn forward(&self, params: &[&Ident], trait_ty: TokenStream2, body: TokenStream2) -> TokenStream2 {
+        let mut generics = self.generics.clone();
+        for p in params {
+            generics.params.push(parse_quote!(#p));
+        }
+        let inner_ty = self.inner_ty;
+        generics.make_where_clause().predicates.push(parse_quote!(#inner_ty: #trait_ty));
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let (_, ty_generics, _) = self.generics.split_for_impl();
+        let name = self.name;
+        quote! {
+            impl #impl_generics #trait_ty for #name #ty_generics #where_clause {
+                #body
+            }
+        }
+    }
+
+    /// forwarding implementation of a trait (given by its name)
+    fn forward_trait(&self, trait_name: &str) -> TokenStream2 {
+        let s = self.inner_field;
+        let inner = self.inner_ty;
+        let n = Ident::new("DogsNode", Span::call_site());
+        let b = Ident::new("DogsBound", Span::call_site());
+        let g = Ident::new("DogsGuide", Span::call_site());
+        let sol = Ident::new("DogsSolution", Span::call_site());
+        let id = Ident::new("DogsId", Span::call_site());
+        let d = Ident::new("DogsDecision", Span::call_site());
+        let json = quote!(::dogs::serde_json);
+        let space = quote!(::dogs::search_space);
+        match trait_name {
+            "SearchSpaceCombinator" => {
+                let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+                let name = self.name;
+                quote! {
+                    impl #impl_generics ::dogs::search_combinator::SearchSpaceCombinator<#inner> for #name #ty_generics #where_clause {
+                        fn unwrap(&self) -> &#inner { &self.#s }
+                    }
+                }
+            }
+            "SearchSpace" => {
+                let t = quote!(#space::SearchSpace<#n, #b>);
+                self.forward(&[&n, &b], t.clone(), quote! {
+                    fn initial(&mut self) -> #n { <#inner as #t>::initial(&mut self.#s) }
+                    fn bound(&mut self, node: &#n) -> #b { <#inner as #t>::bound(&mut self.#s, node) }
+                    fn goal(&mut self, node: &#n) -> bool { <#inner as #t>::goal(&mut self.#s, node) }
+                    fn g_cost(&mut self, node: &#n) -> #b { <#inner as #t>::g_cost(&mut self.#s, node) }
+                    fn handle_new_best(&mut self, node: #n) -> #n { <#inner as #t>::handle_new_best(&mut self.#s, node) }
+                    fn notify_incumbent(&mut self, value: #b) { <#inner as #t>::notify_incumbent(&mut self.#s, value) }
+                    fn start_search(&mut self, msg: String) { <#inner as #t>::start_search(&mut self.#s, msg) }
+                    fn restart(&mut self, msg: String) { <#inner as #t>::restart(&mut self.#s, msg) }
+                    fn stop_search(&mut self, msg: String) { <#inner as #t>::stop_search(&mut self.#s, msg) }
+                    fn display_statistics(&self) { <#inner as #t>::display_statistics(&self.#s) }
+                    fn json_statistics(&self, json: &mut #json::Value) { <#inner as #t>::json_statistics(&self.#s, json) }
+                    fn report_sections(&self, report: &mut ::dogs::search_report::SearchReport) { <#inner as #t>::report_sections(&self.#s, report) }
+                    fn describe_layers(&self, stack: &mut ::dogs::search_combinator::StackDescription) {
+                        <#inner as #t>::describe_layers(&self.#s, stack)
+                    }
+                    fn dual_bound(&self) -> Option<#b> { <#inner as #t>::dual_bound(&self.#s) }
+                    fn sense(&self) -> #space::ObjectiveSense { <#inner as #t>::sense(&self.#s) }
+                    fn is_exact(&self) -> bool { <#inner as #t>::is_exact(&self.#s) }
+                    fn request_log_header(&self, res: Vec<String>) { <#inner as #t>::request_log_header(&self.#s, res) }
+                    fn request_logging(&self, res: Vec<String>) { <#inner as #t>::request_logging(&self.#s, res) }
+                })
+            }
+            "GuidedSpace" => {
+                let t = quote!(#space::GuidedSpace<#n, #g>);
+                self.forward(&[&n, &g], t.clone(), quote! {
+                    fn guide(&mut self, node: &#n) -> #g { <#inner as #t>::guide(&mut self.#s, node) }
+                })
+            }
+            "ToSolution" => {
+                let t = quote!(#space::ToSolution<#n, #sol>);
+                self.forward(&[&n, &sol], t.clone(), quote! {
+                    fn solution(&mut self, node: &mut #n) -> #sol { <#inner as #t>::solution(&mut self.#s, node) }
+                    fn solution_to_node(&mut self, node: &#n, sol: &#sol) -> Option<#n> {
+                        <#inner as #t>::solution_to_node(&mut self.#s, node, sol)
+                    }
+                })
+            }
+            "IteratorNeighborGeneration" => {
+                let t = quote!(#space::IteratorNeighborGeneration<#n>);
+                self.forward(&[&n], t.clone(), quote! {
+                    type Cursor = <#inner as #t>::Cursor;
+                    fn neighbors_cursor(&mut self, node: &mut #n) -> Self::Cursor {
+                        <#inner as #t>::neighbors_cursor(&mut self.#s, node)
+                    }
+                    fn next_neighbor_from(&mut self, node: &mut #n, cursor: &mut Self::Cursor) -> Option<#n> {
+                        <#inner as #t>::next_neighbor_from(&mut self.#s, node, cursor)
+                    }
+                })
+            }
+            "PartialNeighborGeneration" => {
+                let t = quote!(#space::PartialNeighborGeneration<#n>);
+                self.forward(&[&n], t.clone(), quote! {
+                    fn next_neighbor(&mut self, node: &mut #n) -> Option<#n> { <#inner as #t>::next_neighbor(&mut self.#s, node) }
+                })
+            }
+            "CheckpointSpace" => {
+                let t = quote!(#space::CheckpointSpace);
+                self.forward(&[], t.clone(), quote! {
+                    fn save_state(&self, json: &mut #json::Value) { <#inner as #t>::save_state(&self.#s, json) }
+                    fn load_state(&mut self, json: &#json::Value) -> Result<(), #json::Error> {
+                        <#inner as #t>::load_state(&mut self.#s, json)
+                    }
+                })
+            }
+            "Identifiable" => {
+                let t = quote!(#space::Identifiable<#n, #id>);
+                self.forward(&[&n, &id], t.clone(), quote! {
+                    fn id(&self, node: &mut #n) -> #id { <#inner as #t>::id(&self.#s, node) }
+                })
+            }
+            "ParetoDominanceSpace" => {
+                let t = quote!(#space::ParetoDominanceSpace<#n>);
+                self.forward(&[&n], t.clone(), quote! {
+                    fn dominates(&self, a: &#n, b: &#n) -> bool { <#inner as #t>::dominates(&self.#s, a, b) }
+                })
+            }
+            "BiObjectiveSpace" => {
+                let t = quote!(#space::BiObjectiveSpace<#n>);
+                self.forward(&[&n], t.clone(), quote! {
+                    fn objectives(&self, node: &#n) -> [f64; 2] { <#inner as #t>::objectives(&self.#s, node) }
+                })
+            }
+            "BoundedDistanceSpace" => {
+                let t = quote!(#space::BoundedDistanceSpace<#n>);
+                self.forward(&[&n], t.clone(), quote! {
+                    fn maximum_root_distance(&self) -> usize { <#inner as #t>::maximum_root_distance(&self.#s) }
+                    fn distance_from_root(&self, node: &#n) -> usize { <#inner as #t>::distance_from_root(&self.#s, node) }
+                    fn root_distance_ratio(&self, node: &#n) -> f64 { <#inner as #t>::root_distance_ratio(&self.#s, node) }
+                })
+            }
+            "DecisionSpace" => {
+                let t = quote!(#space::DecisionSpace<#n, #d>);
+                self.forward(&[&n, &d], t.clone(), quote! {
+                    fn decision(&self, node: &#n) -> Option<#d> { <#inner as #t>::decision(&self.#s, node) }
+                    fn aspiration_criterion(&self, node: &#n) -> bool { <#inner as #t>::aspiration_criterion(&self.#s, node) }
+                })
+            }
+            "SolutionDistanceSpace" => {
+                let t = quote!(#space::SolutionDistanceSpace<#sol>);
+                self.forward(&[&sol], t.clone(), quote! {
+                    fn solution_distance(&self, a: &#sol, b: &#sol) -> f64 { <#inner as #t>::solution_distance(&self.#s, a, b) }
+                })
+            }
+            "SolutionCostSpace" => {
+                let t = quote!(#space::SolutionCostSpace<#sol, #b>);
+                self.forward(&[&sol, &b], t.clone(), quote! {
+                    fn solution_cost(&self, sol: &#sol) -> #b { <#inner as #t>::solution_cost(&self.#s, sol) }
+                })
+            }
+            _ => unreachable!("unknown forwarded trait {}", trait_name),
+        }
+    }
+}
+
+/// true iff the field is marked with `#[combinator(inner)]`
+fn is_marked_inner(field: &syn::Field) -> syn::Result<bool> {
+    let mut res = false;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("combinator")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("inner") {
+                res = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `inner`"))
+            }
+        })?;
+    }
+    Ok(res)
+}
+
+/// traits listed in the `#[combinator(overrides(...))]` attributes of the decorator
+fn overridden_traits(input: &DeriveInput) -> syn::Result<Vec<String>> {
+    let mut res = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("combinator")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("overrides") {
+                return Err(meta.error("expected `overrides(...)`"));
+            }
+            meta.parse_nested_meta(|t| {
+                let name = match t.path.get_ident() {
+                    Some(ident) => ident.to_string(),
+                    None => return Err(t.error("expected a trait name")),
+                };
+                if !FORWARDED_TRAITS.contains(&name.as_str()) {
+                    return Err(t.error(format!("`{}` is not a forwarded search space trait", name)));
+                }
+                res.push(name);
+                Ok(())
+            })
+        })?;
+    }
+    Ok(res)
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(input, "a combinator must have named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(input, "a combinator must be a struct")),
+    };
+    let mut marked = Vec::new();
+    for field in fields {
+        if is_marked_inner(field)? {
+            marked.push(field);
+        }
+    }
+    let inner = match marked.len() {
+        0 => fields.iter().find(|f| f.ident.as_ref().is_some_and(|i| i == "s")),
+        1 => marked.pop(),
+        _ => return Err(syn::Error::new_spanned(marked[1], "only one field can be marked `#[combinator(inner)]`")),
+    };
+    let inner = inner.ok_or_else(|| syn::Error::new_spanned(
+        input, "no wrapped search space: add a field `s` or mark a field with `#[combinator(inner)]`"
+    ))?;
+    let combinator = Combinator {
+        name: &input.ident,
+        generics: &input.generics,
+        inner_field: inner.ident.as_ref().unwrap(),
+        inner_ty: &inner.ty,
+    };
+    let overrides = overridden_traits(input)?;
+    let impls = FORWARDED_TRAITS.iter()
+        .filter(|t| !overrides.iter().any(|o| o == *t))
+        .map(|t| combinator.forward_trait(t));
+    Ok(quote! { #(#impls)* })
+}
@@ -1,14 +1,16 @@
 use std::collections::BTreeMap;
-use std::fmt::Display;
 use std::cell::RefCell;
-use std::rc::{Weak, Rc};
-use std::cmp::max;
-use serde::{Serialize};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, Weak};
+use std::cmp::{max, min};
 use std::marker::PhantomData;
 
 use crate::metric_logger::{Metric, MetricLogger};
-use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, ToSolution};
-use crate::search_combinator::SearchSpaceCombinator;
+use crate::search_space::{SearchSpace, GuidedSpace, IteratorNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, ToSolution, ObjectiveSense, BiObjectiveSpace};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
+use crate::objective::Objective;
+use crate::space_handle::SpaceHandle;
 
 /**
 Provides methods to be called if a node is destroyed or inserted. 
@@ -23,38 +25,43 @@ pub trait LifetimeEventListener<B> {
 
 /** 
 wrapper on a node to monitor its destruction or insertion.
+The listener is shared through P: `Rc<RefCell<C>>` (single-threaded, default) or `Arc<Mutex<C>>`
+(the nodes can be sent to other threads).
 */
 #[derive(Debug)]
-pub struct LifetimeEventNode<N, B, C>
-where C: LifetimeEventListener<B> {
+pub struct LifetimeEventNode<N, B, C, P=Rc<RefCell<C>>>
+where C: LifetimeEventListener<B>, P: SpaceHandle<C> {
     /// original node
     pub node: N,
     /// bound of the node
     pub bound: B,
     /// reference to the event listener
-    pub lifetime_listener: Rc<RefCell<C>>,
+    pub lifetime_listener: P,
     /// true iff the node was already expanded (used to identify heuristic prunings)
     pub expanded: bool,
+    /// phantom for the listener type (C)
+    pub phantom_listener: PhantomData<C>,
 }
 
-impl<N,B,C> Clone for LifetimeEventNode<N,B,C>
-where N:Clone, B:Clone, C: LifetimeEventListener<B> {
+impl<N,B,C,P> Clone for LifetimeEventNode<N,B,C,P>
+where N:Clone, B:Clone, C: LifetimeEventListener<B>, P: SpaceHandle<C> {
     fn clone(&self) -> Self {
-        self.lifetime_listener.borrow_mut().on_insertvevent(&self.bound);
+        self.lifetime_listener.lock().on_insertvevent(&self.bound);
         Self {
             node: self.node.clone(),
             bound: self.bound.clone(),
             lifetime_listener: self.lifetime_listener.clone(),
             expanded: false,
+            phantom_listener: PhantomData,
         }
     }
 }
 
-impl<N, B, C> Drop for LifetimeEventNode<N, B, C> 
-where C: LifetimeEventListener<B> {
+impl<N, B, C, P> Drop for LifetimeEventNode<N, B, C, P> 
+where C: LifetimeEventListener<B>, P: SpaceHandle<C> {
     fn drop(&mut self) {
         if self.expanded {
-            self.lifetime_listener.borrow_mut().on_destructevent(&self.bound);
+            self.lifetime_listener.lock().on_destructevent(&self.bound);
         }
     }
 }
@@ -69,18 +76,21 @@ pub struct BoundSet<B> {
     pub set: BTreeMap<B, usize>,
     /// best dual bound
     pub global_bound: Option<B>,
+    /// objective sense (the dual bound increases when minimizing, decreases when maximizing)
+    pub sense: ObjectiveSense,
     /// logger to display global bound update
     pub logger: Weak<MetricLogger>,
     /// id of the bound field in the logger
     pub logging_id_bound: Option<usize>,
 }
 
-impl<B> BoundSet<B> where B:Ord+Display+Clone+Copy+Into<i64> {
+impl<B> BoundSet<B> where B:Objective {
     /** builds a bound set by giving it a logger */
     pub fn new(logger: Weak<MetricLogger>,) -> Self {
         Self {
             set: BTreeMap::new(),
             global_bound: None,
+            sense: ObjectiveSense::default(),
             logger,
             logging_id_bound: None,
         }
@@ -100,7 +110,7 @@ impl<B> BoundSet<B> where B:Ord+Display+Clone+Copy+Into<i64> {
             self.global_bound = Some(*b);
             if let Some(logger) = self.logger.upgrade() {
                 if let Some(id) = self.logging_id_bound {
-                    logger.update_metric(id, Metric::Int((*b).into()));
+                    logger.update_metric(id, b.to_metric());
                 }
             }
         }
@@ -144,11 +154,13 @@ impl<B> BoundSet<B> where B:Ord+Display+Clone+Copy+Into<i64> {
                 None => panic!("removing a bound value not-existing global bound"),
                 Some(v) => {
                     let previous_bound = self.global_bound;
-                    self.global_bound = self.set.iter().next().map(|v2|
-                        max(v, *v2.0)
-                    );
-                    if previous_bound < self.global_bound {
-                        logging_required = true;  // only logs if improving the bound
+                    self.global_bound = match self.sense {
+                        ObjectiveSense::Minimize => self.set.keys().next().map(|v2| max(v, *v2)),
+                        ObjectiveSense::Maximize => self.set.keys().next_back().map(|v2| min(v, *v2)),
+                    };
+                    if let (Some(new_bound), Some(d)) = (self.global_bound, previous_bound) {
+                        // only logs if improving the bound
+                        logging_required = self.sense.is_tighter_bound(&new_bound, &d);
                     }
                 }
             }
@@ -157,7 +169,7 @@ impl<B> BoundSet<B> where B:Ord+Display+Clone+Copy+Into<i64> {
                     if let Some(id) = self.logging_id_bound {
                         let metric = match self.global_bound {
                             None => Metric::Text("infeasible".to_string()),
-                            Some(v) => Metric::Int(v.into()),
+                            Some(v) => v.to_metric(),
                         };
                         logger.update_metric(id, metric);
                         // logger.request_logging();
@@ -169,7 +181,7 @@ impl<B> BoundSet<B> where B:Ord+Display+Clone+Copy+Into<i64> {
 }
 
 impl<B> LifetimeEventListener<B> for BoundSet<B> 
-where B:Ord+Display+Clone+Copy+Into<i64> {
+where B:Objective {
     fn on_destructevent(&mut self, bound: &B) {
         self.remove(bound);
     }
@@ -183,44 +195,72 @@ where B:Ord+Display+Clone+Copy+Into<i64> {
  * Registers the global dual bound
  *  - when a node is destructed: remove its bound of the pq and update the global bound
  *  - TODO when a node bound is updated: update the global bound
+ *
+ * It does not implement CheckpointSpace: the bound set refers to the open nodes of the search,
+ * which cannot be restored from a checkpoint.
+ * The bound set is shared with the nodes through P: `Rc<RefCell<_>>` by default, or
+ * `Arc<Mutex<_>>` to run the search on a worker thread (see SyncBoundingCombinator).
  */
-#[derive(Debug)]
-pub struct BoundingCombinator<Space, B, N> {
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, GuidedSpace, ToSolution, IteratorNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, BiObjectiveSpace, BoundedDistanceSpace, DecisionSpace, CheckpointSpace))]
+pub struct BoundingCombinator<Space, B, N, P=Rc<RefCell<BoundSet<B>>>> {
     /// wrapped search space
     s: Space,
     /// bound set to measure the bound
-    bound_set: Rc<RefCell<BoundSet<B>>>,
+    bound_set: P,
+    /// best known primal bound (new-best-known solutions and incumbents provided by a warm start)
+    primal_bound: Option<B>,
     /// phantom for the node type (N)
     phantom_n: PhantomData<N>,
 }
 
 
-impl<N,G,Space,B> GuidedSpace<LifetimeEventNode<N, B, BoundSet<B>>,G> for BoundingCombinator<Space, B, N>
-where Space:GuidedSpace<N,G>, B:Display+Ord+Copy+Into<i64>
+/**
+ * BoundingCombinator whose bound set is shared through an `Arc<Mutex<_>>`: the nodes (and the
+ * search) can be sent to a worker thread.
+ */
+pub type SyncBoundingCombinator<Space, B, N> = BoundingCombinator<Space, B, N, Arc<Mutex<BoundSet<B>>>>;
+
+impl<N,G,Space,B,P> GuidedSpace<LifetimeEventNode<N, B, BoundSet<B>, P>,G> for BoundingCombinator<Space, B, N, P>
+where Space:GuidedSpace<N,G>, B:Objective, P:SpaceHandle<BoundSet<B>>
 {
-    fn guide(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>>) -> G { self.s.guide(&n.node) }
+    fn guide(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>, P>) -> G { self.s.guide(&n.node) }
 }
 
-impl <N,Sol,B,Space> ToSolution<LifetimeEventNode<N, B, BoundSet<B>>,Sol> for BoundingCombinator<Space, B, N>
+impl <N,Sol,B,Space,P> ToSolution<LifetimeEventNode<N, B, BoundSet<B>, P>,Sol> for BoundingCombinator<Space, B, N, P>
 where
+    P: SpaceHandle<BoundSet<B>>,
     Space: SearchSpace<N,B>+ToSolution<N,Sol>,
-    B:Ord+Display+Copy+Into<i64>
+    B:Objective
 {
-    fn solution(&mut self, n: &mut LifetimeEventNode<N, B, BoundSet<B>>) -> Sol {
+    fn solution(&mut self, n: &mut LifetimeEventNode<N, B, BoundSet<B>, P>) -> Sol {
         self.s.solution(&mut n.node)
     }
+
+    fn solution_to_node(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>, P>, sol: &Sol) -> Option<LifetimeEventNode<N, B, BoundSet<B>, P>> {
+        // the new node is not registered in the bound set (it is not expanded)
+        self.s.solution_to_node(&n.node, sol).map(|node| LifetimeEventNode {
+            node,
+            bound: n.bound,
+            lifetime_listener: n.lifetime_listener.clone(),
+            expanded: false,
+            phantom_listener: PhantomData,
+        })
+    }
 }
 
 
-impl<N,Space,B> SearchSpace<LifetimeEventNode<N, B, BoundSet<B>>,B> for BoundingCombinator<Space, B, N>
+impl<N,Space,B,P> SearchSpace<LifetimeEventNode<N, B, BoundSet<B>, P>,B> for BoundingCombinator<Space, B, N, P>
 where
+    P: SpaceHandle<BoundSet<B>>,
     N:Clone,
     Space:SearchSpace<N,B>,
-    B:Display+Ord+Copy+Into<i64>+Serialize,
+    B:Objective,
     // C:LifetimeEventListener<B>
 {
 
-    fn initial(&mut self) -> LifetimeEventNode<N, B, BoundSet<B>> {
+    fn initial(&mut self) -> LifetimeEventNode<N, B, BoundSet<B>, P> {
+        self.bound_set.lock().sense = self.s.sense();
         let initial = self.s.initial();
         let bound = self.s.bound(&initial);
         self.insert_bound(&bound);
@@ -229,33 +269,38 @@ where
             bound,
             lifetime_listener: self.bound_set.clone(),
             expanded: false,
+            phantom_listener: PhantomData,
         }
     }
 
-    fn bound(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>>) -> B {
+    fn bound(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>, P>) -> B {
         self.s.bound(&n.node)
     }
 
-    fn goal(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>>) -> bool {
+    fn goal(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>, P>) -> bool {
         self.s.goal(&n.node)
     }
 
-    fn g_cost(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>>) -> B {
+    fn g_cost(&mut self, n: &LifetimeEventNode<N, B, BoundSet<B>, P>) -> B {
         self.s.g_cost(&n.node)
     }
 
     fn restart(&mut self, msg: String) {
         // reinitializes the boundSet
-        self.bound_set.borrow_mut().reset();
+        self.bound_set.lock().reset();
         self.s.restart(msg);
     }
 
-    fn handle_new_best(&mut self, n: LifetimeEventNode<N, B, BoundSet<B>>) -> LifetimeEventNode<N, B, BoundSet<B>> {
+    fn handle_new_best(&mut self, n: LifetimeEventNode<N, B, BoundSet<B>, P>) -> LifetimeEventNode<N, B, BoundSet<B>, P> {
+        let node = self.s.handle_new_best(n.node.clone());
+        let value = self.s.bound(&node);
+        self.update_primal_bound(value);
         LifetimeEventNode {
-            node: self.s.handle_new_best(n.node.clone()),
+            node,
             bound: n.bound,
             lifetime_listener: n.lifetime_listener.clone(),
             expanded: n.expanded,
+            phantom_listener: PhantomData,
         }
     }
 
@@ -265,111 +310,172 @@ where
 
     fn display_statistics(&self) {
         println!();
-        match self.bound_set.borrow().global_bound {
+        match self.bound_set.lock().global_bound {
             None => println!("{:>25}{:>15}", "dual bound", "infeasible"),
-            Some(v) => println!("{:>25}{:>15}", "dual bound", v),
+            Some(v) => println!("{:>25}{:>15}", "dual bound", v.to_display()),
         }
         println!();
         self.s.display_statistics();
     }
 
     fn json_statistics(&self, json:&mut serde_json::Value) {
-        match self.bound_set.borrow().global_bound {
-            None => {},
-            Some(v) => {
-                json["dual_bound"] = serde_json::json!(v)
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        let mut section = ReportSection::new("bounding");
+        let global_bound = self.bound_set.lock().global_bound;
+        if let Some(v) = global_bound {
+            section = section.with_value("dual_bound", v);
+        }
+        if let (Some(best), Some(bound)) = (self.primal_bound, global_bound) {
+            let gap = best.gap(&bound, self.s.sense());
+            if gap.is_finite() {
+                section = section.with_value("gap", gap);
             }
         }
-        self.s.json_statistics(json);
+        report.push(section);
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("Bounding"));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.bound_set.lock().global_bound }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) {
+        self.update_primal_bound(value);
+        self.s.notify_incumbent(value);
     }
 }
 
-impl<N, B, Space> TotalNeighborGeneration<LifetimeEventNode<N, B, BoundSet<B>>> for BoundingCombinator<Space, B, N>
+impl<N, B, Space, P> IteratorNeighborGeneration<LifetimeEventNode<N, B, BoundSet<B>, P>> for BoundingCombinator<Space, B, N, P>
 where
-    Space: TotalNeighborGeneration<N>+SearchSpace<N,B>,
-    B: Ord+Display+Copy+Into<i64>
+    P: SpaceHandle<BoundSet<B>>,
+    Space: IteratorNeighborGeneration<N>+SearchSpace<N,B>,
+    B: Objective
 {
-    fn neighbors(&mut self, n: &mut LifetimeEventNode<N, B, BoundSet<B>>) -> Vec<LifetimeEventNode<N, B, BoundSet<B>>> {
-        let children = self.s.neighbors(&mut n.node);
-        // create node wrappers
-        let mut res:Vec<LifetimeEventNode<N, B, BoundSet<B>>> = Vec::new();
-        for e in children {
-            let bound_e = self.s.bound(&e);
-            self.insert_bound(&bound_e);
-            res.push(LifetimeEventNode {
-                node: e,
-                bound: bound_e,
-                lifetime_listener: self.bound_set.clone(),
-                expanded: false,
-            });
+    type Cursor = Space::Cursor;
+
+    fn neighbors_cursor(&mut self, n: &mut LifetimeEventNode<N, B, BoundSet<B>, P>) -> Self::Cursor {
+        self.s.neighbors_cursor(&mut n.node)
+    }
+
+    fn next_neighbor_from(&mut self, n: &mut LifetimeEventNode<N, B, BoundSet<B>, P>, cursor: &mut Self::Cursor) -> Option<LifetimeEventNode<N, B, BoundSet<B>, P>> {
+        match self.s.next_neighbor_from(&mut n.node, cursor) {
+            None => { n.expanded = true; None }
+            Some(e) => {
+                // create node wrapper
+                let bound_e = self.s.bound(&e);
+                self.insert_bound(&bound_e);
+                Some(LifetimeEventNode {
+                    node: e,
+                    bound: bound_e,
+                    lifetime_listener: self.bound_set.clone(),
+                    expanded: false,
+                    phantom_listener: PhantomData,
+                })
+            }
         }
-        n.expanded = true;
-        res
     }
 }
 
 
-impl<Space, B, N> SearchSpaceCombinator<Space> for BoundingCombinator<Space, B, N> {
-    fn unwrap(&self) -> &Space { &self.s }
+impl<Space, B:Objective, N> BoundingCombinator<Space, B, N> {
+    /** builds the decorator using the wrapped space (single-threaded) */
+    pub fn new(s: Space) -> Self { Self::with_handle(s) }
 }
 
+impl<Space, B:Objective, N> SyncBoundingCombinator<Space, B, N> {
+    /** builds the decorator using the wrapped space (the search can run on a worker thread) */
+    pub fn new_sync(s: Space) -> Self { Self::with_handle(s) }
+}
 
-impl<Space, B, N> BoundingCombinator<Space, B, N> where B:Ord+Display+Copy+Into<i64> {
+impl<Space, B, N, P> BoundingCombinator<Space, B, N, P> where B:Objective, P:SpaceHandle<BoundSet<B>> {
     /** unwraps itself */
     pub fn unwrap(&self) -> &Space { &self.s }
 
     /** builds the decorator using the wrapped space */
-    pub fn new(s: Space) -> Self
-    where B:Ord+Into<i64> {
+    fn with_handle(s: Space) -> Self {
         Self {
             s,
-            bound_set:Rc::new(RefCell::new(BoundSet::new(Weak::new()))),
+            bound_set:P::from_value(BoundSet::new(Weak::new())),
+            primal_bound: None,
             phantom_n:PhantomData
         }
     }
 
+    /** registers a primal bound (ignored if it does not improve the best known one) */
+    fn update_primal_bound(&mut self, value:B) where Space:SearchSpace<N,B> {
+        if self.primal_bound.is_none_or(|v| self.s.sense().is_better(&value, &v)) {
+            self.primal_bound = Some(value);
+        }
+    }
+
     /** insert the bound in the bound set */
     fn insert_bound(&mut self, bound:&B) {
-        self.bound_set.borrow_mut().insert(bound);
+        self.bound_set.lock().insert(bound);
     }
 
-    /** binds the logger to display bound updates */
+    /** binds the logger to display bound updates (`Arc::downgrade(&logger)`, see StatTsCombinator::bind_logger) */
     pub fn bind_logger(self, logger_ref:Weak<MetricLogger>) -> Self {
         if let Some(logger) = logger_ref.upgrade() {
             // adds headers to the logger
             let tmp = logger.register_headers([
                 format!("{:<10}","dual"),
             ].to_vec());
-            self.bound_set.as_ref().borrow_mut().logging_id_bound = Some(tmp[0]);
+            self.bound_set.lock().logging_id_bound = Some(tmp[0]);
         }
         // registers the logger
-        self.bound_set.as_ref().borrow_mut().logger = logger_ref;
+        self.bound_set.lock().logger = logger_ref;
         self
     }
 
 }
 
-impl<N, B, Id, Space> Identifiable<LifetimeEventNode<N, B, BoundSet<B>>, Id> for BoundingCombinator<Space, B, N>
+impl<N, B, Id, Space, P> Identifiable<LifetimeEventNode<N, B, BoundSet<B>, P>, Id> for BoundingCombinator<Space, B, N, P>
 where
+    P: SpaceHandle<BoundSet<B>>,
     Space: Identifiable<N, Id>,
-    B:Ord+Display+Copy+Into<i64>,
+    B:Objective,
 {
-    fn id(&self, n: &mut LifetimeEventNode<N, B, BoundSet<B>>) -> Id { self.s.id(&mut n.node) }
+    fn id(&self, n: &mut LifetimeEventNode<N, B, BoundSet<B>, P>) -> Id { self.s.id(&mut n.node) }
 }
 
 
-impl<N,Space,B> ParetoDominanceSpace<N> for BoundingCombinator<Space, B, N>
-where Space: ParetoDominanceSpace<N>,
+impl<N,Space,B,P> ParetoDominanceSpace<LifetimeEventNode<N, B, BoundSet<B>, P>> for BoundingCombinator<Space, B, N, P>
+where
+    P: SpaceHandle<BoundSet<B>>,
+    Space: ParetoDominanceSpace<N>,
+    B: Objective,
+{
+    fn dominates(&self, a:&LifetimeEventNode<N, B, BoundSet<B>, P>, b:&LifetimeEventNode<N, B, BoundSet<B>, P>) -> bool {
+        self.s.dominates(&a.node, &b.node)
+    }
+}
+
+impl<N,Space,B,P> BiObjectiveSpace<LifetimeEventNode<N, B, BoundSet<B>, P>> for BoundingCombinator<Space, B, N, P>
+where
+    P: SpaceHandle<BoundSet<B>>,
+    Space: BiObjectiveSpace<N>,
+    B: Objective,
 {
-    fn dominates(&self, a:&N, b:&N) -> bool { self.s.dominates(a,b) }
+    fn objectives(&self, n:&LifetimeEventNode<N, B, BoundSet<B>, P>) -> [f64; 2] { self.s.objectives(&n.node) }
 }
 
-impl<N,Space,B> PartialNeighborGeneration<LifetimeEventNode<N, B, BoundSet<B>>> for BoundingCombinator<Space, B, N>
+impl<N,Space,B,P> PartialNeighborGeneration<LifetimeEventNode<N, B, BoundSet<B>, P>> for BoundingCombinator<Space, B, N, P>
 where
+    P: SpaceHandle<BoundSet<B>>,
     Space: PartialNeighborGeneration<N>+SearchSpace<N,B>,
-    B: Ord+Copy+Into<i64>+Display
+    B: Objective
 {
-    fn next_neighbor(&mut self, node: &mut LifetimeEventNode<N, B, BoundSet<B>>) -> Option<LifetimeEventNode<N, B, BoundSet<B>>> {
+    fn next_neighbor(&mut self, node: &mut LifetimeEventNode<N, B, BoundSet<B>, P>) -> Option<LifetimeEventNode<N, B, BoundSet<B>, P>> {
         match self.s.next_neighbor(&mut node.node) {
             None => { node.expanded = true; None }
             Some(c) => {
@@ -380,6 +486,7 @@ where
                     bound,
                     lifetime_listener: self.bound_set.clone(),
                     expanded: false,
+                    phantom_listener: PhantomData,
                 })
             }
         }
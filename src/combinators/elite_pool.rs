@@ -0,0 +1,263 @@
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::search_space::{
+    SearchSpace,
+    ToSolution,
+    SolutionDistanceSpace,
+    ObjectiveSense,
+    CheckpointSpace,
+};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
+use crate::data_structures::elite_pool::ElitePool;
+use crate::local_search::path_relinking::PathRelinkingSpace;
+
+/// path relinking between the pool members (requires a PathRelinkingSpace)
+type Relinker<Space, Sol, B> = fn(&mut ElitePoolCombinator<Space, Sol, B>);
+
+/**
+Elite pool decorator: each new-best-known solution is converted to a solution (ToSolution) and
+inserted in a bounded elite pool (diversity-aware insertion using SolutionDistanceSpace).
+If path relinking is enabled, each restart (for instance between two iterations of an
+IterativeSearch) relinks the best member of the pool with the most distant one (in both
+directions) and inserts the resulting solutions in the pool.
+The pool is kept across restarts.
+*/
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, CheckpointSpace))]
+pub struct ElitePoolCombinator<Space, Sol, B> {
+    s: Space,
+    pool: ElitePool<Sol, B>,
+    /// path relinking performed at each restart (if enabled)
+    relinker: Option<Relinker<Space, Sol, B>>,
+    nb_relinks: u64,
+    nb_relink_insertions: u64,
+    phantom_sol: PhantomData<Sol>,
+}
+
+impl<N,Space,Sol,B> SearchSpace<N,B> for ElitePoolCombinator<Space, Sol, B>
+where
+    Space: SearchSpace<N,B>+ToSolution<N,Sol>+SolutionDistanceSpace<Sol>,
+    B: PartialOrd+Copy,
+{
+    fn initial(&mut self) -> N { self.s.initial() }
+
+    fn bound(&mut self, n: &N) -> B { self.s.bound(n) }
+
+    fn g_cost(&mut self, n: &N) -> B { self.s.g_cost(n) }
+
+    fn goal(&mut self, n: &N) -> bool { self.s.goal(n) }
+
+    fn start_search(&mut self, msg: String) {
+        // the pool follows the objective sense of the search space
+        self.pool.set_sense(self.s.sense());
+        self.s.start_search(msg);
+    }
+
+    fn restart(&mut self, msg: String) {
+        if let Some(relink) = self.relinker {
+            relink(self);
+        }
+        self.s.restart(msg);
+    }
+
+    fn handle_new_best(&mut self, n: N) -> N {
+        let mut best = self.s.handle_new_best(n);
+        let sol = self.s.solution(&mut best);
+        let cost = self.s.bound(&best);
+        let space = &self.s;
+        self.pool.insert(sol, cost, |a,b| space.solution_distance(a,b));
+        best
+    }
+
+    fn stop_search(&mut self, msg: String) {
+        self.s.stop_search(msg);
+    }
+
+    fn display_statistics(&self) {
+        self.s.display_statistics();
+    }
+
+    fn json_statistics(&self, json:&mut serde_json::Value) {
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        report.push(ReportSection::new("elite_pool")
+            .with_counter("size", self.pool.len() as u64)
+            .with_counter("nb_insertions", self.pool.nb_insertions())
+            .with_counter("nb_rejections", self.pool.nb_rejections())
+            .with_counter("nb_relinks", self.nb_relinks)
+            .with_counter("nb_relink_insertions", self.nb_relink_insertions)
+        );
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("ElitePool")
+            .with_parameter("capacity", self.pool.capacity())
+            .with_parameter("min_distance", self.pool.min_distance())
+            .with_parameter("relink_on_restart", self.relinker.is_some()));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
+}
+
+impl<Space, Sol, B> CheckpointSpace for ElitePoolCombinator<Space, Sol, B>
+where
+    Space: CheckpointSpace,
+    Sol: Serialize+DeserializeOwned,
+    B: PartialOrd+Copy+Serialize+DeserializeOwned,
+{
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["elite_pool"] = json!({
+            "pool": self.pool.save_state(),
+            "nb_relinks": self.nb_relinks,
+            "nb_relink_insertions": self.nb_relink_insertions,
+        });
+        self.s.save_state(json);
+    }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.pool.load_state(&json["elite_pool"]["pool"])?;
+        self.nb_relinks = serde_json::from_value(json["elite_pool"]["nb_relinks"].clone())?;
+        self.nb_relink_insertions = serde_json::from_value(json["elite_pool"]["nb_relink_insertions"].clone())?;
+        self.s.load_state(json)
+    }
+}
+
+impl<Space, Sol, B:PartialOrd+Copy> ElitePoolCombinator<Space, Sol, B> {
+    /** builds the decorator around a search space given the pool capacity and the minimum
+    distance between two pool members */
+    pub fn new(s: Space, capacity:usize, min_distance:f64) -> Self {
+        Self {
+            s,
+            pool: ElitePool::new(capacity, min_distance),
+            relinker: None,
+            nb_relinks: 0,
+            nb_relink_insertions: 0,
+            phantom_sol: PhantomData,
+        }
+    }
+
+    /** returns the elite pool */
+    pub fn pool(&self) -> &ElitePool<Sol, B> { &self.pool }
+
+    /** returns the number of path relinkings performed */
+    pub fn nb_relinks(&self) -> u64 { self.nb_relinks }
+}
+
+impl<Space, Sol, B> ElitePoolCombinator<Space, Sol, B>
+where
+    Space: SolutionDistanceSpace<Sol>+PathRelinkingSpace<Sol,B>,
+    B: PartialOrd+Copy,
+{
+    /** enables path relinking between pool members at each restart */
+    pub fn with_path_relinking(mut self) -> Self {
+        self.relinker = Some(Self::relink);
+        self
+    }
+
+    /**
+    relinks the best member of the pool with the most distant one (in both directions) and
+    inserts the intermediate solutions found in the pool.
+    */
+    pub fn relink(&mut self) {
+        if self.pool.len() < 2 { return; }
+        let space = &mut self.s;
+        let best = self.pool.best_index().expect("non-empty pool");
+        let (best_sol, _) = self.pool.get(best).expect("valid index");
+        let other = match self.pool.most_distant(best_sol, |a,b| space.solution_distance(a,b)) {
+            Some(i) if i != best => i,
+            _ => { return; }
+        };
+        let (other_sol, _) = self.pool.get(other).expect("valid index");
+        let forward = space.relink(best_sol, other_sol);
+        let backward = space.relink(other_sol, best_sol);
+        self.nb_relinks += 2;
+        for (sol, cost) in forward.into_iter().chain(backward) {
+            if self.pool.insert(sol, cost, |a,b| space.solution_distance(a,b)) {
+                self.nb_relink_insertions += 1;
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion};
+    use crate::search_manager::SearchStatus;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+    use crate::combinators::neighbor_generation::TotalAdapter;
+    use crate::test_spaces::{Node, BinaryTree, TieTree, leaf};
+
+    impl PathRelinkingSpace<i64, i64> for BinaryTree {
+        /// the middle of the path (if distinct from both ends)
+        fn relink(&mut self, from:&i64, to:&i64) -> Option<(i64, i64)> {
+            let middle = (from + to) / 2;
+            if middle == *from || middle == *to { None } else { Some((middle, middle)) }
+        }
+    }
+
+    #[test]
+    fn pool_without_relinking() {
+        // the tie tree does not implement PathRelinkingSpace
+        let pool:ElitePoolCombinator<_, i64, i64> = ElitePoolCombinator::new(TotalAdapter::new(TieTree { depth: 4 }), 3, 0.5);
+        let space = Rc::new(RefCell::new(pool));
+        let mut dfs:DepthFirstSearch<Node, i64, i32, _> = DepthFirstSearch::new(space.clone());
+        dfs.run(NeverStoppingCriterion::default());
+        assert_eq!(dfs.status(), SearchStatus::Optimal);
+        let best_val = dfs.get_manager().best_val().unwrap();
+        assert!(!space.borrow().pool().is_empty());
+        assert_eq!(space.borrow().pool().best().map(|e| e.1), Some(best_val));
+        space.borrow_mut().restart("restart".to_string());
+        assert_eq!(space.borrow().nb_relinks(), 0);
+        assert_eq!(space.borrow().describe().layers[0].parameters["relink_on_restart"], serde_json::json!(false));
+    }
+
+    #[test]
+    fn maximization() {
+        // the pool takes the objective sense of the search space when the search starts
+        let pool:ElitePoolCombinator<_, i64, i64> = ElitePoolCombinator::new(BinaryTree::new(ObjectiveSense::Maximize), 3, 0.5);
+        let space = Rc::new(RefCell::new(pool));
+        let mut dfs:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(space.clone());
+        dfs.run(NeverStoppingCriterion::default());
+        assert_eq!(space.borrow().pool().sense(), ObjectiveSense::Maximize);
+        assert_eq!(space.borrow().pool().best().map(|e| e.1), Some(3));
+        assert_eq!(*dfs.get_manager().best_val(), Some(3));
+    }
+
+    #[test]
+    fn relinking_on_restart() {
+        let mut space = ElitePoolCombinator::new(BinaryTree::new(ObjectiveSense::Minimize), 5, 0.5).with_path_relinking();
+        space.handle_new_best(leaf(0));
+        // a single member: nothing to relink
+        space.restart("restart".to_string());
+        assert_eq!(space.nb_relinks(), 0);
+        space.handle_new_best(leaf(3));
+        space.restart("restart".to_string());
+        // both directions give the middle of the path (inserted once)
+        assert_eq!(space.nb_relinks(), 2);
+        let mut json = serde_json::json!({});
+        space.json_statistics(&mut json);
+        assert_eq!(json["elite_pool_nb_relink_insertions"], serde_json::json!(1));
+        let mut members:Vec<i64> = space.pool().iter().map(|e| e.0).collect();
+        members.sort_unstable();
+        assert_eq!(members, vec![0, 1, 3]);
+    }
+}
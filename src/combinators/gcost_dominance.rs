@@ -3,9 +3,13 @@ use std::collections::hash_map::Entry;
 use std::hash::Hash;
 
 use fxhash::FxHashMap;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
 
-use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, ToSolution};
-use crate::search_combinator::SearchSpaceCombinator;
+use crate::search_space::{SearchSpace, IteratorNeighborGeneration, PartialNeighborGeneration, Identifiable, ObjectiveSense, CheckpointSpace};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
 
 /**
 implements a dominance information used to represent a previous state (g-cost and iter number)
@@ -42,14 +46,16 @@ impl<Id, B> Default for DominanceStore<Id, B> where Id:Eq+Hash, B:PartialOrd {
 impl<Id, B> DominanceStore<Id, B> where Id:Eq+Hash, B:PartialOrd {
     /**
     returns true if the information is dominated, or insert it and returns false.
+    A stored value dominates b if it is better according to the objective sense (or equal and
+    inserted during the same iteration).
     */
-    pub fn is_dominated_or_add(&mut self, pe:Id, b:B, iter:u32) -> bool {
+    pub fn is_dominated_or_add(&mut self, pe:Id, b:B, iter:u32, sense:ObjectiveSense) -> bool {
         self.nb_gets += 1;
         match self.store.entry(pe) {
             Entry::Occupied(o) => {
                 // if the prefix equivalence exists in the database
                 let info = o.into_mut();
-                if info.val < b
+                if sense.is_better(&info.val, &b)
                     || (info.val == b && info.iter == iter)
                 {
                     self.nb_dominations += 1;
@@ -84,34 +90,52 @@ impl<Id, B> DominanceStore<Id, B> where Id:Eq+Hash, B:PartialOrd {
 
     /** exports statistics to a JSON format */
     pub fn export_statistics(&self, json:&mut serde_json::Value) {
-        json["gcost_dom_nb_elts"] = serde_json::json!(self.store.len());
-        json["gcost_dom_nb_gets"] = serde_json::json!(self.nb_gets);
-        json["gcost_dom_nb_pruned"] = serde_json::json!(self.nb_dominations);
-        json["gcost_dom_nb_updates"] = serde_json::json!(self.nb_updates);
+        self.report_section().write_statistics(json);
+    }
+
+    /** returns the statistics of the store as a report section */
+    pub fn report_section(&self) -> ReportSection {
+        ReportSection::new("gcost_dominance")
+            .with_counter("nb_elts", self.store.len() as u64)
+            .with_counter("nb_gets", self.nb_gets as u64)
+            .with_counter("nb_pruned", self.nb_dominations as u64)
+            .with_counter("nb_updates", self.nb_updates as u64)
+    }
+
+    /** exports the content of the store (and its counters) to a JSON format */
+    pub fn save_state(&self) -> serde_json::Value where Id:Serialize, B:Serialize {
+        // entries are stored as (id, value, iter) (ids may not be valid JSON keys)
+        let entries:Vec<_> = self.store.iter().map(|(id, info)| (id, &info.val, info.iter)).collect();
+        json!({
+            "entries": entries,
+            "nb_gets": self.nb_gets,
+            "nb_dominations": self.nb_dominations,
+            "nb_updates": self.nb_updates,
+        })
     }
-}
 
+    /** restores the content of the store from a JSON format (built by save_state) */
+    pub fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error>
+    where Id:DeserializeOwned, B:DeserializeOwned {
+        let entries:Vec<(Id, B, u32)> = serde_json::from_value(json["entries"].clone())?;
+        self.store = entries.into_iter().map(|(id, val, iter)| (id, DominanceInfo { val, iter })).collect();
+        self.nb_gets = serde_json::from_value(json["nb_gets"].clone())?;
+        self.nb_dominations = serde_json::from_value(json["nb_dominations"].clone())?;
+        self.nb_updates = serde_json::from_value(json["nb_updates"].clone())?;
+        Ok(())
+    }
+}
 
 
 /// Prefix Equivalence Dominance Combinator
-#[derive(Debug)]
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, IteratorNeighborGeneration, PartialNeighborGeneration, CheckpointSpace))]
 pub struct GcostDominanceTsCombinator<Space, Id, B> {
     s: Space,
     current_iter: u32,
     store: DominanceStore<Id, B>,
 }
 
-impl<N,G,Space,Id,B> GuidedSpace<N,G> for GcostDominanceTsCombinator<Space, Id, B>
-where Space:GuidedSpace<N,G>
-{
-    fn guide(&mut self, n: &N) -> G { self.s.guide(n) }
-}
-
-impl<N,Sol,Space,Id,B> ToSolution<N,Sol> for GcostDominanceTsCombinator<Space, Id, B>
-where Space:ToSolution<N,Sol> {
-    fn solution(&mut self, node: &mut N) -> Sol { self.s.solution(node) }
-}
-
 impl<N,Space,Id,B> SearchSpace<N,B> for GcostDominanceTsCombinator<Space, Id, B>
 where Space:SearchSpace<N,B>, B:serde::Serialize+PartialOrd, Id:Hash+Eq
 {
@@ -143,28 +167,73 @@ where Space:SearchSpace<N,B>, B:serde::Serialize+PartialOrd, Id:Hash+Eq
     }
 
     fn json_statistics(&self, json:&mut serde_json::Value) {
-        self.store.export_statistics(json);
-        self.s.json_statistics(json);
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        report.push(self.store.report_section());
+        self.s.report_sections(report);
     }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("GcostDominance"));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
 }
 
-impl<N, Space, Id, B> TotalNeighborGeneration<N> for GcostDominanceTsCombinator<Space, Id, B>
+impl<N, Space, Id, B> IteratorNeighborGeneration<N> for GcostDominanceTsCombinator<Space, Id, B>
 where 
-    Space: TotalNeighborGeneration<N>+Identifiable<N, Id>+SearchSpace<N,B>,
+    Space: IteratorNeighborGeneration<N>+Identifiable<N, Id>+SearchSpace<N,B>,
     Id: Eq + Hash,
     B: PartialOrd
 {
-    fn neighbors(&mut self, node: &mut N) -> Vec<N> {
-        // check if current node is dominated, otherwise, return neighbors of underlying node
+    /// None if the node is dominated (no neighbors)
+    type Cursor = Option<Space::Cursor>;
+
+    fn neighbors_cursor(&mut self, node: &mut N) -> Self::Cursor {
+        // check if current node is dominated, otherwise, enumerate neighbors of underlying node
         let pe = self.s.id(node);
         let bound = self.s.g_cost(node);
-        if self.store.is_dominated_or_add(pe, bound, self.current_iter) { Vec::new() }
-        else { self.s.neighbors(node) }
+        let sense = self.s.sense();
+        if self.store.is_dominated_or_add(pe, bound, self.current_iter, sense) { None }
+        else { Some(self.s.neighbors_cursor(node)) }
+    }
+
+    fn next_neighbor_from(&mut self, node: &mut N, cursor: &mut Self::Cursor) -> Option<N> {
+        match cursor {
+            None => None,
+            Some(c) => self.s.next_neighbor_from(node, c),
+        }
     }
 }
 
-impl<Space, Id, B> SearchSpaceCombinator<Space> for GcostDominanceTsCombinator<Space, Id, B> {
-    fn unwrap(&self) -> &Space { &self.s }
+impl<Space, Id, B> CheckpointSpace for GcostDominanceTsCombinator<Space, Id, B>
+where
+    Space: CheckpointSpace,
+    Id: Hash+Eq+Serialize+DeserializeOwned,
+    B: PartialOrd+Serialize+DeserializeOwned,
+{
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["gcost_dominance"] = json!({
+            "current_iter": self.current_iter,
+            "store": self.store.save_state(),
+        });
+        self.s.save_state(json);
+    }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.current_iter = serde_json::from_value(json["gcost_dominance"]["current_iter"].clone())?;
+        self.store.load_state(&json["gcost_dominance"]["store"])?;
+        self.s.load_state(json)
+    }
 }
 
 
@@ -182,12 +251,6 @@ impl<Space, Id, B> GcostDominanceTsCombinator<Space, Id, B> where Id:Hash+Eq, B:
     }
 }
 
-impl<N,Space,Id,B> ParetoDominanceSpace<N> for GcostDominanceTsCombinator<Space, Id, B>
-where Space: ParetoDominanceSpace<N>
-{
-    fn dominates(&self, a:&N, b:&N) -> bool { self.s.dominates(a,b) }
-}
-
 impl<N,Space,Id,B> PartialNeighborGeneration<N> for GcostDominanceTsCombinator<Space, Id, B>
 where
     Space: PartialNeighborGeneration<N>+Identifiable<N, Id>+SearchSpace<N,B>,
@@ -201,10 +264,40 @@ where
                 // checks if c is dominated
                 let id = self.s.id(&mut c);
                 let prefix_bound = self.s.g_cost(&c);
-                if self.store.is_dominated_or_add(id, prefix_bound, self.current_iter) {
+                let sense = self.s.sense();
+                if self.store.is_dominated_or_add(id, prefix_bound, self.current_iter, sense) {
                     self.next_neighbor(node) // if node dominated, try another one
                 } else { Some(c) }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimization_dominance() {
+        let mut store:DominanceStore<u32, i64> = DominanceStore::default();
+        assert!(!store.is_dominated_or_add(0, 5, 0, ObjectiveSense::Minimize));
+        assert!(store.is_dominated_or_add(0, 6, 0, ObjectiveSense::Minimize));
+        assert!(!store.is_dominated_or_add(0, 4, 0, ObjectiveSense::Minimize));
+        // equal value: dominated within the same iteration only
+        assert!(store.is_dominated_or_add(0, 4, 0, ObjectiveSense::Minimize));
+        assert!(!store.is_dominated_or_add(0, 4, 1, ObjectiveSense::Minimize));
+    }
+
+    #[test]
+    fn maximization_dominance() {
+        let mut store:DominanceStore<u32, i64> = DominanceStore::default();
+        assert!(!store.is_dominated_or_add(0, 5, 0, ObjectiveSense::Maximize));
+        assert!(store.is_dominated_or_add(0, 4, 0, ObjectiveSense::Maximize));
+        assert!(!store.is_dominated_or_add(0, 6, 0, ObjectiveSense::Maximize));
+        assert!(store.is_dominated_or_add(0, 6, 0, ObjectiveSense::Maximize));
+        let mut json = serde_json::json!({});
+        store.export_statistics(&mut json);
+        assert_eq!(json["gcost_dominance_nb_pruned"], serde_json::json!(2));
+        assert_eq!(json["gcost_dominance_nb_updates"], serde_json::json!(1));
+    }
+}
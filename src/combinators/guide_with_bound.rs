@@ -4,19 +4,18 @@ use serde::Serialize;
 use crate::search_space::{
     SearchSpace,
     GuidedSpace,
-    TotalNeighborGeneration,
-    PartialNeighborGeneration,
-    Identifiable,
-    ParetoDominanceSpace,
-    ToSolution,
     BoundedDistanceSpace,
+    ObjectiveSense,
+    CheckpointSpace,
 };
-use crate::search_combinator::SearchSpaceCombinator;
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
 
 /**
 guide_with_bound decorator: generates a guide that incorporates the bound and the guide dynamically.
 */
-#[derive(Debug)]
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, GuidedSpace, CheckpointSpace))]
 pub struct GuideWithBoundCombinator<Space,N,B> {
     s: Space,
     avg_bound: Vec<f64>,
@@ -52,11 +51,6 @@ where
     }
 }
 
-impl<N,Sol,Space,B> ToSolution<N,Sol> for GuideWithBoundCombinator<Space,N,B>
-where Space:ToSolution<N,Sol> {
-    fn solution(&mut self, node: &mut N) -> Sol { self.s.solution(node) }
-}
-
 impl<N,Space,B> SearchSpace<N,B> for GuideWithBoundCombinator<Space,N,B>
 where Space:SearchSpace<N,B>+BoundedDistanceSpace<N>, B:serde::Serialize+PartialOrd+Clone
 {
@@ -93,21 +87,45 @@ where Space:SearchSpace<N,B>+BoundedDistanceSpace<N>, B:serde::Serialize+Partial
     }
 
     fn json_statistics(&self, json:&mut serde_json::Value) {
-        self.s.json_statistics(json);
+        SearchReport::statistics(|r| self.report(r), json);
     }
-}
 
+    fn report_sections(&self, report:&mut SearchReport) {
+        report.push(ReportSection::new("guide_with_bound"));
+        self.s.report_sections(report);
+    }
 
-impl<N, Space,B> TotalNeighborGeneration<N> for GuideWithBoundCombinator<Space,N,B>
-where 
-    Space: TotalNeighborGeneration<N>
-{
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("GuideWithBound"));
+        self.s.describe_layers(stack);
+    }
 
-    fn neighbors(&mut self, n: &mut N) -> Vec<N> { self.s.neighbors(n) }
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
 }
 
-impl<Space,N,B> SearchSpaceCombinator<Space> for GuideWithBoundCombinator<Space,N,B> {
-    fn unwrap(&self) -> &Space { &self.s }
+
+impl<Space:CheckpointSpace,N,B> CheckpointSpace for GuideWithBoundCombinator<Space,N,B> {
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["guide_with_bound"] = serde_json::json!({
+            "avg_bound": self.avg_bound,
+            "avg_guide": self.avg_guide,
+            "nb_vals": self.nb_vals,
+        });
+        self.s.save_state(json);
+    }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.avg_bound = serde_json::from_value(json["guide_with_bound"]["avg_bound"].clone())?;
+        self.avg_guide = serde_json::from_value(json["guide_with_bound"]["avg_guide"].clone())?;
+        self.nb_vals = serde_json::from_value(json["guide_with_bound"]["nb_vals"].clone())?;
+        self.s.load_state(json)
+    }
 }
 
 
@@ -125,32 +143,3 @@ impl<Space,N,B> GuideWithBoundCombinator<Space,N,B> where Space:BoundedDistanceS
         }
     }
 }
-
-impl<N, Id, Space,B> Identifiable<N, Id> for GuideWithBoundCombinator<Space,N,B>
-where
-    Space: Identifiable<N, Id>,
-{
-    fn id(&self, n: &mut N) -> Id { self.s.id(n) }
-}
-
-
-impl<N,Space,B> ParetoDominanceSpace<N> for GuideWithBoundCombinator<Space,N,B>
-where Space: ParetoDominanceSpace<N>
-{
-    fn dominates(&self, a:&N, b:&N) -> bool { self.s.dominates(a,b) }
-}
-
-
-impl<N, Space,B> PartialNeighborGeneration<N> for GuideWithBoundCombinator<Space,N,B>
-where 
-    Space: PartialNeighborGeneration<N>
-{
-    fn next_neighbor(&mut self, node: &mut N) -> Option<N> { self.s.next_neighbor(node) }
-}
-
-impl<N,Space,B> BoundedDistanceSpace<N> for GuideWithBoundCombinator<Space,N,B>
-where Space:BoundedDistanceSpace<N> {
-    fn maximum_root_distance(&self) -> usize { self.s.maximum_root_distance() }
-
-    fn distance_from_root(&self, n:&N) -> usize { self.s.distance_from_root(n) }
-}
\ No newline at end of file
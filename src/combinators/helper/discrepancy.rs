@@ -15,15 +15,15 @@ pub struct DiscrepancyNode<N> {
 
 
 /**
- * Defines the discrepancy behaviour (constant, linear, *etc.*)
+ * Defines the discrepancy behaviour (constant, linear, *etc.*) for a guide type G
  */
-pub trait DiscrepancyType {
+pub trait DiscrepancyType<G> {
     
     /**
      * given a root node and its neighbors, returns a discrepancy 
      */
-    fn compute_discrepancies<S,N,G>(&mut self, s:&mut S, n:&mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>>
-        where S:TotalNeighborGeneration<N>+GuidedSpace<N,G>, G:Ord+Into<f64>+From<f64>;
+    fn compute_discrepancies<S,N>(&mut self, s:&mut S, n:&mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>>
+        where S:TotalNeighborGeneration<N>+GuidedSpace<N,G>;
 }
 
 
@@ -33,9 +33,9 @@ pub trait DiscrepancyType {
 #[derive(Debug, Default)]
 pub struct LinearDiscrepancy {}
 
-impl DiscrepancyType for LinearDiscrepancy {
-    fn compute_discrepancies<S,N,G>(&mut self, s:&mut S, n:&mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>> 
-    where S:TotalNeighborGeneration<N>+GuidedSpace<N,G>, G:Ord {
+impl<G:Ord> DiscrepancyType<G> for LinearDiscrepancy {
+    fn compute_discrepancies<S,N>(&mut self, s:&mut S, n:&mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>> 
+    where S:TotalNeighborGeneration<N>+GuidedSpace<N,G> {
         let d:f64 = n.discrepancies;
         let mut neighbors:Vec<N> = s.neighbors(&mut n.node);
         neighbors.sort_by_key(|e| Reverse(s.guide(e)));
@@ -57,9 +57,9 @@ impl DiscrepancyType for LinearDiscrepancy {
 #[derive(Debug, Default)]
 pub struct ConstantDiscrepancy {}
 
-impl DiscrepancyType for ConstantDiscrepancy {
-    fn compute_discrepancies<S,N,G>(&mut self, s:&mut S, n:&mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>> 
-    where S:TotalNeighborGeneration<N>+GuidedSpace<N,G>, G:Ord {
+impl<G:Ord> DiscrepancyType<G> for ConstantDiscrepancy {
+    fn compute_discrepancies<S,N>(&mut self, s:&mut S, n:&mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>> 
+    where S:TotalNeighborGeneration<N>+GuidedSpace<N,G> {
         let d:f64 = n.discrepancies;
         let mut neighbors:Vec<N> = s.neighbors(&mut n.node);
         neighbors.sort_by_key(|e| Reverse(s.guide(e)));
@@ -86,9 +86,9 @@ impl DiscrepancyType for ConstantDiscrepancy {
 #[derive(Debug, Default)]
 pub struct RatioToBestDiscrepancy {}
 
-impl DiscrepancyType for RatioToBestDiscrepancy {
-    fn compute_discrepancies<S,N,G>(&mut self, s:&mut S, n:&mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>> 
-    where S:TotalNeighborGeneration<N>+GuidedSpace<N,G>, G:Ord+Into<f64>+From<f64> {
+impl<G:Ord+Into<f64>> DiscrepancyType<G> for RatioToBestDiscrepancy {
+    fn compute_discrepancies<S,N>(&mut self, s:&mut S, n:&mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>> 
+    where S:TotalNeighborGeneration<N>+GuidedSpace<N,G> {
         let d:f64 = n.discrepancies;
         let mut neighbors:Vec<N> = s.neighbors(&mut n.node);
         if neighbors.is_empty() {
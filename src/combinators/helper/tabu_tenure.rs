@@ -1,6 +1,7 @@
 use std::hash::Hash;
 
 use fxhash::FxHashSet;
+use serde::{Serialize, Deserialize};
 
 /** defines the behavior of a tabu tenure component. */
 pub trait TabuTenure<Node, Decision:Hash+Eq> {
@@ -13,7 +14,8 @@ pub trait TabuTenure<Node, Decision:Hash+Eq> {
 }
 
 /** tabu tenure that maintains every decision taken so far (no forgetting). */
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(bound(deserialize = "Decision: Deserialize<'de>+Hash+Eq"))]
 pub struct FullTabuTenure<Decision> {
     decisions:FxHashSet<Decision>,
 }
@@ -1,13 +1,17 @@
 use std::marker::PhantomData;
+use std::iter::Rev;
+use std::vec::IntoIter;
 
-use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, ToSolution};
+use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, IteratorNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, ToSolution, ObjectiveSense, BiObjectiveSpace};
 use crate::combinators::helper::discrepancy::{DiscrepancyNode, DiscrepancyType};
-use crate::search_combinator::SearchSpaceCombinator;
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
 
 /**
  * Restrics the search tree by the children having positive remaining discrepancies.
  */
-#[derive(Debug)]
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, GuidedSpace, ToSolution, IteratorNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, BiObjectiveSpace, BoundedDistanceSpace, DecisionSpace))]
 pub struct LDSCombinator<Tree, D, G, B> {
     s: Tree,
     allowed_discrepancies: f64,
@@ -23,26 +27,25 @@ where
     fn guide(&mut self, n: &DiscrepancyNode<N>) -> G { self.s.guide(&n.node) }
 }
 
-impl<N, Space, D, G, B> TotalNeighborGeneration<DiscrepancyNode<N>> for LDSCombinator<Space, D, G, B>
+impl<N, Space, D, G, B> IteratorNeighborGeneration<DiscrepancyNode<N>> for LDSCombinator<Space, D, G, B>
 where 
     Space: TotalNeighborGeneration<N>+GuidedSpace<N,G>+SearchSpace<N,B>,
-    D: DiscrepancyType,
-    G: Ord+Into<f64>+From<f64>
+    D: DiscrepancyType<G>,
 {
-    fn neighbors(&mut self, n: &mut DiscrepancyNode<N>) -> Vec<DiscrepancyNode<N>> {
+    /// children ranked by the discrepancy type (the discrepancies require all of them)
+    type Cursor = Rev<IntoIter<DiscrepancyNode<N>>>;
+
+    fn neighbors_cursor(&mut self, n: &mut DiscrepancyNode<N>) -> Self::Cursor {
         if n.discrepancies > self.allowed_discrepancies {
-            return Vec::new();
-        }
-        let mut tmp:Vec<DiscrepancyNode<N>> = self.discrepancy_type.compute_discrepancies(&mut self.s, n);
-        // extracts and filters by discrepancies <= allowed
-        let mut res = Vec::new();
-        while !tmp.is_empty() {
-            let n = tmp.pop().unwrap();
-            if n.discrepancies <= self.allowed_discrepancies {
-                res.push(n);
-            }
+            return Vec::new().into_iter().rev();
         }
-        res
+        self.discrepancy_type.compute_discrepancies(&mut self.s, n).into_iter().rev()
+    }
+
+    fn next_neighbor_from(&mut self, _n: &mut DiscrepancyNode<N>, cursor: &mut Self::Cursor) -> Option<DiscrepancyNode<N>> {
+        // filters by discrepancies <= allowed
+        let allowed = self.allowed_discrepancies;
+        cursor.find(|c| c.discrepancies <= allowed)
     }
 }
 
@@ -52,6 +55,13 @@ where
     Space: SearchSpace<N,B>+ToSolution<N,Sol>
 {
     fn solution(&mut self, n: &mut DiscrepancyNode<N>) -> Sol { self.s.solution(&mut n.node) }
+
+    fn solution_to_node(&mut self, n: &DiscrepancyNode<N>, sol: &Sol) -> Option<DiscrepancyNode<N>> {
+        self.s.solution_to_node(&n.node, sol).map(|node| DiscrepancyNode {
+            node,
+            discrepancies: n.discrepancies
+        })
+    }
 }
 
 
@@ -92,12 +102,27 @@ where Space:SearchSpace<N,B>
     }
 
     fn json_statistics(&self, json:&mut serde_json::Value) {
-        self.s.json_statistics(json);
+        SearchReport::statistics(|r| self.report(r), json);
     }
-}
 
-impl<Space, D, G, B> SearchSpaceCombinator<Space> for LDSCombinator<Space, D, G, B> {
-    fn unwrap(&self) -> &Space { &self.s }
+    fn report_sections(&self, report:&mut SearchReport) {
+        report.push(ReportSection::new("lds").with_parameter("allowed_discrepancies", self.allowed_discrepancies));
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("LDS").with_parameter("allowed_discrepancies", self.allowed_discrepancies));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    /// the discarded children may contain better solutions
+    fn is_exact(&self) -> bool { false }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
 }
 
 impl<Space, D, G, B> LDSCombinator<Space, D, G, B> {
@@ -130,6 +155,12 @@ where Space: ParetoDominanceSpace<N>
     }
 }
 
+impl<N,Space,D,G,B> BiObjectiveSpace<DiscrepancyNode<N>> for LDSCombinator<Space, D, G, B>
+where Space: BiObjectiveSpace<N>
+{
+    fn objectives(&self, n:&DiscrepancyNode<N>) -> [f64; 2] { self.s.objectives(&n.node) }
+}
+
 
 impl<N,Tree,D,G,B> PartialNeighborGeneration<DiscrepancyNode<N>> for LDSCombinator<Tree, D, G, B>
 where
@@ -0,0 +1,250 @@
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::search_space::{
+    SearchSpace,
+    ToSolution,
+    ObjectiveSense,
+    CheckpointSpace,
+    SolutionCostSpace,
+};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
+use crate::search_algorithm::{CancellationStoppingCriterion, TimeStoppingCriterion, AnyStoppingCriterion};
+use crate::local_search::local_search_space::LocalSearch;
+
+/**
+local search improvement decorator: each new-best-known solution is converted to a solution
+(ToSolution) and improved by a local search. If the search space can build a node from the
+improved solution (ToSolution::solution_to_node), this node replaces the new-best-known node.
+Otherwise, the improved solution is evaluated (SolutionCostSpace) and kept as an external
+incumbent if it is better than the previous one. Its cost is notified to the wrapped search
+space (notify_incumbent), so that the pruning layers below use it.
+Each local search call is limited by a time budget; iteration budgets are configured on the local
+search itself (for instance HillClimbing::with_max_iterations). The local search calls stop
+when the search is cancelled (see with_cancellation).
+*/
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, CheckpointSpace))]
+pub struct LocalSearchImprovementCombinator<Space, LS, Sol, B> {
+    s: Space,
+    local_search: LS,
+    /// time budget (in seconds) of each local search call (None: runs until a local optimum)
+    t_max: Option<f32>,
+    /// stops the local search calls when the search is cancelled
+    cancellation: CancellationStoppingCriterion,
+    /// best solution improved by the local search that could not be converted to a node (and its cost)
+    external_incumbent: Option<(Sol, B)>,
+    nb_calls: u64,
+    nb_improvements: u64,
+    nb_mapped: u64,
+    nb_external: u64,
+    phantom_sol: PhantomData<Sol>,
+}
+
+impl<N,Space,LS,Sol,B> SearchSpace<N,B> for LocalSearchImprovementCombinator<Space, LS, Sol, B>
+where
+    Space: SearchSpace<N,B>+ToSolution<N,Sol>+SolutionCostSpace<Sol,B>,
+    LS: LocalSearch<Sol>,
+    B: serde::Serialize+PartialOrd+Clone,
+{
+    fn initial(&mut self) -> N { self.s.initial() }
+
+    fn bound(&mut self, n: &N) -> B { self.s.bound(n) }
+
+    fn g_cost(&mut self, n: &N) -> B { self.s.g_cost(n) }
+
+    fn goal(&mut self, n: &N) -> bool { self.s.goal(n) }
+
+    fn restart(&mut self, msg: String) {
+        self.s.restart(msg);
+    }
+
+    fn handle_new_best(&mut self, n: N) -> N {
+        let mut best = self.s.handle_new_best(n);
+        // improve the solution using the local search
+        self.nb_calls += 1;
+        let mut sol = self.s.solution(&mut best);
+        let improved = match self.t_max {
+            None => self.local_search.improve(&mut sol, self.cancellation.clone()),
+            Some(t) => self.local_search.improve(&mut sol, AnyStoppingCriterion::new(
+                self.cancellation.clone(), TimeStoppingCriterion::new(t)
+            )),
+        };
+        if !improved { return best; }
+        self.nb_improvements += 1;
+        // map the improved solution back to a node if possible
+        match self.s.solution_to_node(&best, &sol) {
+            Some(n2) => {
+                self.nb_mapped += 1;
+                let (v2, v) = (self.s.bound(&n2), self.s.bound(&best));
+                if self.s.sense().is_better(&v, &v2) { best } else { n2 }
+            },
+            None => {
+                self.nb_external += 1;
+                let v = self.s.solution_cost(&sol);
+                let sense = self.s.sense();
+                if self.external_incumbent.as_ref().is_none_or(|(_, v_prev)| sense.is_better(&v, v_prev)) {
+                    self.external_incumbent = Some((sol, v.clone()));
+                    self.s.notify_incumbent(v);
+                }
+                best
+            }
+        }
+    }
+
+    fn stop_search(&mut self, msg: String) {
+        self.s.stop_search(msg);
+    }
+
+    fn display_statistics(&self) {
+        self.s.display_statistics();
+    }
+
+    fn json_statistics(&self, json:&mut serde_json::Value) {
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        let mut section = ReportSection::new("local_search_improvement")
+            .with_counter("nb_calls", self.nb_calls)
+            .with_counter("nb_improvements", self.nb_improvements)
+            .with_counter("nb_mapped", self.nb_mapped)
+            .with_counter("nb_external", self.nb_external);
+        if let Some((_, v)) = &self.external_incumbent {
+            section = section.with_value("external_incumbent", v);
+        }
+        report.push(section);
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("LocalSearchImprovement").with_parameter("t_max", self.t_max));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
+}
+
+impl<Space, LS, Sol, B> CheckpointSpace for LocalSearchImprovementCombinator<Space, LS, Sol, B>
+where Space: CheckpointSpace, Sol: Serialize+DeserializeOwned, B: Serialize+DeserializeOwned {
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["local_search_improvement"] = json!({
+            "external_incumbent": self.external_incumbent,
+            "nb_calls": self.nb_calls,
+            "nb_improvements": self.nb_improvements,
+            "nb_mapped": self.nb_mapped,
+            "nb_external": self.nb_external,
+        });
+        self.s.save_state(json);
+    }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        let state = &json["local_search_improvement"];
+        self.external_incumbent = serde_json::from_value(state["external_incumbent"].clone())?;
+        self.nb_calls = serde_json::from_value(state["nb_calls"].clone())?;
+        self.nb_improvements = serde_json::from_value(state["nb_improvements"].clone())?;
+        self.nb_mapped = serde_json::from_value(state["nb_mapped"].clone())?;
+        self.nb_external = serde_json::from_value(state["nb_external"].clone())?;
+        self.s.load_state(json)
+    }
+}
+
+impl<Space, LS, Sol, B> LocalSearchImprovementCombinator<Space, LS, Sol, B> {
+    /** builds the decorator around a search space given a local search and the time budget
+    (in seconds) of each local search call (None: the local search runs until a local optimum) */
+    pub fn new(s: Space, local_search: LS, t_max: Option<f32>) -> Self {
+        Self {
+            s,
+            local_search,
+            t_max,
+            cancellation: CancellationStoppingCriterion::new(),
+            external_incumbent: None,
+            nb_calls: 0,
+            nb_improvements: 0,
+            nb_mapped: 0,
+            nb_external: 0,
+            phantom_sol: PhantomData,
+        }
+    }
+
+    /** stops the local search calls when the search using the stopping criterion is cancelled
+    (the improvement found so far is kept) */
+    pub fn with_cancellation(mut self, cancellation:&CancellationStoppingCriterion) -> Self {
+        self.cancellation = cancellation.clone();
+        self
+    }
+
+    /** returns the best improved solution that could not be converted to a node and its cost (if any) */
+    pub fn external_incumbent(&self) -> &Option<(Sol, B)> { &self.external_incumbent }
+
+    /** returns the number of improvements found by the local search */
+    pub fn nb_improvements(&self) -> u64 { self.nb_improvements }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_algorithm::StoppingCriterion;
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::test_spaces::{BinaryTree, TieTree, leaf};
+
+    /// halves the solution (cost) while it is positive
+    #[derive(Debug)]
+    struct Halving;
+
+    impl LocalSearch<i64> for Halving {
+        fn improve<SC:StoppingCriterion>(&mut self, sol:&mut i64, _stopping_criterion:SC) -> bool {
+            if *sol == 0 { return false; }
+            *sol /= 2;
+            true
+        }
+    }
+
+    #[test]
+    fn improved_and_mapped() {
+        let mut space = LocalSearchImprovementCombinator::new(BinaryTree::new(ObjectiveSense::Minimize), Halving, None);
+        // the improved solution replaces the new-best-known node
+        assert_eq!(space.handle_new_best(leaf(3)).cost, 1);
+        // a solution that is not improved is kept
+        assert_eq!(space.handle_new_best(leaf(0)).cost, 0);
+        assert_eq!(space.nb_improvements(), 1);
+        assert!(space.external_incumbent().is_none());
+        let mut json = serde_json::json!({});
+        space.json_statistics(&mut json);
+        assert_eq!(json["local_search_improvement_nb_calls"], serde_json::json!(2));
+        assert_eq!(json["local_search_improvement_nb_mapped"], serde_json::json!(1));
+        assert_eq!(json["local_search_improvement_nb_external"], serde_json::json!(0));
+    }
+
+    #[test]
+    fn external_incumbent() {
+        // the tie tree cannot build a node from a solution
+        let mut space = LocalSearchImprovementCombinator::new(PruningCombinator::new(TieTree { depth: 3 }), Halving, Some(1.));
+        assert_eq!(space.handle_new_best(leaf(5)).cost, 5);
+        assert_eq!(*space.external_incumbent(), Some((2, 2)));
+        assert_eq!(space.handle_new_best(leaf(2)).cost, 2);
+        assert_eq!(*space.external_incumbent(), Some((1, 1)));
+        // a worse improved solution does not replace the external incumbent
+        assert_eq!(space.handle_new_best(leaf(8)).cost, 8);
+        assert_eq!(*space.external_incumbent(), Some((1, 1)));
+        assert_eq!(space.nb_improvements(), 3);
+        let mut json = serde_json::json!({});
+        space.json_statistics(&mut json);
+        assert_eq!(json["local_search_improvement_nb_mapped"], serde_json::json!(0));
+        assert_eq!(json["local_search_improvement_nb_external"], serde_json::json!(3));
+        assert_eq!(json["local_search_improvement_external_incumbent"], serde_json::json!(1));
+        // the pruning layer below is notified of the external incumbent
+        assert_eq!(json["pruning_primal_bound"], serde_json::json!(1));
+    }
+}
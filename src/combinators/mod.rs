@@ -26,4 +26,19 @@ the distance from the root.
 pub mod guide_with_bound;
 
 /** tabu combinator (allows to forbid some moves depending on the search history. */
-pub mod tabu;
\ No newline at end of file
+pub mod tabu;
+
+/** pheromone combinator (mixes the guide with a pheromone table, used by Beam-ACO) */
+pub mod pheromone;
+
+/** improves new-best-known solutions using a local search */
+pub mod local_search_improvement;
+
+/** feeds an elite pool with new-best-known solutions (path relinking between restarts) */
+pub mod elite_pool;
+
+/** keeps the k best distinct goals found by the search */
+pub mod solution_pool;
+
+/** adapters providing an iterator neighbor generation to total or partial neighbor generations */
+pub mod neighbor_generation;
@@ -0,0 +1,108 @@
+use std::vec::IntoIter;
+
+use crate::search_space::{TotalNeighborGeneration, PartialNeighborGeneration, IteratorNeighborGeneration};
+use crate::search_combinator::SearchSpaceCombinator;
+
+/**
+Provides an iterator neighbor generation to a search space that generates all the children of a
+node at once (TotalNeighborGeneration). The children are generated when the enumeration starts,
+then given one at a time to the combinators above (pruning, tabu, dominance, *etc.*).
+The other traits are forwarded.
+*/
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(IteratorNeighborGeneration))]
+pub struct TotalAdapter<Space> {
+    s: Space,
+}
+
+impl<N, Space:TotalNeighborGeneration<N>> IteratorNeighborGeneration<N> for TotalAdapter<Space> {
+    /// children generated by the search space
+    type Cursor = IntoIter<N>;
+
+    fn neighbors_cursor(&mut self, node: &mut N) -> Self::Cursor { self.s.neighbors(node).into_iter() }
+
+    fn next_neighbor_from(&mut self, _node: &mut N, cursor: &mut Self::Cursor) -> Option<N> { cursor.next() }
+}
+
+impl<Space> TotalAdapter<Space> {
+    /** builds the adapter around a search space */
+    pub fn new(s: Space) -> Self { Self { s } }
+
+    /** unwraps itself */
+    pub fn unwrap(&self) -> &Space { &self.s }
+}
+
+
+/**
+Provides an iterator neighbor generation to a search space that generates the children of a
+node one at a time (PartialNeighborGeneration). The state of the enumeration is kept in the
+node, so the cursor is empty.
+The other traits are forwarded.
+*/
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(IteratorNeighborGeneration))]
+pub struct PartialAdapter<Space> {
+    s: Space,
+}
+
+impl<N, Space:PartialNeighborGeneration<N>> IteratorNeighborGeneration<N> for PartialAdapter<Space> {
+    /// the state of the enumeration is kept in the node
+    type Cursor = ();
+
+    fn neighbors_cursor(&mut self, _node: &mut N) -> Self::Cursor {}
+
+    fn next_neighbor_from(&mut self, node: &mut N, _cursor: &mut Self::Cursor) -> Option<N> { self.s.next_neighbor(node) }
+}
+
+impl<Space> PartialAdapter<Space> {
+    /** builds the adapter around a search space */
+    pub fn new(s: Space) -> Self { Self { s } }
+
+    /** unwraps itself */
+    pub fn unwrap(&self) -> &Space { &self.s }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion};
+    use crate::search_manager::SearchStatus;
+    use crate::search_space::{SearchSpace, ObjectiveSense};
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::combinators::stats::StatTsCombinator;
+    use crate::tree_search::beam_search::BeamSearch;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+    use crate::test_spaces::{Node, BinaryTree, TieTree};
+
+    #[test]
+    fn total_generation() {
+        // the tie tree only implements TotalNeighborGeneration
+        let space = Rc::new(RefCell::new(PruningCombinator::new(StatTsCombinator::new(TotalAdapter::new(TieTree { depth: 4 })))));
+        let mut algo:BeamSearch<Node, i64, i32, _> = BeamSearch::new(space.clone(), 100);
+        algo.run(NeverStoppingCriterion::default());
+        assert_eq!(algo.status(), SearchStatus::Optimal);
+        assert_eq!(*algo.get_manager().best_val(), Some(1));
+        let mut json = serde_json::json!({});
+        space.borrow().json_statistics(&mut json);
+        assert_eq!(json["nb_generated"], serde_json::json!(3 + 9 + 27 + 81));
+        assert_eq!(space.borrow().describe().names(), vec!["Pruning", "Stats", "Problem"]);
+    }
+
+    #[test]
+    fn partial_generation() {
+        let mut adapter = PartialAdapter::new(BinaryTree::new(ObjectiveSense::Minimize));
+        let mut root = adapter.initial();
+        let costs:Vec<i64> = adapter.neighbors_iter(&mut root).map(|c| c.cost).collect();
+        assert_eq!(costs, vec![0, 1]);
+        // the enumeration state is kept in the node
+        assert_eq!(root.nb_children_generated, 2);
+        let space = Rc::new(RefCell::new(PruningCombinator::new(PartialAdapter::new(BinaryTree::new(ObjectiveSense::Minimize)))));
+        let mut algo:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(space);
+        algo.run(NeverStoppingCriterion::default());
+        assert_eq!(algo.status(), SearchStatus::Optimal);
+        assert_eq!(*algo.get_manager().best_val(), Some(0));
+    }
+}
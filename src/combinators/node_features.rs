@@ -3,12 +3,16 @@ use std::marker::PhantomData;
 use crate::search_space::{
     SearchSpace,
     GuidedSpace,
-    TotalNeighborGeneration,
+    IteratorNeighborGeneration,
+    PartialNeighborGeneration,
     Identifiable,
     ParetoDominanceSpace,
-    ToSolution
+    ToSolution,
+    ObjectiveSense,
+    BiObjectiveSpace,
 };
-use crate::search_combinator::SearchSpaceCombinator;
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
 
 /// adds a depth field to a node
 #[derive(Debug,Clone)]
@@ -20,7 +24,8 @@ pub struct DepthNode<N> {
 }
 
 /// pruning decorator: stores the best known solution and counts the number of prunings for statistics.
-#[derive(Debug)]
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, GuidedSpace, ToSolution, IteratorNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, BiObjectiveSpace, BoundedDistanceSpace, DecisionSpace))]
 pub struct NodeFeaturesCombinator<Space,B,G> {
     s: Space,
     /// vector of (bound, guide, depth, bool)
@@ -38,6 +43,10 @@ where Space:GuidedSpace<N,G>
 impl<N,Sol,Space,B,G> ToSolution<DepthNode<N>,Sol> for NodeFeaturesCombinator<Space,B,G>
 where Space:ToSolution<N,Sol> {
     fn solution(&mut self, node: &mut DepthNode<N>) -> Sol { self.s.solution(&mut node.node) }
+
+    fn solution_to_node(&mut self, n: &DepthNode<N>, sol: &Sol) -> Option<DepthNode<N>> {
+        self.s.solution_to_node(&n.node, sol).map(|node| DepthNode { node, depth: n.depth })
+    }
 }
 
 impl<N,Space,B,G> SearchSpace<DepthNode<N>,B> for NodeFeaturesCombinator<Space,B,G>
@@ -62,35 +71,64 @@ where Space:SearchSpace<N,B>, B:serde::Serialize+PartialOrd+Clone
 
     fn display_statistics(&self) { self.s.display_statistics(); }
 
-    fn json_statistics(&self, json:&mut serde_json::Value) { self.s.json_statistics(json); }
+    fn json_statistics(&self, json:&mut serde_json::Value) {
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        report.push(ReportSection::new("node_features").with_counter("nb_nodes", self.node_information.len() as u64));
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("NodeFeatures"));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
 }
 
 
-impl<N, Space, B, G> TotalNeighborGeneration<DepthNode<N>> for NodeFeaturesCombinator<Space,B,G>
+impl<N, Space, B, G> IteratorNeighborGeneration<DepthNode<N>> for NodeFeaturesCombinator<Space,B,G>
 where 
-    Space: TotalNeighborGeneration<N> + SearchSpace<N,B> + GuidedSpace<N,G>,
+    Space: IteratorNeighborGeneration<N> + SearchSpace<N,B> + GuidedSpace<N,G>,
     B: Into<f64>,
     G: Into<f64>,
 {
+    type Cursor = Space::Cursor;
+
+    fn neighbors_cursor(&mut self, n: &mut DepthNode<N>) -> Self::Cursor {
+        self.s.neighbors_cursor(&mut n.node)
+    }
 
-    fn neighbors(&mut self, n: &mut DepthNode<N>) -> Vec<DepthNode<N>> {
-        self.s.neighbors(&mut n.node).into_iter().map(|child| {
-            self.node_information.push((
-                self.s.bound(&child).into(),
-                self.s.guide(&child).into(),
-                n.depth+1,
-                self.s.goal(&child)
-            ));
+    fn next_neighbor_from(&mut self, n: &mut DepthNode<N>, cursor: &mut Self::Cursor) -> Option<DepthNode<N>> {
+        self.s.next_neighbor_from(&mut n.node, cursor).map(|child| {
+            self.record(&child, n.depth+1);
             DepthNode { node:child, depth:n.depth+1 }
-        }).collect()
+        })
     }
 }
 
-impl<Space,B,G> SearchSpaceCombinator<Space> for NodeFeaturesCombinator<Space,B,G> {
-    fn unwrap(&self) -> &Space { &self.s }
+impl<N, Space, B, G> PartialNeighborGeneration<DepthNode<N>> for NodeFeaturesCombinator<Space,B,G>
+where 
+    Space: PartialNeighborGeneration<N> + SearchSpace<N,B> + GuidedSpace<N,G>,
+    B: Into<f64>,
+    G: Into<f64>,
+{
+    fn next_neighbor(&mut self, n: &mut DepthNode<N>) -> Option<DepthNode<N>> {
+        self.s.next_neighbor(&mut n.node).map(|child| {
+            self.record(&child, n.depth+1);
+            DepthNode { node:child, depth:n.depth+1 }
+        })
+    }
 }
 
-
 impl<Space,B,G> NodeFeaturesCombinator<Space,B,G> {
     /// builds the decorator around a search space
     pub fn new(s: Space) -> Self {
@@ -101,17 +139,34 @@ impl<Space,B,G> NodeFeaturesCombinator<Space,B,G> {
             phantom_g: PhantomData::default()
         }
     }
+
+    /// records the features of a generated node
+    fn record<N>(&mut self, child:&N, depth:usize)
+    where Space: SearchSpace<N,B> + GuidedSpace<N,G>, B: Into<f64>, G: Into<f64> {
+        self.node_information.push((
+            self.s.bound(child).into(),
+            self.s.guide(child).into(),
+            depth,
+            self.s.goal(child)
+        ));
+    }
 }
 
-impl<N, Id, Space,B,G> Identifiable<N, Id> for NodeFeaturesCombinator<Space,B,G>
+impl<N, Id, Space,B,G> Identifiable<DepthNode<N>, Id> for NodeFeaturesCombinator<Space,B,G>
 where Space: Identifiable<N, Id>,
 {
-    fn id(&self, n: &mut N) -> Id { self.s.id(n) }
+    fn id(&self, n: &mut DepthNode<N>) -> Id { self.s.id(&mut n.node) }
 }
 
 
-impl<N,Space,B,G> ParetoDominanceSpace<N> for NodeFeaturesCombinator<Space,B,G>
+impl<N,Space,B,G> ParetoDominanceSpace<DepthNode<N>> for NodeFeaturesCombinator<Space,B,G>
 where Space: ParetoDominanceSpace<N>
 {
-    fn dominates(&self, a:&N, b:&N) -> bool { self.s.dominates(a,b) }
-}
\ No newline at end of file
+    fn dominates(&self, a:&DepthNode<N>, b:&DepthNode<N>) -> bool { self.s.dominates(&a.node,&b.node) }
+}
+
+impl<N,Space,B,G> BiObjectiveSpace<DepthNode<N>> for NodeFeaturesCombinator<Space,B,G>
+where Space: BiObjectiveSpace<N>
+{
+    fn objectives(&self, n:&DepthNode<N>) -> [f64; 2] { self.s.objectives(&n.node) }
+}
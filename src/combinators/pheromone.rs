@@ -0,0 +1,290 @@
+use std::marker::PhantomData;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use ordered_float::OrderedFloat;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::search_space::{
+    SearchSpace,
+    GuidedSpace,
+    IteratorNeighborGeneration,
+    PartialNeighborGeneration,
+    Identifiable,
+    ParetoDominanceSpace,
+    ToSolution,
+    DecisionSpace,
+    ObjectiveSense,
+    BiObjectiveSpace,
+    CheckpointSpace,
+};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::objective::GuideValue;
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
+use crate::data_structures::decision_tree::DecisionTree;
+use crate::tree_search::helper::pheromone::{PheromoneTable, positive_guide};
+
+/// adds the path of decisions (from the root) to a node
+#[derive(Debug, Clone)]
+pub struct PheromoneNode<N, D> {
+    /// underlying node
+    pub node: N,
+    /// decisions taken from the root (None if no decision was taken yet)
+    pub path: Option<Rc<DecisionTree<D>>>,
+}
+
+impl<N, D:Clone> PheromoneNode<N, D> {
+    /** returns the decisions taken from the root to the node */
+    pub fn decisions(&self) -> Vec<D> {
+        match &self.path {
+            None => Vec::new(),
+            Some(p) => DecisionTree::decisions_from_root(p),
+        }
+    }
+}
+
+/**
+Pheromone combinator (used by Beam-ACO). The guide of a node mixes the guide of the underlying
+space and the pheromone of its decision: guide^beta / pheromone^alpha (the smaller, the better).
+The guides are first mapped to positive values, keeping their order (see positive_guide): a null
+guide (for instance a g-cost guide at the root) is mapped to 1/2, so that the pheromone can still
+rank such nodes after nodes of larger guides.
+It records the best goal reached since the last restart. When the search restarts (or stops),
+the pheromone evaporates and the decisions of this goal reinforce it.
+*/
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, GuidedSpace, ToSolution, IteratorNeighborGeneration, PartialNeighborGeneration, Identifiable, ParetoDominanceSpace, BiObjectiveSpace, BoundedDistanceSpace, DecisionSpace, CheckpointSpace))]
+pub struct PheromoneCombinator<Space, B, D, G> {
+    s: Space,
+    pheromones: PheromoneTable<D>,
+    alpha: f64,
+    beta: f64,
+    /// best goal (value and decisions) reached since the last pheromone update
+    iter_best: Option<(B, Vec<D>)>,
+    nb_updates: u64,
+    phantom_g: PhantomData<G>,
+}
+
+impl<N,G,Space,B,D> GuidedSpace<PheromoneNode<N,D>,OrderedFloat<f64>> for PheromoneCombinator<Space,B,D,G>
+where
+    Space: GuidedSpace<N,G>+DecisionSpace<N,D>,
+    G: GuideValue,
+    D: Hash+Eq,
+{
+    fn guide(&mut self, n: &PheromoneNode<N,D>) -> OrderedFloat<f64> {
+        let guide = positive_guide(self.s.guide(&n.node).to_f64());
+        let tau = match self.s.decision(&n.node) {
+            None => self.pheromones.tau_max(),
+            Some(d) => self.pheromones.get(&d),
+        };
+        OrderedFloat(guide.powf(self.beta) / tau.powf(self.alpha))
+    }
+}
+
+impl<N,Sol,Space,B,D,G> ToSolution<PheromoneNode<N,D>,Sol> for PheromoneCombinator<Space,B,D,G>
+where Space:ToSolution<N,Sol> {
+    fn solution(&mut self, node: &mut PheromoneNode<N,D>) -> Sol { self.s.solution(&mut node.node) }
+
+    fn solution_to_node(&mut self, n: &PheromoneNode<N,D>, sol: &Sol) -> Option<PheromoneNode<N,D>> {
+        self.s.solution_to_node(&n.node, sol).map(|node| PheromoneNode { node, path: n.path.clone() })
+    }
+}
+
+impl<N,Space,B,D,G> SearchSpace<PheromoneNode<N,D>,B> for PheromoneCombinator<Space,B,D,G>
+where
+    Space: SearchSpace<N,B>,
+    B: PartialOrd+Copy,
+    D: Hash+Eq+Clone,
+{
+    fn initial(&mut self) -> PheromoneNode<N,D> {
+        PheromoneNode { node: self.s.initial(), path: None }
+    }
+
+    fn bound(&mut self, n: &PheromoneNode<N,D>) -> B { self.s.bound(&n.node) }
+
+    fn g_cost(&mut self, n: &PheromoneNode<N,D>) -> B { self.s.g_cost(&n.node) }
+
+    fn goal(&mut self, n: &PheromoneNode<N,D>) -> bool {
+        let res = self.s.goal(&n.node);
+        if res {
+            let v = self.s.bound(&n.node);
+            let is_iter_best = match &self.iter_best {
+                None => true,
+                Some((best_v, _)) => self.s.sense().is_better(&v, best_v),
+            };
+            if is_iter_best {
+                self.iter_best = Some((v, n.decisions()));
+            }
+        }
+        res
+    }
+
+    fn restart(&mut self, msg: String) {
+        self.update_pheromones();
+        self.s.restart(msg);
+    }
+
+    fn handle_new_best(&mut self, n: PheromoneNode<N,D>) -> PheromoneNode<N,D> {
+        PheromoneNode { node: self.s.handle_new_best(n.node), path: n.path }
+    }
+
+    fn stop_search(&mut self, msg: String) {
+        self.update_pheromones();
+        self.s.stop_search(msg);
+    }
+
+    fn display_statistics(&self) {
+        self.s.display_statistics();
+    }
+
+    fn json_statistics(&self, json:&mut serde_json::Value) {
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        report.push(ReportSection::new("pheromone")
+            .with_counter("nb_updates", self.nb_updates)
+            .with_counter("nb_decisions", self.pheromones.len() as u64)
+        );
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("Pheromone")
+            .with_parameter("alpha", self.alpha)
+            .with_parameter("beta", self.beta));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
+}
+
+impl<N, Space, B, D, G> IteratorNeighborGeneration<PheromoneNode<N,D>> for PheromoneCombinator<Space,B,D,G>
+where
+    Space: IteratorNeighborGeneration<N>+DecisionSpace<N,D>,
+    D: Hash+Eq+Clone,
+{
+    type Cursor = Space::Cursor;
+
+    fn neighbors_cursor(&mut self, n: &mut PheromoneNode<N,D>) -> Self::Cursor {
+        self.s.neighbors_cursor(&mut n.node)
+    }
+
+    fn next_neighbor_from(&mut self, n: &mut PheromoneNode<N,D>, cursor: &mut Self::Cursor) -> Option<PheromoneNode<N,D>> {
+        self.s.next_neighbor_from(&mut n.node, cursor).map(|c| {
+            let path = self.child_path(&n.path, &c);
+            PheromoneNode { node: c, path }
+        })
+    }
+}
+
+impl<N, Space, B, D, G> PartialNeighborGeneration<PheromoneNode<N,D>> for PheromoneCombinator<Space,B,D,G>
+where
+    Space: PartialNeighborGeneration<N>+DecisionSpace<N,D>,
+    D: Hash+Eq+Clone,
+{
+    fn next_neighbor(&mut self, n: &mut PheromoneNode<N,D>) -> Option<PheromoneNode<N,D>> {
+        self.s.next_neighbor(&mut n.node).map(|c| {
+            let path = self.child_path(&n.path, &c);
+            PheromoneNode { node: c, path }
+        })
+    }
+}
+
+impl<Space,B,D,G> CheckpointSpace for PheromoneCombinator<Space,B,D,G>
+where
+    Space: CheckpointSpace,
+    B: Serialize+DeserializeOwned,
+    D: Hash+Eq+Serialize+DeserializeOwned,
+{
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["pheromone"] = json!({
+            "table": self.pheromones.save_state(),
+            "iter_best": self.iter_best,
+            "nb_updates": self.nb_updates,
+        });
+        self.s.save_state(json);
+    }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.pheromones.load_state(&json["pheromone"]["table"])?;
+        self.iter_best = serde_json::from_value(json["pheromone"]["iter_best"].clone())?;
+        self.nb_updates = serde_json::from_value(json["pheromone"]["nb_updates"].clone())?;
+        self.s.load_state(json)
+    }
+}
+
+impl<Space,B,D,G> PheromoneCombinator<Space,B,D,G> where D:Hash+Eq+Clone {
+    /** builds the decorator around a search space, given the importance of the pheromone (alpha),
+    the importance of the guide (beta) and the pheromone table */
+    pub fn new(s: Space, alpha:f64, beta:f64, pheromones:PheromoneTable<D>) -> Self {
+        Self {
+            s,
+            pheromones,
+            alpha,
+            beta,
+            iter_best: None,
+            nb_updates: 0,
+            phantom_g: PhantomData,
+        }
+    }
+
+    /** returns the pheromone table */
+    pub fn pheromones(&self) -> &PheromoneTable<D> { &self.pheromones }
+
+    /** evaporates the pheromone and reinforces the decisions of the best goal since the last update */
+    fn update_pheromones(&mut self) {
+        if let Some((_, decisions)) = self.iter_best.take() {
+            self.pheromones.evaporate();
+            let tau_max = self.pheromones.tau_max();
+            self.pheromones.reinforce(&decisions, tau_max);
+            self.nb_updates += 1;
+        }
+    }
+
+    /** path of a child given the path of its parent */
+    fn child_path<N>(&self, parent:&Option<Rc<DecisionTree<D>>>, c:&N) -> Option<Rc<DecisionTree<D>>>
+    where Space:DecisionSpace<N,D> {
+        match (self.s.decision(c), parent) {
+            (None, _) => parent.clone(),
+            (Some(d), None) => Some(DecisionTree::new(d)),
+            (Some(d), Some(p)) => Some(DecisionTree::add_child(p, d)),
+        }
+    }
+}
+
+impl<N, Id, Space, B, D, G> Identifiable<PheromoneNode<N,D>, Id> for PheromoneCombinator<Space,B,D,G>
+where Space: Identifiable<N, Id>,
+{
+    fn id(&self, n: &mut PheromoneNode<N,D>) -> Id { self.s.id(&mut n.node) }
+}
+
+impl<N,Space,B,D,G> ParetoDominanceSpace<PheromoneNode<N,D>> for PheromoneCombinator<Space,B,D,G>
+where Space: ParetoDominanceSpace<N>
+{
+    fn dominates(&self, a:&PheromoneNode<N,D>, b:&PheromoneNode<N,D>) -> bool {
+        self.s.dominates(&a.node, &b.node)
+    }
+}
+
+impl<N,Space,B,D,G> BiObjectiveSpace<PheromoneNode<N,D>> for PheromoneCombinator<Space,B,D,G>
+where Space: BiObjectiveSpace<N>
+{
+    fn objectives(&self, n:&PheromoneNode<N,D>) -> [f64; 2] { self.s.objectives(&n.node) }
+}
+
+impl<N,Space,B,D,G> DecisionSpace<PheromoneNode<N,D>,D> for PheromoneCombinator<Space,B,D,G>
+where Space: DecisionSpace<N,D>
+{
+    fn decision(&self, n:&PheromoneNode<N,D>) -> Option<D> { self.s.decision(&n.node) }
+
+    fn aspiration_criterion(&self, n:&PheromoneNode<N,D>) -> bool { self.s.aspiration_criterion(&n.node) }
+}
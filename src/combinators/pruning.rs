@@ -1,35 +1,36 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json::json;
 
 use crate::search_space::{
     SearchSpace,
-    GuidedSpace,
-    TotalNeighborGeneration,
+    IteratorNeighborGeneration,
     PartialNeighborGeneration,
-    Identifiable,
-    ParetoDominanceSpace,
-    ToSolution
+    ObjectiveSense,
+    CheckpointSpace,
 };
-use crate::search_combinator::SearchSpaceCombinator;
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
+use crate::objective::Objective;
+
+/// returns true iff a bound is within a relative gap (tolerance) of the best known value
+type ToleranceCheck<B> = fn(&B, &B, ObjectiveSense, f64) -> bool;
 
 /**
 pruning decorator: stores the best known solution and counts the number of prunings for statistics.
+Nodes whose bound does not improve the best known solution (given the objective sense) are pruned.
+With a tolerance (with_tolerance), the nodes whose bound is within a relative gap of the best known
+solution are kept as well (for instance to collect near-optimal solutions in a solution pool).
 */
-#[derive(Debug)]
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, IteratorNeighborGeneration, PartialNeighborGeneration, CheckpointSpace))]
 pub struct PruningCombinator<Space, B> {
     s: Space,
     best_val: Option<B>,
-    nb_prunings: u64
-}
-
-impl<N,G,Space,B> GuidedSpace<N,G> for PruningCombinator<Space, B> 
-where Space:GuidedSpace<N,G>
-{
-    fn guide(&mut self, n: &N) -> G { self.s.guide(n) }
-}
-
-impl<N,Sol,Space,B> ToSolution<N,Sol> for PruningCombinator<Space, B>
-where Space:ToSolution<N,Sol> {
-    fn solution(&mut self, node: &mut N) -> Sol { self.s.solution(node) }
+    nb_prunings: u64,
+    /// relative gap to the best known solution under which nodes are not pruned
+    tolerance: f64,
+    tolerance_check: Option<ToleranceCheck<B>>,
 }
 
 impl<N,Space,B> SearchSpace<N,B> for PruningCombinator<Space,B>
@@ -48,7 +49,7 @@ where Space:SearchSpace<N,B>, B:serde::Serialize+PartialOrd+Clone
             match self.best_val.clone() {  // check if the best-known should be updated
                 None => { self.best_val = Some(eval_n) }
                 Some(v) => {
-                    if eval_n < v {
+                    if self.s.sense().is_better(&eval_n, &v) {
                         self.best_val = Some(eval_n);
                     } 
                 }
@@ -76,69 +77,112 @@ where Space:SearchSpace<N,B>, B:serde::Serialize+PartialOrd+Clone
     }
 
     fn json_statistics(&self, json:&mut serde_json::Value) {
-        json["nb_pruned"] = serde_json::json!(self.nb_prunings);
-        match &self.best_val {
-            None => {},
-            Some(v) => { json["primal_bound"] = json!(v); }
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        let mut section = ReportSection::new("pruning").with_counter("nb_pruned", self.nb_prunings);
+        if let Some(v) = &self.best_val {
+            section = section.with_value("primal_bound", v);
+        }
+        report.push(section);
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        let mut layer = LayerDescription::new("Pruning");
+        if self.tolerance_check.is_some() {
+            layer = layer.with_parameter("tolerance", self.tolerance);
         }
-        self.s.json_statistics(json);
+        stack.push(layer);
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) {
+        if self.best_val.as_ref().is_none_or(|v| self.s.sense().is_better(&value, v)) {
+            self.best_val = Some(value.clone());
+        }
+        self.s.notify_incumbent(value);
     }
 }
 
 
-impl<N, Space, B> TotalNeighborGeneration<N> for PruningCombinator<Space,B>
+impl<N, Space, B> IteratorNeighborGeneration<N> for PruningCombinator<Space,B>
 where 
-    Space: TotalNeighborGeneration<N> + SearchSpace<N, B>,
+    Space: IteratorNeighborGeneration<N> + SearchSpace<N, B>,
     B: PartialOrd+Copy,
 {
-
-    fn neighbors(&mut self, n: &mut N) -> Vec<N> {
-        match self.best_val {
-            None => { self.s.neighbors(n) }
-            Some(best_v) => {
-                let mut res = Vec::new();
-                let mut children = self.s.neighbors(n);
-                let children_size = children.len();
-                while !children.is_empty() {
-                    let child = children.pop().unwrap();
-                    if self.s.bound(&child) < best_v {
-                        res.push(child);
-                    }
+    type Cursor = Space::Cursor;
+
+    fn neighbors_cursor(&mut self, n: &mut N) -> Self::Cursor { self.s.neighbors_cursor(n) }
+
+    fn next_neighbor_from(&mut self, n: &mut N, cursor: &mut Self::Cursor) -> Option<N> {
+        // skips the children that cannot improve the best known solution
+        while let Some(child) = self.s.next_neighbor_from(n, cursor) {
+            match self.best_val {
+                None => { return Some(child); }
+                Some(best_v) => {
+                    let bound = self.s.bound(&child);
+                    if self.keeps(&bound, &best_v, self.s.sense()) { return Some(child); }
+                    self.nb_prunings += 1;
                 }
-                self.nb_prunings += children_size as u64 - res.len() as u64;
-                res
             }
         }
+        None
     }
 }
 
-impl<Space, B> SearchSpaceCombinator<Space> for PruningCombinator<Space, B> {
-    fn unwrap(&self) -> &Space { &self.s }
+impl<Space:CheckpointSpace, B:Serialize+DeserializeOwned> CheckpointSpace for PruningCombinator<Space, B> {
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["pruning"] = json!({
+            "best_val": self.best_val,
+            "nb_prunings": self.nb_prunings,
+        });
+        self.s.save_state(json);
+    }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.best_val = serde_json::from_value(json["pruning"]["best_val"].clone())?;
+        self.nb_prunings = serde_json::from_value(json["pruning"]["nb_prunings"].clone())?;
+        self.s.load_state(json)
+    }
 }
 
 
 impl<Space, B> PruningCombinator<Space, B> {
     /** builds the decorator around a search space */
     pub fn new(s: Space) -> Self {
-        Self {s, best_val: None, nb_prunings: 0}
+        Self {s, best_val: None, nb_prunings: 0, tolerance: 0., tolerance_check: None}
     }
-}
-
-impl<N, B, Id, Space> Identifiable<N, Id> for PruningCombinator<Space, B>
-where
-    Space: Identifiable<N, Id>,
-{
-    fn id(&self, n: &mut N) -> Id { self.s.id(n) }
-}
 
+    /** keeps the nodes whose bound is worse than the best known solution by at most a relative
+    gap (tolerance, for instance 0.05 for 5%). The bounds equal to the best known solution are
+    kept. If the best known value is 0, the relative gap is not defined: only these ties are kept */
+    pub fn with_tolerance(mut self, tolerance:f64) -> Self where B:Objective {
+        self.tolerance = tolerance;
+        self.tolerance_check = Some(|best, bound, sense, tol| {
+            // the gap is negative when the bound is worse than the best known value
+            let gap = best.gap(bound, sense);
+            bound == best || (gap < 0. && -gap <= tol)
+        });
+        self
+    }
 
-impl<N,Space,B> ParetoDominanceSpace<N> for PruningCombinator<Space, B>
-where Space: ParetoDominanceSpace<N>
-{
-    fn dominates(&self, a:&N, b:&N) -> bool { self.s.dominates(a,b) }
+    /** returns true iff a node of the given bound is not pruned */
+    fn keeps(&self, bound:&B, best:&B, sense:ObjectiveSense) -> bool where B:PartialOrd {
+        sense.is_better(bound, best) || match self.tolerance_check {
+            None => false,
+            Some(check) => check(best, bound, sense, self.tolerance),
+        }
+    }
 }
 
-
 impl<N, Space, B> PartialNeighborGeneration<N> for PruningCombinator<Space,B>
 where 
     Space: PartialNeighborGeneration<N>+SearchSpace<N,B>,
@@ -151,7 +195,8 @@ where
                 match self.best_val {
                     None => { Some(c) }
                     Some(best_v) => {
-                        if self.s.bound(&c) < best_v { Some(c) }
+                        let bound = self.s.bound(&c);
+                        if self.keeps(&bound, &best_v, self.s.sense()) { Some(c) }
                         else {
                             self.nb_prunings += 1;
                             self.next_neighbor(node)
@@ -162,4 +207,28 @@ where
             }
         }
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_spaces::BinaryTree;
+
+    #[test]
+    fn tolerance() {
+        let strict:PruningCombinator<BinaryTree, i64> = PruningCombinator::new(BinaryTree::new(ObjectiveSense::Minimize));
+        assert!(strict.keeps(&99, &100, ObjectiveSense::Minimize));
+        assert!(!strict.keeps(&100, &100, ObjectiveSense::Minimize));
+        let tolerant = PruningCombinator::new(BinaryTree::new(ObjectiveSense::Minimize)).with_tolerance(0.1);
+        // the bounds worse than the best known value by more than 10% are pruned
+        assert!(tolerant.keeps(&100, &100, ObjectiveSense::Minimize));
+        assert!(tolerant.keeps(&105, &100, ObjectiveSense::Minimize));
+        assert!(!tolerant.keeps(&150, &100, ObjectiveSense::Minimize));
+        assert!(tolerant.keeps(&95, &100, ObjectiveSense::Maximize));
+        assert!(!tolerant.keeps(&10, &100, ObjectiveSense::Maximize));
+        // a null best known value only keeps the ties
+        assert!(tolerant.keeps(&0, &0, ObjectiveSense::Minimize));
+        assert!(!tolerant.keeps(&1, &0, ObjectiveSense::Minimize));
+    }
+}
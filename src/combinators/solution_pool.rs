@@ -0,0 +1,256 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::search_space::{
+    SearchSpace,
+    Identifiable,
+    ToSolution,
+    ObjectiveSense,
+    CheckpointSpace,
+};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
+use crate::data_structures::solution_pool::{SolutionPool, PooledSolution};
+
+/**
+Solution pool decorator: keeps the k best distinct goals reached by the search (not only the
+new-best-known ones), with the time they were found.
+If built with with_identifiable, goals with the same id (Identifiable) are duplicates.
+Otherwise, a goal reached twice by the search may appear twice in the pool.
+The pool only sees the goals reached by the search: in a stack with a PruningCombinator (above
+or below the pool), the goals that do not improve the best known solution are pruned before they
+are reached. To collect the solutions within a few percent of the best one, build the pruning
+combinator with a tolerance (PruningCombinator::with_tolerance).
+*/
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, CheckpointSpace))]
+pub struct SolutionPoolCombinator<Space, N, B, Id> {
+    s: Space,
+    pool: SolutionPool<N, B, Id>,
+    /// computes the key of a node (None: no deduplication)
+    id: Option<fn(&Space, &mut N) -> Id>,
+    t_start: Instant,
+    nb_goals: u64,
+}
+
+impl<N,Space,B,Id> SearchSpace<N,B> for SolutionPoolCombinator<Space, N, B, Id>
+where
+    N: Clone,
+    Space: SearchSpace<N,B>,
+    B: PartialOrd+Copy,
+    Id: Eq,
+{
+    fn initial(&mut self) -> N { self.s.initial() }
+
+    fn bound(&mut self, n: &N) -> B { self.s.bound(n) }
+
+    fn g_cost(&mut self, n: &N) -> B { self.s.g_cost(n) }
+
+    fn goal(&mut self, n: &N) -> bool {
+        let res = self.s.goal(n);
+        if res {
+            self.nb_goals += 1;
+            let v = self.s.bound(n);
+            match self.id {
+                None => {
+                    if self.pool.accepts(v, &None) {
+                        self.pool.insert(n.clone(), v, self.t_start.elapsed(), None);
+                    }
+                },
+                Some(id) => {
+                    let mut node = n.clone();
+                    let key = Some(id(&self.s, &mut node));
+                    self.pool.insert(node, v, self.t_start.elapsed(), key);
+                }
+            }
+        }
+        res
+    }
+
+    fn start_search(&mut self, msg: String) {
+        // the pool follows the objective sense of the search space
+        self.pool.set_sense(self.s.sense());
+        self.s.start_search(msg);
+    }
+
+    fn restart(&mut self, msg: String) {
+        self.s.restart(msg);
+    }
+
+    fn handle_new_best(&mut self, n: N) -> N { self.s.handle_new_best(n) }
+
+    fn stop_search(&mut self, msg: String) {
+        self.s.stop_search(msg);
+    }
+
+    fn display_statistics(&self) {
+        self.s.display_statistics();
+    }
+
+    fn json_statistics(&self, json:&mut serde_json::Value) {
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        report.push(ReportSection::new("solution_pool")
+            .with_counter("size", self.pool.len() as u64)
+            .with_counter("nb_goals", self.nb_goals)
+        );
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("SolutionPool")
+            .with_parameter("k", self.pool.capacity())
+            .with_parameter("deduplication", self.id.is_some()));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
+}
+
+impl<Space, N, B, Id> CheckpointSpace for SolutionPoolCombinator<Space, N, B, Id>
+where
+    Space: CheckpointSpace,
+    N: Serialize+DeserializeOwned,
+    B: PartialOrd+Copy+Serialize+DeserializeOwned,
+    Id: Eq+Serialize+DeserializeOwned,
+{
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["solution_pool"] = json!({
+            "pool": self.pool.save_state(),
+            "nb_goals": self.nb_goals,
+            "elapsed": self.t_start.elapsed().as_secs_f64(),
+        });
+        self.s.save_state(json);
+    }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.pool.load_state(&json["solution_pool"]["pool"])?;
+        self.nb_goals = serde_json::from_value(json["solution_pool"]["nb_goals"].clone())?;
+        // times are measured from the beginning of the checkpointed search
+        let elapsed_secs:f64 = serde_json::from_value(json["solution_pool"]["elapsed"].clone())?;
+        let elapsed = Duration::try_from_secs_f64(elapsed_secs).map_err(serde::de::Error::custom)?;
+        self.t_start = Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now);
+        self.s.load_state(json)
+    }
+}
+
+impl<Space, N, B:PartialOrd+Copy> SolutionPoolCombinator<Space, N, B, ()> {
+    /** builds the decorator around a search space, keeping the k best goals (without
+    deduplication) */
+    pub fn new(s: Space, k:usize) -> Self {
+        Self {
+            s,
+            pool: SolutionPool::new(k),
+            id: None,
+            t_start: Instant::now(),
+            nb_goals: 0,
+        }
+    }
+}
+
+impl<Space, N, B:PartialOrd+Copy, Id:Eq> SolutionPoolCombinator<Space, N, B, Id> {
+    /** builds the decorator around a search space, keeping the k best distinct goals
+    (two goals with the same id are duplicates) */
+    pub fn with_identifiable(s: Space, k:usize) -> Self
+    where Space: Identifiable<N, Id> {
+        Self {
+            s,
+            pool: SolutionPool::new(k),
+            id: Some(|space, n| space.id(n)),
+            t_start: Instant::now(),
+            nb_goals: 0,
+        }
+    }
+
+    /** returns the pool of the k best goals (nodes) */
+    pub fn pool(&self) -> &SolutionPool<N, B, Id> { &self.pool }
+
+    /** returns the k best solutions (from the best to the worst) with their value and the time
+    they were found */
+    pub fn solutions<Sol>(&mut self) -> Vec<(Sol, B, Duration)>
+    where Space: ToSolution<N, Sol>, N: Clone {
+        let nodes:Vec<&PooledSolution<N, B, Id>> = self.pool.iter().collect();
+        let mut res = Vec::with_capacity(nodes.len());
+        for e in nodes {
+            let mut node = e.solution.clone();
+            res.push((self.s.solution(&mut node), e.value, e.time));
+        }
+        res
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion};
+    use crate::search_manager::SearchStatus;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::test_spaces::{Node, BinaryTree};
+
+    #[test]
+    fn k_best_goals() {
+        let space = Rc::new(RefCell::new(SolutionPoolCombinator::new(BinaryTree::new(ObjectiveSense::Minimize), 3)));
+        let mut dfs:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(space.clone());
+        dfs.run(NeverStoppingCriterion::default());
+        assert_eq!(dfs.status(), SearchStatus::Optimal);
+        // the 8 leaves cost 0, 1, 1, 1, 2, 2, 2, 3: the pool keeps the 3 best ones
+        let values:Vec<i64> = space.borrow().pool().iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![0, 1, 1]);
+        let solutions:Vec<i64> = space.borrow_mut().solutions().into_iter().map(|(sol, _, _):(i64, i64, Duration)| sol).collect();
+        assert_eq!(solutions, vec![0, 1, 1]);
+        let mut json = serde_json::json!({});
+        space.borrow().json_statistics(&mut json);
+        assert_eq!(json["solution_pool_nb_goals"], serde_json::json!(8));
+        assert_eq!(json["solution_pool_size"], serde_json::json!(3));
+    }
+
+    #[test]
+    fn distinct_goals() {
+        // all the leaves have the same id (their depth): the pool keeps the best one
+        let space = Rc::new(RefCell::new(SolutionPoolCombinator::with_identifiable(BinaryTree::new(ObjectiveSense::Maximize), 3)));
+        let mut dfs:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(space.clone());
+        dfs.run(NeverStoppingCriterion::default());
+        assert_eq!(*dfs.get_manager().best_val(), Some(3));
+        let pool = space.borrow();
+        assert_eq!(pool.pool().len(), 1);
+        assert_eq!(pool.pool().best().map(|e| (e.value, e.id)), Some((3, Some(3))));
+    }
+
+    /// values of the pool (8 best goals) after a depth first search (maximization) on a pruned
+    /// binary tree
+    fn pruned_pool(pruning:PruningCombinator<BinaryTree, i64>) -> Vec<i64> {
+        let space = Rc::new(RefCell::new(SolutionPoolCombinator::new(pruning, 8)));
+        let mut dfs:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(space.clone());
+        dfs.run(NeverStoppingCriterion::default());
+        assert_eq!(dfs.status(), SearchStatus::Optimal);
+        let values = space.borrow().pool().iter().map(|e| e.value).collect();
+        values
+    }
+
+    #[test]
+    fn pruned_goals() {
+        let pruning = || PruningCombinator::new(BinaryTree::new(ObjectiveSense::Maximize));
+        // the optimal leaf (3) is reached first: the other goals are pruned, except its sibling
+        // (generated before the optimal leaf is reached)
+        assert_eq!(pruned_pool(pruning()), vec![3, 2]);
+        // the goals within 34% of the best one (2) are kept, the others (1 and 0) are pruned
+        assert_eq!(pruned_pool(pruning().with_tolerance(0.34)), vec![3, 2, 2, 2]);
+        // with a 100% tolerance, nothing is pruned
+        assert_eq!(pruned_pool(pruning().with_tolerance(1.)), vec![3, 2, 2, 2, 1, 1, 1, 0]);
+    }
+}
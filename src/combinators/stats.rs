@@ -1,16 +1,19 @@
-use std::fmt::{Display, Debug};
-use std::time::SystemTime;
-use std::rc::Weak;
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+use std::sync::Weak;
 
-use serde::Serialize;
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
 use serde_json::json;
 
 use crate::metric_logger::{Metric, MetricLogger};
-use crate::search_space::{SearchSpace, GuidedSpace, Identifiable, TotalNeighborGeneration, PartialNeighborGeneration, ParetoDominanceSpace, ToSolution};
-use crate::search_combinator::SearchSpaceCombinator;
+use crate::search_space::{SearchSpace, GuidedSpace, IteratorNeighborGeneration, PartialNeighborGeneration, ObjectiveSense, CheckpointSpace};
+use crate::search_report::{SearchReport, ReportSection, ProfilePoint};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
+use crate::objective::Objective;
 
 /// search statistics data (at a given time)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PerfProfilePoint {
     expanded: u64,
     generated: u64,
@@ -23,13 +26,29 @@ pub struct PerfProfilePoint {
 }
 
 /// performance profile entry (PerfProfilePoint + time + solution value)
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PerfProfileEntry<B> {
     p: PerfProfilePoint,
     t: f32,
     v: Option<B>
 }
 
+impl<B:Serialize> PerfProfileEntry<B> {
+    /** converts the entry to a point of a search report */
+    fn profile_point(&self) -> ProfilePoint {
+        let counters = [
+            ("expanded", self.p.expanded), ("generated", self.p.generated),
+            ("initial", self.p.initial), ("eval", self.p.eval), ("goals", self.p.goals),
+            ("trashed", self.p.trashed), ("solutions", self.p.solutions), ("guide", self.p.guide),
+        ];
+        ProfilePoint {
+            time: f64::from(self.t),
+            counters: counters.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            value: self.v.as_ref().map(|v| json!(v)),
+        }
+    }
+}
+
 /// Statistics tree search Combinator
 impl PerfProfilePoint {
     fn new() -> PerfProfilePoint {
@@ -47,17 +66,19 @@ impl PerfProfilePoint {
 }
 
 /** stats decorator. Stores statistics data-structures and reference to the logger. */
-#[derive(Debug)]
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, GuidedSpace, IteratorNeighborGeneration, PartialNeighborGeneration, CheckpointSpace))]
 pub struct StatTsCombinator<Space, B> {
     s: Space,
     stats: PerfProfilePoint,
-    t_start: SystemTime,
+    t_start: Instant,
     nb_sols: u64,
     perfprofile: Vec<PerfProfileEntry<B>>,
     logger: Weak<MetricLogger>,
     logging_id_nbexpanded: Option<usize>,
     logging_id_nbgenerated: Option<usize>,
-    logging_id_obj: Option<usize>,
+    /// one logger column per objective component (lexicographic objectives)
+    logging_ids_obj: Vec<usize>,
 }
 
 impl<N,G,Space,B> GuidedSpace<N,G> for StatTsCombinator<Space,B>
@@ -72,18 +93,10 @@ where
 }
 
 
-impl<N,Sol,Space,B> ToSolution<N,Sol> for StatTsCombinator<Space, B>
-where Space:ToSolution<N,Sol>, B:serde::Serialize {
-    fn solution(&mut self, node: &mut N) -> Sol {
-        self.s.solution(node)
-    }
-}
-
-
 impl<N,Space,B> SearchSpace<N,B> for StatTsCombinator<Space,B>
 where 
     Space: SearchSpace<N,B>,
-    B: Clone+Serialize+Into<i64>+PartialOrd+Copy+std::fmt::Display
+    B: Objective
 {
 
     fn initial(&mut self) -> N {
@@ -116,33 +129,23 @@ where
         self.nb_sols += 1;
         self.perfprofile.push(PerfProfileEntry {
             p: self.stats.clone(),
-            t: self.t_start.elapsed().unwrap().as_secs_f32(),
+            t: self.t_start.elapsed().as_secs_f32(),
             v: Some(obj1)
         });
         // updates logger and display statistics
-        if let Some(logger) = self.logger.upgrade() {
-            if let Some(id) = self.logging_id_obj {
-                logger.update_metric(id, Metric::Int(obj1.into()));
-                logger.request_logging();
-            }
-        }
+        self.log_objective(&obj1);
         // call the handle new best (possibly improve the solution)
         let n2 = self.s.handle_new_best(n);
         self.nb_sols += 1;
         let obj2 = self.s.bound(&n2);
         self.perfprofile.push(PerfProfileEntry {
             p: self.stats.clone(),
-            t: self.t_start.elapsed().unwrap().as_secs_f32(),
+            t: self.t_start.elapsed().as_secs_f32(),
             v: Some(obj2)
         });
         // updates logger and display statistics
-        if obj2 < obj1 {
-            if let Some(logger) = self.logger.upgrade() {
-                if let Some(id) = self.logging_id_obj {
-                    logger.update_metric(id, Metric::Int(obj2.into()));
-                    logger.request_logging();
-                }
-            }
+        if self.s.sense().is_better(&obj2, &obj1) {
+            self.log_objective(&obj2);
         }
         n2
     }
@@ -152,7 +155,7 @@ where
         self.s.stop_search(_msg);
         self.perfprofile.push(PerfProfileEntry {
             p: self.stats.clone(),
-            t: self.t_start.elapsed().unwrap().as_secs_f32(),
+            t: self.t_start.elapsed().as_secs_f32(),
             v: match self.perfprofile.len() {
                 0 => None,
                 n => self.perfprofile[n-1].v
@@ -161,29 +164,63 @@ where
     }
 
     fn json_statistics(&self, json:&mut serde_json::Value) {
-        let time = self.t_start.elapsed().unwrap().as_secs_f32();
-        json["nb_generated"] = serde_json::json!(self.stats.generated);
-        json["nb_expanded"] = serde_json::json!(self.stats.expanded);
-        json["nb_trashed"] = serde_json::json!(self.stats.trashed);
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        let time = self.t_start.elapsed().as_secs_f64();
+        // main statistics of the search: written without prefix in json_statistics
+        let mut section = ReportSection::new("stats").unprefixed()
+            .with_counter("nb_generated", self.stats.generated)
+            .with_counter("nb_expanded", self.stats.expanded)
+            .with_counter("nb_trashed", self.stats.trashed)
+            .with_counter("nb_eval", self.stats.eval)
+            .with_counter("nb_guide", self.stats.guide)
+            .with_counter("nb_sols_created", self.stats.solutions)
+            .with_value("nodes_generated_per_sec", (self.stats.generated as f64) / time)
+            .with_timing("searched", time)
+            .with_perf_profile(self.perfprofile.iter().map(|e| e.profile_point()).collect());
         if self.stats.expanded > 0 {
-            json["avg_branching_factor"] = serde_json::json!((self.stats.generated as f64) / (self.stats.expanded as f64));
+            section = section.with_value("avg_branching_factor", (self.stats.generated as f64) / (self.stats.expanded as f64));
         }
-        json["nb_eval"] = serde_json::json!(self.stats.eval);
-        json["nb_guide"] = serde_json::json!(self.stats.guide);
-        json["time_searched"] = serde_json::json!(time);
-        json["nodes_generated_per_sec"] = serde_json::json!((self.stats.generated as f32) / time);
-        json["nb_sols_created"] = serde_json::json!(self.stats.solutions);
-        json["primal_pareto_diagram"] = self.get_pareto_diagram();
-        match self.perfprofile.last() {
-            None => {},
-            Some(v) => { json["best_primal"] = json!(v.v); }
+        if let Some(v) = self.perfprofile.last() {
+            section = section.with_value("best_primal", v.v);
         }
-        self.s.json_statistics(json);
+        report.push(section);
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("Stats"));
+        self.s.describe_layers(stack);
+    }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    fn is_exact(&self) -> bool { self.s.is_exact() }
+
+    fn notify_incumbent(&mut self, value: B) {
+        // only registers the incumbent if it improves the performance profile
+        let improves = match self.perfprofile.last() {
+            Some(PerfProfileEntry { v: Some(v), .. }) => self.s.sense().is_better(&value, v),
+            _ => true,
+        };
+        if improves {
+            self.perfprofile.push(PerfProfileEntry {
+                p: self.stats.clone(),
+                t: self.t_start.elapsed().as_secs_f32(),
+                v: Some(value)
+            });
+            self.log_objective(&value);
+        }
+        self.s.notify_incumbent(value);
     }
 
     fn display_statistics(&self) {
         self.s.display_statistics();
-        let time = self.t_start.elapsed().unwrap().as_secs_f32();
+        let time = self.t_start.elapsed().as_secs_f32();
         let format = |e| human_format::Formatter::new().with_decimals(1).format(e);
         println!();
         println!(
@@ -225,19 +262,16 @@ where
 }
 
 
-impl<N, Space, B> TotalNeighborGeneration<N> for StatTsCombinator<Space,B>
+impl<N, Space, B> IteratorNeighborGeneration<N> for StatTsCombinator<Space,B>
 where 
-    Space: TotalNeighborGeneration<N>,
+    Space: IteratorNeighborGeneration<N>,
     B: Serialize,
 {
+    /// underlying cursor and number of neighbors generated so far
+    type Cursor = (Space::Cursor, u64);
 
-    fn neighbors(&mut self, node: &mut N) -> Vec<N> {
-        let res = self.s.neighbors(node);
+    fn neighbors_cursor(&mut self, node: &mut N) -> Self::Cursor {
         self.stats.expanded += 1;
-        self.stats.generated += (res.len()) as u64;
-        if res.is_empty() {
-            self.stats.trashed += 1;
-        }
         // updates logger and display statistics
         if let Some(logger) = self.logger.upgrade() {
             if let Some(id) = self.logging_id_nbexpanded {
@@ -246,17 +280,34 @@ where
                     Metric::LargeNumber(self.stats.expanded as f64)
                 );
             }
-            if let Some(id) = self.logging_id_nbgenerated {
-                logger.update_metric(
-                    id,
-                    Metric::LargeNumber(self.stats.generated as f64)
-                );
+        }
+        (self.s.neighbors_cursor(node), 0)
+    }
+
+    fn next_neighbor_from(&mut self, node: &mut N, cursor: &mut Self::Cursor) -> Option<N> {
+        let res = self.s.next_neighbor_from(node, &mut cursor.0);
+        match &res {
+            Some(_) => {
+                self.stats.generated += 1;
+                cursor.1 += 1;
+            },
+            None => {
+                if cursor.1 == 0 {
+                    self.stats.trashed += 1;
+                }
+                if let Some(logger) = self.logger.upgrade() {
+                    if let Some(id) = self.logging_id_nbgenerated {
+                        logger.update_metric(
+                            id,
+                            Metric::LargeNumber(self.stats.generated as f64)
+                        );
+                    }
+                }
             }
         }
         res
     }
 }
-
 impl<N, Space, B> PartialNeighborGeneration<N> for StatTsCombinator<Space,B>
 where 
     Space: PartialNeighborGeneration<N>,
@@ -281,52 +332,81 @@ where
 }
 
 
-impl<N, B, Id, Space> Identifiable<N, Id> for StatTsCombinator<Space, B>
-where
-    Space: Identifiable<N, Id>,
-    B: Serialize,
-{
-    fn id(&self, n: &mut N) -> Id { self.s.id(n) }
-}
+impl<Space:CheckpointSpace, B:Serialize+DeserializeOwned> CheckpointSpace for StatTsCombinator<Space, B> {
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["stats"] = json!({
+            "counters": self.stats,
+            "nb_sols": self.nb_sols,
+            "perfprofile": self.perfprofile,
+            "elapsed": self.t_start.elapsed().as_secs_f64(),
+        });
+        self.s.save_state(json);
+    }
 
-impl<Space, B> SearchSpaceCombinator<Space> for StatTsCombinator<Space, B> {
-    fn unwrap(&self) -> &Space { &self.s }
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.stats = serde_json::from_value(json["stats"]["counters"].clone())?;
+        self.nb_sols = serde_json::from_value(json["stats"]["nb_sols"].clone())?;
+        self.perfprofile = serde_json::from_value(json["stats"]["perfprofile"].clone())?;
+        // times are measured from the beginning of the checkpointed search
+        let elapsed_secs:f64 = serde_json::from_value(json["stats"]["elapsed"].clone())?;
+        let elapsed = Duration::try_from_secs_f64(elapsed_secs).map_err(serde::de::Error::custom)?;
+        self.t_start = Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now);
+        self.s.load_state(json)
+    }
 }
 
-impl<Space, B:Serialize+Copy+Display> StatTsCombinator<Space, B> {
+impl<Space, B:Objective> StatTsCombinator<Space, B> {
     /** builds the decorator around a search space */
     pub fn new(s: Space) -> Self {
         Self {
             s,
             stats: PerfProfilePoint::new(),
-            t_start: SystemTime::now(),
+            t_start: Instant::now(),
             nb_sols: 0,
             perfprofile: Vec::new(),
             logger: Weak::new(),
             logging_id_nbexpanded: None,
             logging_id_nbgenerated: None,
-            logging_id_obj: None,
+            logging_ids_obj: Vec::new(),
         }
     }
 
-    /** binds to a logger (to display statistics in the console) */
-    pub fn bind_logger(mut self, logger_ref:Weak<MetricLogger>) -> Self {
+    /** binds to a logger (to display statistics in the console). The description of the stack
+    (from this layer) is displayed above the headers.
+    The logger is shared with Arc (`Arc::downgrade(&logger)`), Rc-based loggers are no longer accepted. */
+    pub fn bind_logger<N>(mut self, logger_ref:Weak<MetricLogger>) -> Self where Self:SearchSpace<N,B> {
         if let Some(logger) = logger_ref.upgrade() {
+            logger.set_description(&self.describe().to_string());
             // adds headers to the logger
-            let tmp = logger.register_headers([
+            let mut headers = vec![
                 format!("{:<15}","nb expanded"),
                 format!("{:<15}","nb generated"),
-                format!("{:<15}","objective"),
-            ].to_vec());
+            ];
+            match B::nb_components() {
+                1 => headers.push(format!("{:<15}","objective")),
+                k => headers.extend((1..=k).map(|i| format!("{:<15}", format!("objective {}", i)))),
+            }
+            let tmp = logger.register_headers(headers);
             self.logging_id_nbexpanded = Some(tmp[0]);
             self.logging_id_nbgenerated = Some(tmp[1]);
-            self.logging_id_obj = Some(tmp[2]);
+            self.logging_ids_obj = tmp[2..].to_vec();
         }
         // registers the logger
         self.logger = logger_ref;
         self
     }
 
+    /** updates the objective columns of the logger (one per component) and requests a logging */
+    fn log_objective(&self, v:&B) {
+        if self.logging_ids_obj.is_empty() { return; }
+        if let Some(logger) = self.logger.upgrade() {
+            for (id, m) in self.logging_ids_obj.iter().zip(v.component_metrics()) {
+                logger.update_metric(*id, m);
+            }
+            logger.request_logging();
+        }
+    }
+
     /** generates pareto diagram to visualize the search statistics */
     pub fn get_pareto_diagram(&self) -> serde_json::Value {
         let mut points:Vec<serde_json::Value> = vec![];
@@ -353,11 +433,3 @@ impl<Space, B:Serialize+Copy+Display> StatTsCombinator<Space, B> {
 
 }
 
-
-impl<N,Space,B> ParetoDominanceSpace<N> for StatTsCombinator<Space, B>
-where 
-    Space: ParetoDominanceSpace<N>,
-    B: Serialize,
-{
-    fn dominates(&self, a:&N, b:&N) -> bool { self.s.dominates(a,b) }
-}
\ No newline at end of file
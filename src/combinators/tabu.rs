@@ -1,14 +1,19 @@
 use std::marker::PhantomData;
 
-use crate::search_space::{DecisionSpace, GuidedSpace, Identifiable, ParetoDominanceSpace, PartialNeighborGeneration, SearchSpace, ToSolution, TotalNeighborGeneration};
-use crate::search_combinator::SearchSpaceCombinator;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::search_space::{DecisionSpace, PartialNeighborGeneration, SearchSpace, IteratorNeighborGeneration, ObjectiveSense, CheckpointSpace};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription, LayerDescription};
 
 use crate::combinators::helper::tabu_tenure::TabuTenure;
 
 /**
 pruning decorator: stores the best known solution and counts the number of prunings for statistics.
 */
-#[derive(Debug)]
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(SearchSpace, IteratorNeighborGeneration, PartialNeighborGeneration, CheckpointSpace))]
 pub struct TabuCombinator<Space, B, Tenure, D> {
     s: Space,
     tenure: Tenure,
@@ -16,17 +21,6 @@ pub struct TabuCombinator<Space, B, Tenure, D> {
     phandom_d: PhantomData<D>,
 }
 
-impl<N,G,Space,B, Tenure, D> GuidedSpace<N,G> for TabuCombinator<Space, B, Tenure, D> 
-where Space:GuidedSpace<N,G>
-{
-    fn guide(&mut self, n: &N) -> G { self.s.guide(n) }
-}
-
-impl<N,Sol,Space,B, Tenure, D> ToSolution<N,Sol> for TabuCombinator<Space, B, Tenure, D>
-where Space:ToSolution<N,Sol> {
-    fn solution(&mut self, node: &mut N) -> Sol { self.s.solution(node) }
-}
-
 impl<N,Space,B, Tenure, D> SearchSpace<N,B> for TabuCombinator<Space,B,Tenure, D>
 where Space:SearchSpace<N,B>, B:serde::Serialize+PartialOrd+Clone
 {
@@ -55,38 +49,72 @@ where Space:SearchSpace<N,B>, B:serde::Serialize+PartialOrd+Clone
     }
 
     fn json_statistics(&self, json:&mut serde_json::Value) {
-        self.s.json_statistics(json);
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    fn report_sections(&self, report:&mut SearchReport) {
+        report.push(ReportSection::new("tabu"));
+        self.s.report_sections(report);
+    }
+
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("Tabu"));
+        self.s.describe_layers(stack);
     }
+
+    fn dual_bound(&self) -> Option<B> { self.s.dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { self.s.sense() }
+
+    /// the discarded children may contain better solutions
+    fn is_exact(&self) -> bool { false }
+
+    fn notify_incumbent(&mut self, value: B) { self.s.notify_incumbent(value) }
 }
 
 
-impl<N, Space, B, Tenure, Decision> TotalNeighborGeneration<N> for TabuCombinator<Space,B,Tenure, Decision>
+impl<N, Space, B, Tenure, Decision> IteratorNeighborGeneration<N> for TabuCombinator<Space,B,Tenure, Decision>
 where 
-    Space: TotalNeighborGeneration<N> + SearchSpace<N, B> + DecisionSpace<N,Decision>,
+    Space: IteratorNeighborGeneration<N> + SearchSpace<N, B> + DecisionSpace<N,Decision>,
     B: PartialOrd+Copy,
     Decision: std::hash::Hash+Eq,
     Tenure: TabuTenure<N, Decision>,
-    N: Clone,
 {
+    type Cursor = Space::Cursor;
 
-    fn neighbors(&mut self, n: &mut N) -> Vec<N> {
+    fn neighbors_cursor(&mut self, n: &mut N) -> Self::Cursor {
         // add the decision to the tabu list
         match self.s.decision(n) {
             None => {},
             Some(d) => self.tenure.insert(n, d)
         }
-        // remove neighbors that have their decision in the tabu tenure
-        self.s.neighbors(n).iter().filter(|neigh| {
-            match self.s.decision(neigh) {
+        self.s.neighbors_cursor(n)
+    }
+
+    fn next_neighbor_from(&mut self, n: &mut N, cursor: &mut Self::Cursor) -> Option<N> {
+        // skip neighbors that have their decision in the tabu tenure
+        while let Some(neigh) = self.s.next_neighbor_from(n, cursor) {
+            let allowed = match self.s.decision(&neigh) {
                 None => true,
-                Some(d) => !self.tenure.contains(neigh, &d) || self.s.aspiration_criterion(n)
-            }
-        }).cloned().collect()
+                Some(d) => !self.tenure.contains(&neigh, &d) || self.s.aspiration_criterion(n)
+            };
+            if allowed { return Some(neigh); }
+        }
+        None
     }
 }
 
-impl<Space, B, Tenure, D> SearchSpaceCombinator<Space> for TabuCombinator<Space, B, Tenure, D> {
-    fn unwrap(&self) -> &Space { &self.s }
+impl<Space, B, Tenure, D> CheckpointSpace for TabuCombinator<Space, B, Tenure, D>
+where Space:CheckpointSpace, Tenure:Serialize+DeserializeOwned {
+    fn save_state(&self, json:&mut serde_json::Value) {
+        json["tabu"] = serde_json::json!(self.tenure);
+        self.s.save_state(json);
+    }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.tenure = serde_json::from_value(json["tabu"].clone())?;
+        self.s.load_state(json)
+    }
 }
 
 
@@ -97,29 +125,25 @@ impl<Space, B, Tenure, D> TabuCombinator<Space, B, Tenure, D> {
     }
 }
 
-impl<N, B, Id, Space, Tenure, D> Identifiable<N, Id> for TabuCombinator<Space, B, Tenure, D>
-where
-    Space: Identifiable<N, Id>,
-{
-    fn id(&self, n: &mut N) -> Id { self.s.id(n) }
-}
-
-
-impl<N,Space,B, Tenure, D> ParetoDominanceSpace<N> for TabuCombinator<Space, B, Tenure, D>
-where Space: ParetoDominanceSpace<N>
-{
-    fn dominates(&self, a:&N, b:&N) -> bool { self.s.dominates(a,b) }
-}
-
-
-impl<N, Space, B, Tenure, D> PartialNeighborGeneration<N> for TabuCombinator<Space,B, Tenure, D>
+impl<N, Space, B, Tenure, Decision> PartialNeighborGeneration<N> for TabuCombinator<Space,B, Tenure, Decision>
 where 
-    Space: PartialNeighborGeneration<N>+SearchSpace<N,B>,
-    B: PartialOrd+Copy,
+    Space: PartialNeighborGeneration<N> + DecisionSpace<N,Decision>,
+    Decision: std::hash::Hash+Eq,
+    Tenure: TabuTenure<N, Decision>,
 {
-    fn next_neighbor(&mut self, _node: &mut N) -> Option<N> {
-        // TODO iterate until a node has not its decision in the tenure
-        todo!()
-        // self.s.next_neighbor(node)
+    fn next_neighbor(&mut self, n: &mut N) -> Option<N> {
+        // add the decision to the tabu list (the first time the node is expanded)
+        if let Some(d) = self.s.decision(n) {
+            if !self.tenure.contains(n, &d) { self.tenure.insert(n, d); }
+        }
+        // skip neighbors that have their decision in the tabu tenure
+        while let Some(neigh) = self.s.next_neighbor(n) {
+            let allowed = match self.s.decision(&neigh) {
+                None => true,
+                Some(d) => !self.tenure.contains(&neigh, &d) || self.s.aspiration_criterion(n)
+            };
+            if allowed { return Some(neigh); }
+        }
+        None
     }
-}
\ No newline at end of file
+}
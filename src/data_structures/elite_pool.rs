@@ -0,0 +1,250 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::search_space::ObjectiveSense;
+
+/**
+Bounded pool of elite solutions with a diversity-aware insertion. Each solution is stored with
+its cost (the better cost is given by the objective sense, minimization by default). The
+distance between solutions is given by the user at insertion time.
+
+Insertion rules:
+ - if the new solution is too close (distance < min_distance) to some member, it replaces the
+   closest one if it is better, otherwise it is rejected.
+ - if the pool is not full, the solution is added.
+ - if the pool is full and the solution is better than the worst member, it replaces the most
+   similar member among the members that are worse than it.
+ - otherwise, the solution is rejected.
+*/
+#[derive(Debug, Clone)]
+pub struct ElitePool<Sol, B> {
+    capacity: usize,
+    min_distance: f64,
+    sense: ObjectiveSense,
+    elements: Vec<(Sol, B)>,
+    nb_insertions: u64,
+    nb_rejections: u64,
+}
+
+impl<Sol, B:PartialOrd+Copy> ElitePool<Sol, B> {
+    /** creates an empty pool given its maximum size and the minimum distance between members */
+    pub fn new(capacity:usize, min_distance:f64) -> Self {
+        Self {
+            capacity,
+            min_distance,
+            sense: ObjectiveSense::default(),
+            elements: Vec::with_capacity(capacity),
+            nb_insertions: 0,
+            nb_rejections: 0,
+        }
+    }
+
+    /** sets the objective sense of the pool (before any insertion) */
+    pub fn with_sense(mut self, sense:ObjectiveSense) -> Self {
+        self.set_sense(sense);
+        self
+    }
+
+    /** sets the objective sense of the pool (should be called before any insertion) */
+    pub fn set_sense(&mut self, sense:ObjectiveSense) { self.sense = sense; }
+
+    /** objective sense of the pool */
+    pub fn sense(&self) -> ObjectiveSense { self.sense }
+
+    /** number of solutions in the pool */
+    pub fn len(&self) -> usize { self.elements.len() }
+
+    /** true iff the pool is empty */
+    pub fn is_empty(&self) -> bool { self.elements.is_empty() }
+
+    /** maximum number of solutions in the pool */
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /** minimum distance between a new solution and the pool members (to be inserted) */
+    pub fn min_distance(&self) -> f64 { self.min_distance }
+
+    /** number of accepted insertions */
+    pub fn nb_insertions(&self) -> u64 { self.nb_insertions }
+
+    /** number of rejected insertions */
+    pub fn nb_rejections(&self) -> u64 { self.nb_rejections }
+
+    /** iterates over the pool members (solution and cost) */
+    pub fn iter(&self) -> impl Iterator<Item=&(Sol, B)> { self.elements.iter() }
+
+    /** returns the i-th member of the pool */
+    pub fn get(&self, i:usize) -> Option<&(Sol, B)> { self.elements.get(i) }
+
+    /** returns the index of the best member (if any) */
+    pub fn best_index(&self) -> Option<usize> {
+        self.index_by(|a,b| self.sense.is_better(a, b))
+    }
+
+    /** returns the best member (if any) */
+    pub fn best(&self) -> Option<&(Sol, B)> {
+        self.best_index().map(|i| &self.elements[i])
+    }
+
+    /** returns the index of the worst member (if any) */
+    pub fn worst_index(&self) -> Option<usize> {
+        self.index_by(|a,b| self.sense.is_better(b, a))
+    }
+
+    /** returns the index of the member that is the most distant from sol (if any) */
+    pub fn most_distant<F:Fn(&Sol,&Sol)->f64>(&self, sol:&Sol, distance:F) -> Option<usize> {
+        let mut res:Option<(usize, f64)> = None;
+        for (i,(e,_)) in self.elements.iter().enumerate() {
+            let d = distance(sol, e);
+            if res.is_none_or(|(_,best_d)| d > best_d) { res = Some((i,d)); }
+        }
+        res.map(|(i,_)| i)
+    }
+
+    /**
+    tries to insert a solution in the pool. Returns true iff the solution was inserted.
+    */
+    pub fn insert<F:Fn(&Sol,&Sol)->f64>(&mut self, sol:Sol, cost:B, distance:F) -> bool {
+        // closest member
+        let mut closest:Option<(usize, f64)> = None;
+        for (i,(e,_)) in self.elements.iter().enumerate() {
+            let d = distance(&sol, e);
+            if closest.is_none_or(|(_,best_d)| d < best_d) { closest = Some((i,d)); }
+        }
+        let inserted = match closest {
+            // too close to some member: replaces it if better
+            Some((i,d)) if d < self.min_distance => {
+                if self.sense.is_better(&cost, &self.elements[i].1) {
+                    self.elements[i] = (sol, cost);
+                    true
+                } else { false }
+            },
+            // the pool is not full
+            _ if self.elements.len() < self.capacity => {
+                self.elements.push((sol, cost));
+                true
+            },
+            // the pool is full: replaces the most similar member among the worse ones
+            _ => {
+                let mut replaced:Option<(usize, f64)> = None;
+                for (i,(e,c)) in self.elements.iter().enumerate() {
+                    if self.sense.is_better(&cost, c) {
+                        let d = distance(&sol, e);
+                        if replaced.is_none_or(|(_,best_d)| d < best_d) { replaced = Some((i,d)); }
+                    }
+                }
+                match replaced {
+                    None => false,
+                    Some((i,_)) => {
+                        self.elements[i] = (sol, cost);
+                        true
+                    }
+                }
+            }
+        };
+        if inserted { self.nb_insertions += 1; } else { self.nb_rejections += 1; }
+        inserted
+    }
+
+    /** removes all the members of the pool */
+    pub fn clear(&mut self) { self.elements.clear(); }
+
+    /** exports the members of the pool (and its counters) to a JSON format */
+    pub fn save_state(&self) -> serde_json::Value where Sol:Serialize, B:Serialize {
+        json!({
+            "elements": self.elements,
+            "nb_insertions": self.nb_insertions,
+            "nb_rejections": self.nb_rejections,
+        })
+    }
+
+    /** restores the members of the pool (and its counters) from a JSON format (built by save_state) */
+    pub fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error>
+    where Sol:DeserializeOwned, B:DeserializeOwned {
+        self.elements = serde_json::from_value(json["elements"].clone())?;
+        self.nb_insertions = serde_json::from_value(json["nb_insertions"].clone())?;
+        self.nb_rejections = serde_json::from_value(json["nb_rejections"].clone())?;
+        Ok(())
+    }
+
+    /** index of the member that is preferred according to the comparison function */
+    fn index_by<F:Fn(&B,&B)->bool>(&self, prefer:F) -> Option<usize> {
+        let mut res:Option<usize> = None;
+        for (i,(_,c)) in self.elements.iter().enumerate() {
+            if res.is_none_or(|j| prefer(c, &self.elements[j].1)) { res = Some(i); }
+        }
+        res
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dist(a:&i32, b:&i32) -> f64 { (a-b).abs() as f64 }
+
+    #[test]
+    fn insert_until_full() {
+        let mut pool = ElitePool::new(2, 1.);
+        assert!(pool.insert(0, 10, dist));
+        assert!(pool.insert(5, 8, dist));
+        assert_eq!(pool.len(), 2);
+        // full and worse than every member
+        assert!(!pool.insert(10, 12, dist));
+        assert_eq!(pool.nb_rejections(), 1);
+        assert_eq!(pool.best().unwrap().1, 8);
+    }
+
+    #[test]
+    fn too_close_replaces_if_better() {
+        let mut pool = ElitePool::new(3, 2.);
+        assert!(pool.insert(0, 10, dist));
+        assert!(!pool.insert(1, 11, dist));
+        assert!(pool.insert(1, 9, dist));
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.get(0).unwrap().0, 1);
+    }
+
+    #[test]
+    fn full_replaces_most_similar_worse() {
+        let mut pool = ElitePool::new(3, 1.);
+        pool.insert(0, 5, dist);
+        pool.insert(10, 20, dist);
+        pool.insert(20, 30, dist);
+        // better than 10 and 20, closer to 20
+        assert!(pool.insert(18, 7, dist));
+        let sols:Vec<i32> = pool.iter().map(|(s,_)| *s).collect();
+        assert_eq!(sols, vec![0, 10, 18]);
+        assert_eq!(pool.worst_index(), Some(1));
+        assert_eq!(pool.most_distant(&0, dist), Some(2));
+    }
+
+    #[test]
+    fn maximization() {
+        let mut pool = ElitePool::new(2, 2.).with_sense(ObjectiveSense::Maximize);
+        assert!(pool.insert(0, 10, dist));
+        assert!(!pool.insert(1, 9, dist));
+        assert!(pool.insert(1, 11, dist));
+        assert!(pool.insert(10, 5, dist));
+        // full: better than the member of value 5 only
+        assert!(!pool.insert(20, 4, dist));
+        assert!(pool.insert(20, 8, dist));
+        let sols:Vec<i32> = pool.iter().map(|(s,_)| *s).collect();
+        assert_eq!(sols, vec![1, 20]);
+        assert_eq!(pool.best().unwrap().1, 11);
+        assert_eq!(pool.worst_index(), Some(1));
+    }
+
+    #[test]
+    fn state_round_trip() {
+        let mut pool = ElitePool::new(3, 1.);
+        pool.insert(0, 5, dist);
+        pool.insert(10, 20, dist);
+        pool.insert(10, 30, dist);
+        let mut restored:ElitePool<i32, i32> = ElitePool::new(3, 1.);
+        restored.load_state(&pool.save_state()).unwrap();
+        assert_eq!(restored.iter().collect::<Vec<_>>(), pool.iter().collect::<Vec<_>>());
+        assert_eq!((restored.nb_insertions(), restored.nb_rejections()), (2, 1));
+    }
+}
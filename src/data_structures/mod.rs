@@ -13,4 +13,15 @@ pub mod lazy_clonable;
 /**
 Implmements a sparse set. Allows fast operations but consumes more memory than a standard bit-set.
 */
-pub mod sparse_set;
\ No newline at end of file
+pub mod sparse_set;
+
+/**
+Bounded pool of elite solutions with a diversity-aware insertion (used for instance by path
+relinking).
+*/
+pub mod elite_pool;
+
+/**
+Bounded pool of the k best distinct solutions (with the time they were found).
+*/
+pub mod solution_pool;
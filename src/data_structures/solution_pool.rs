@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::search_space::ObjectiveSense;
+
+/**
+solution stored in a solution pool: its value, the time it was found, and an optional key used
+to detect duplicates.
+*/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PooledSolution<T, B, Id> {
+    /// solution (or node)
+    pub solution: T,
+    /// objective value
+    pub value: B,
+    /// time the solution was found (since the beginning of the search)
+    pub time: Duration,
+    /// key used to detect duplicates
+    pub id: Option<Id>,
+}
+
+/**
+Bounded pool of the k best distinct solutions (the better value is given by the objective sense,
+minimization by default).
+If a key is provided, two solutions with the same key are duplicates (only the best one is kept).
+Solutions are sorted from the best to the worst (ties are broken by the time they were found).
+*/
+#[derive(Debug, Clone)]
+pub struct SolutionPool<T, B, Id> {
+    k: usize,
+    sense: ObjectiveSense,
+    elements: Vec<PooledSolution<T, B, Id>>,
+}
+
+impl<T, B:PartialOrd+Copy, Id:Eq> SolutionPool<T, B, Id> {
+    /** creates an empty pool keeping at most k solutions */
+    pub fn new(k:usize) -> Self {
+        Self { k, sense: ObjectiveSense::default(), elements: Vec::with_capacity(k) }
+    }
+
+    /** sets the objective sense of the pool (before any insertion) */
+    pub fn with_sense(mut self, sense:ObjectiveSense) -> Self {
+        self.set_sense(sense);
+        self
+    }
+
+    /** sets the objective sense of the pool (should be called before any insertion) */
+    pub fn set_sense(&mut self, sense:ObjectiveSense) { self.sense = sense; }
+
+    /** objective sense of the pool */
+    pub fn sense(&self) -> ObjectiveSense { self.sense }
+
+    /** number of solutions in the pool */
+    pub fn len(&self) -> usize { self.elements.len() }
+
+    /** true iff the pool is empty */
+    pub fn is_empty(&self) -> bool { self.elements.is_empty() }
+
+    /** maximum number of solutions in the pool */
+    pub fn capacity(&self) -> usize { self.k }
+
+    /** iterates over the solutions (from the best to the worst) */
+    pub fn iter(&self) -> impl Iterator<Item=&PooledSolution<T, B, Id>> { self.elements.iter() }
+
+    /** returns the best solution (if any) */
+    pub fn best(&self) -> Option<&PooledSolution<T, B, Id>> { self.elements.first() }
+
+    /** true iff a solution of this value and key would be inserted in the pool */
+    pub fn accepts(&self, value:B, id:&Option<Id>) -> bool {
+        if let Some(key) = id {
+            if let Some(e) = self.elements.iter().find(|e| e.id.as_ref() == Some(key)) {
+                return self.sense.is_better(&value, &e.value);
+            }
+        }
+        self.elements.len() < self.k || self.elements.last().is_some_and(|e| self.sense.is_better(&value, &e.value))
+    }
+
+    /**
+    inserts a solution. Returns true iff it was inserted (it is among the k best distinct
+    solutions).
+    */
+    pub fn insert(&mut self, solution:T, value:B, time:Duration, id:Option<Id>) -> bool {
+        if !self.accepts(value, &id) { return false; }
+        // removes the duplicate (worse) solution if any
+        if let Some(key) = &id {
+            self.elements.retain(|e| e.id.as_ref() != Some(key));
+        }
+        let pos = self.elements.iter().position(|e| self.sense.is_better(&value, &e.value)).unwrap_or(self.elements.len());
+        self.elements.insert(pos, PooledSolution { solution, value, time, id });
+        self.elements.truncate(self.k);
+        true
+    }
+
+    /** exports the solutions of the pool to a JSON format */
+    pub fn save_state(&self) -> serde_json::Value where T:Serialize, B:Serialize, Id:Serialize {
+        json!({ "elements": self.elements })
+    }
+
+    /** restores the solutions of the pool from a JSON format (built by save_state) */
+    pub fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error>
+    where T:DeserializeOwned, B:DeserializeOwned, Id:DeserializeOwned {
+        self.elements = serde_json::from_value(json["elements"].clone())?;
+        Ok(())
+    }
+
+    /**
+    returns the solutions whose value is not worse than threshold (for instance, the solutions
+    within a few percent of the best one)
+    */
+    pub fn within(&self, threshold:B) -> Vec<&PooledSolution<T, B, Id>> {
+        self.elements.iter().filter(|e| !self.sense.is_better(&threshold, &e.value)).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_k_best() {
+        let mut pool:SolutionPool<&str, i32, ()> = SolutionPool::new(2);
+        assert!(pool.insert("a", 10, Duration::default(), None));
+        assert!(pool.insert("b", 5, Duration::default(), None));
+        assert!(pool.insert("c", 7, Duration::default(), None));
+        assert!(!pool.insert("d", 12, Duration::default(), None));
+        let values:Vec<i32> = pool.iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![5, 7]);
+        assert_eq!(pool.best().unwrap().solution, "b");
+    }
+
+    #[test]
+    fn deduplicates_by_key() {
+        let mut pool:SolutionPool<&str, i32, u32> = SolutionPool::new(3);
+        assert!(pool.insert("a", 10, Duration::default(), Some(1)));
+        assert!(!pool.insert("a'", 11, Duration::default(), Some(1)));
+        assert!(pool.insert("a''", 8, Duration::default(), Some(1)));
+        assert!(pool.insert("b", 9, Duration::default(), Some(2)));
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.best().unwrap().solution, "a''");
+    }
+
+    #[test]
+    fn within() {
+        let mut pool:SolutionPool<&str, i32, ()> = SolutionPool::new(5);
+        pool.insert("a", 100, Duration::default(), None);
+        pool.insert("b", 102, Duration::default(), None);
+        pool.insert("c", 110, Duration::default(), None);
+        assert_eq!(pool.within(105).len(), 2);
+    }
+
+    #[test]
+    fn maximization() {
+        let mut pool:SolutionPool<&str, i32, u32> = SolutionPool::new(2).with_sense(ObjectiveSense::Maximize);
+        assert!(pool.insert("a", 5, Duration::default(), Some(1)));
+        assert!(pool.insert("b", 10, Duration::default(), Some(2)));
+        assert!(!pool.insert("c", 3, Duration::default(), Some(3)));
+        assert!(pool.insert("a'", 7, Duration::default(), Some(1)));
+        assert!(!pool.insert("a''", 6, Duration::default(), Some(1)));
+        let values:Vec<i32> = pool.iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![10, 7]);
+        assert_eq!(pool.best().unwrap().solution, "b");
+        assert_eq!(pool.within(8).len(), 1);
+    }
+}
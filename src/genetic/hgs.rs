@@ -0,0 +1,756 @@
+use std::marker::PhantomData;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Weak;
+use std::cmp::Ordering;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::Serialize;
+
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_algorithm::{SearchAlgorithm, StoppingCriterion, DEFAULT_SEED};
+use crate::search_space::ObjectiveSense;
+use crate::metric_logger::{Metric, MetricLogger};
+use crate::local_search::local_search_space::LocalSearch;
+
+/**
+Defines the problem-specific operators required by the hybrid genetic search.
+*/
+pub trait HgsSpace<Sol> {
+    /**
+    generates a random (possibly infeasible) solution. Used to build the initial population.
+    */
+    fn random_solution<R:Rng>(&mut self, rng:&mut R) -> Sol;
+
+    /**
+    generates an offspring from two parents (for instance an OX crossover)
+    */
+    fn crossover<R:Rng>(&mut self, p1:&Sol, p2:&Sol, rng:&mut R) -> Sol;
+
+    /**
+    returns the objective of the solution (without penalties)
+    */
+    fn cost(&mut self, sol:&Sol) -> f64;
+
+    /**
+    returns the excess of each constraint (0 if the constraint is satisfied).
+    The returned vector must always have the same size.
+    */
+    fn violations(&mut self, sol:&Sol) -> Vec<f64>;
+
+    /**
+    returns the distance between two solutions in [0,1] (for instance the broken-pairs distance).
+    Used to measure the diversity contribution of an individual.
+    */
+    fn distance(&mut self, a:&Sol, b:&Sol) -> f64;
+}
+
+
+/**
+computes the broken-pairs distance between two permutations, *i.e.* the proportion of
+successions (a[i], a[i+1]) in a that do not appear in b. Elements must be in 0..a.len().
+*/
+pub fn broken_pairs_distance(a:&[usize], b:&[usize]) -> f64 {
+    if a.len() < 2 { return 0.; }
+    let mut successor_b = vec![usize::MAX; b.len()];
+    for w in b.windows(2) {
+        successor_b[w[0]] = w[1];
+    }
+    let nb_broken = a.windows(2).filter(|w| successor_b[w[0]] != w[1]).count();
+    nb_broken as f64 / (a.len()-1) as f64
+}
+
+
+/**
+parameters of the hybrid genetic search
+*/
+#[derive(Debug, Clone, Serialize)]
+pub struct HgsParams {
+    /// minimum size of each subpopulation
+    pub mu: usize,
+    /// number of individuals generated before a survivor selection
+    pub lambda: usize,
+    /// number of elite individuals (protected by the biased fitness)
+    pub nb_elite: usize,
+    /// number of closest individuals considered to compute the diversity contribution
+    pub nb_close: usize,
+    /// target proportion of feasible individuals after education
+    pub target_feasible: f64,
+    /// number of iterations between two penalty adjustments
+    pub penalty_management_frequency: usize,
+    /// initial penalty coefficient of each constraint
+    pub initial_penalty: f64,
+    /// probability to repair an infeasible offspring
+    pub repair_probability: f64,
+    /// number of iterations without improvement before restarting the population
+    pub nb_iter_no_improvement: usize,
+    /// number of iterations between two log displays
+    pub log_frequency: usize,
+}
+
+impl Default for HgsParams {
+    fn default() -> Self {
+        Self {
+            mu: 25,
+            lambda: 40,
+            nb_elite: 4,
+            nb_close: 5,
+            target_feasible: 0.2,
+            penalty_management_frequency: 100,
+            initial_penalty: 1.,
+            repair_probability: 0.5,
+            nb_iter_no_improvement: 20000,
+            log_frequency: 500,
+        }
+    }
+}
+
+
+/**
+individual of the population with its cached evaluation
+*/
+#[derive(Debug, Clone)]
+struct Individual<Sol> {
+    sol: Sol,
+    cost: f64,
+    violations: Vec<f64>,
+    penalized_cost: f64,
+    biased_fitness: f64,
+    is_clone: bool,
+    /// identifier in its subpopulation
+    id: usize,
+    /// distances to the other individuals of its subpopulation (sorted by increasing distance)
+    proximity: Vec<(f64, usize)>,
+}
+
+impl<Sol> Individual<Sol> {
+    fn is_feasible(&self) -> bool { self.violations.iter().all(|v| *v <= 0.) }
+
+    fn update_penalized_cost(&mut self, penalties:&[f64]) {
+        self.penalized_cost = self.cost + self.violations.iter().zip(penalties.iter())
+            .map(|(v,p)| v*p).sum::<f64>();
+    }
+}
+
+
+/**
+subpopulation (feasible or infeasible individuals) sorted by penalized cost.
+The distances between its individuals are computed once (on insertion) and kept in their
+proximity lists.
+*/
+#[derive(Debug)]
+struct SubPopulation<Sol> {
+    individuals: Vec<Individual<Sol>>,
+    /// identifier of the next inserted individual
+    next_id: usize,
+}
+
+impl<Sol> SubPopulation<Sol> {
+    fn new() -> Self { Self { individuals: Vec::new(), next_id: 0 } }
+
+    fn len(&self) -> usize { self.individuals.len() }
+
+    fn sort(&mut self) {
+        self.individuals.sort_by(|a,b|
+            a.penalized_cost.partial_cmp(&b.penalized_cost).unwrap_or(Ordering::Equal)
+        );
+    }
+
+    /**
+    inserts an individual and computes its distance to the other individuals
+    */
+    fn insert<Space:HgsSpace<Sol>>(&mut self, mut individual:Individual<Sol>, space:&mut Space) {
+        individual.id = self.next_id;
+        self.next_id += 1;
+        individual.proximity.clear();
+        for other in self.individuals.iter_mut() {
+            let d = space.distance(&individual.sol, &other.sol);
+            Self::insert_proximity(&mut other.proximity, d, individual.id);
+            Self::insert_proximity(&mut individual.proximity, d, other.id);
+        }
+        self.individuals.push(individual);
+        self.sort();
+    }
+
+    /** inserts a distance in a proximity list (sorted by increasing distance) */
+    fn insert_proximity(proximity:&mut Vec<(f64, usize)>, d:f64, id:usize) {
+        let pos = proximity.partition_point(|(d2, _)| *d2 <= d);
+        proximity.insert(pos, (d, id));
+    }
+
+    /**
+    removes the i-th individual (and its distances to the other individuals)
+    */
+    fn remove(&mut self, i:usize) {
+        let removed = self.individuals.remove(i);
+        for other in self.individuals.iter_mut() {
+            other.proximity.retain(|(_, id)| *id != removed.id);
+        }
+    }
+
+    /**
+    updates the biased fitness of every individual.
+    BF(I) = fit(I) + (1 - nb_elite/n) * dc(I), where fit is the rank by penalized cost and dc the
+    rank by diversity contribution (average distance to the nb_close closest individuals).
+    */
+    fn update_biased_fitness(&mut self, nb_elite:usize, nb_close:usize) {
+        let n = self.len();
+        if n == 0 { return; }
+        if n == 1 {
+            self.individuals[0].biased_fitness = 0.;
+            self.individuals[0].is_clone = false;
+            return;
+        }
+        // diversity contribution of each individual
+        let mut diversity:Vec<(usize, f64)> = Vec::with_capacity(n);
+        for (i, individual) in self.individuals.iter_mut().enumerate() {
+            let distances = &individual.proximity;
+            individual.is_clone = distances[0].0 <= 0.;
+            let k = nb_close.min(distances.len()).max(1);
+            diversity.push((i, distances[..k].iter().map(|(d, _)| d).sum::<f64>() / k as f64));
+        }
+        // rank by decreasing diversity contribution
+        diversity.sort_by(|a,b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        let elite_factor = 1. - (nb_elite as f64) / (n as f64);
+        for (div_rank, (i, _)) in diversity.iter().enumerate() {
+            // individuals are sorted by penalized cost, so i is the cost rank
+            let fit_rank = (*i as f64) / ((n-1) as f64);
+            let div_rank_ratio = (div_rank as f64) / ((n-1) as f64);
+            self.individuals[*i].biased_fitness = fit_rank + elite_factor.max(0.) * div_rank_ratio;
+        }
+    }
+
+    /**
+    removes the individual with the worst biased fitness (clones are removed first)
+    */
+    fn remove_worst(&mut self, nb_elite:usize, nb_close:usize) {
+        self.update_biased_fitness(nb_elite, nb_close);
+        let worst = self.individuals.iter().enumerate().max_by(|(_,a),(_,b)| {
+            a.is_clone.cmp(&b.is_clone).then(
+                a.biased_fitness.partial_cmp(&b.biased_fitness).unwrap_or(Ordering::Equal)
+            )
+        }).map(|(i,_)| i);
+        if let Some(i) = worst {
+            self.remove(i);
+        }
+    }
+
+    /**
+    survivor selection: reduces the subpopulation to mu individuals
+    */
+    fn select_survivors(&mut self, mu:usize, nb_elite:usize, nb_close:usize) {
+        while self.len() > mu {
+            self.remove_worst(nb_elite, nb_close);
+        }
+    }
+}
+
+
+/**
+Hybrid Genetic Search (HGS) with diversity management. Maintains a feasible and an infeasible
+subpopulation. Individuals are selected by binary tournament on their biased fitness (cost rank
+and diversity contribution), educated using a local search, and constraint violations are handled
+by adaptive penalties.
+*/
+#[derive(Debug)]
+pub struct HybridGeneticSearch<Sol, Space, LS, H=Rc<RefCell<Space>>> {
+    /// search manager (keeps the best feasible solution)
+    pub manager: SearchManager<Sol, f64>,
+    space: H,
+    space_phantom: PhantomData<Space>,
+    local_search: LS,
+    params: HgsParams,
+    feasible: SubPopulation<Sol>,
+    infeasible: SubPopulation<Sol>,
+    penalties: Vec<f64>,
+    /// for each constraint, number of educated individuals (total, satisfying the constraint)
+    feasibility_stats: Vec<(usize, usize)>,
+    nb_iter: usize,
+    nb_restarts: usize,
+    /// seed of the random number generator
+    seed: u64,
+    rng: StdRng,
+    logger: Weak<MetricLogger>,
+    logging_id_iter: Option<usize>,
+    logging_id_pop: Option<usize>,
+    logging_id_best: Option<usize>,
+}
+
+impl<Sol, Space, LS, H:SpaceHandle<Space>> HybridGeneticSearch<Sol, Space, LS, H>
+where
+    Sol: Clone,
+    Space: HgsSpace<Sol>,
+    LS: LocalSearch<Sol>,
+{
+    /** builds the hybrid genetic search given a search space, a local search (education)
+    and the search parameters */
+    pub fn new(space:H, local_search:LS, params:HgsParams) -> Self {
+        Self {
+            manager: SearchManager::default(),
+            space,
+            space_phantom: PhantomData,
+            local_search,
+            params,
+            feasible: SubPopulation::new(),
+            infeasible: SubPopulation::new(),
+            penalties: Vec::new(),
+            feasibility_stats: Vec::new(),
+            nb_iter: 0,
+            nb_restarts: 0,
+            seed: DEFAULT_SEED,
+            rng: StdRng::seed_from_u64(DEFAULT_SEED),
+            logger: Weak::new(),
+            logging_id_iter: None,
+            logging_id_pop: None,
+            logging_id_best: None,
+        }
+    }
+
+    /** sets the seed of the random number generator (random solutions, crossovers and tournaments) */
+    pub fn with_seed(mut self, seed:u64) -> Self {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /**
+    binds the search to a logger (to display the iterations and population sizes).
+    Takes a `std::sync::Weak` obtained with `Arc::downgrade(&logger)`.
+    */
+    pub fn bind_logger(mut self, logger_ref:Weak<MetricLogger>) -> Self {
+        if let Some(logger) = logger_ref.upgrade() {
+            // adds headers to the logger
+            let tmp = logger.register_headers([
+                format!("{:<15}","HGS iter"),
+                format!("{:<15}","pop (f/inf)"),
+                format!("{:<15}","best"),
+            ].to_vec());
+            self.logging_id_iter = Some(tmp[0]);
+            self.logging_id_pop = Some(tmp[1]);
+            self.logging_id_best = Some(tmp[2]);
+        }
+        // registers the logger
+        self.logger = logger_ref;
+        self
+    }
+
+    /** returns the current penalty coefficients */
+    pub fn penalties(&self) -> &[f64] { &self.penalties }
+
+    /** evaluates a solution and builds the corresponding individual */
+    fn evaluate(&mut self, sol:Sol) -> Individual<Sol> {
+        let mut space = self.space.lock();
+        let cost = space.cost(&sol);
+        let violations = space.violations(&sol);
+        if self.penalties.len() != violations.len() {
+            self.penalties = vec![self.params.initial_penalty; violations.len()];
+            self.feasibility_stats = vec![(0,0); violations.len()];
+        }
+        let mut res = Individual {
+            sol, cost, violations, penalized_cost: cost, biased_fitness: 0., is_clone: false,
+            id: 0, proximity: Vec::new(),
+        };
+        res.update_penalized_cost(&self.penalties);
+        res
+    }
+
+    /** applies the local search to the solution given a penalty factor */
+    fn educate<SC:StoppingCriterion>(&mut self, sol:&mut Sol, penalty_factor:f64, stopping_criterion:&SC) {
+        if self.penalties.is_empty() { // first education: initializes the penalties
+            let nb_constraints = self.space.lock().violations(sol).len();
+            self.penalties = vec![self.params.initial_penalty; nb_constraints];
+            self.feasibility_stats = vec![(0,0); nb_constraints];
+        }
+        let penalties:Vec<f64> = self.penalties.iter().map(|p| p*penalty_factor).collect();
+        self.local_search.set_penalties(&penalties);
+        self.local_search.improve(sol, stopping_criterion.clone());
+    }
+
+    /** inserts an individual in its subpopulation and updates the best known solution.
+    Returns true iff it is a new best known solution */
+    fn add_individual(&mut self, individual:Individual<Sol>) -> bool {
+        let mut is_new_best = false;
+        let mut space = self.space.lock();
+        if individual.is_feasible() {
+            if self.manager.is_better(individual.cost) {
+                self.manager.update_best(individual.sol.clone(), individual.cost);
+                is_new_best = true;
+            }
+            self.feasible.insert(individual, &mut *space);
+            if self.feasible.len() > self.params.mu + self.params.lambda {
+                self.feasible.select_survivors(self.params.mu, self.params.nb_elite, self.params.nb_close);
+            }
+        } else {
+            self.infeasible.insert(individual, &mut *space);
+            if self.infeasible.len() > self.params.mu + self.params.lambda {
+                self.infeasible.select_survivors(self.params.mu, self.params.nb_elite, self.params.nb_close);
+            }
+        }
+        is_new_best
+    }
+
+    /** records the feasibility of an educated individual (for penalty management) */
+    fn record_feasibility(&mut self, individual:&Individual<Sol>) {
+        for (stat, v) in self.feasibility_stats.iter_mut().zip(individual.violations.iter()) {
+            stat.0 += 1;
+            if *v <= 0. { stat.1 += 1; }
+        }
+    }
+
+    /**
+    adapts the penalties to reach the target proportion of feasible individuals:
+    increases the penalty of a constraint if too few individuals satisfy it, decreases it otherwise.
+    */
+    fn manage_penalties(&mut self) {
+        for (p, stat) in self.penalties.iter_mut().zip(self.feasibility_stats.iter_mut()) {
+            if stat.0 > 0 {
+                let ratio = stat.1 as f64 / stat.0 as f64;
+                if ratio < self.params.target_feasible - 0.05 {
+                    *p = (*p * 1.2).min(100_000.);
+                } else if ratio > self.params.target_feasible + 0.05 {
+                    *p = (*p * 0.85).max(0.1);
+                }
+            }
+            *stat = (0,0);
+        }
+        // penalized costs of infeasible individuals changed
+        for individual in self.infeasible.individuals.iter_mut() {
+            individual.update_penalized_cost(&self.penalties);
+        }
+        self.infeasible.sort();
+    }
+
+    /** selects an individual by binary tournament on the biased fitness */
+    fn binary_tournament(&mut self) -> Sol {
+        let n_feas = self.feasible.len();
+        let n = n_feas + self.infeasible.len();
+        let i = self.rng.gen_range(0..n);
+        let j = self.rng.gen_range(0..n);
+        let a = if i < n_feas { &self.feasible.individuals[i] } else { &self.infeasible.individuals[i-n_feas] };
+        let b = if j < n_feas { &self.feasible.individuals[j] } else { &self.infeasible.individuals[j-n_feas] };
+        if a.biased_fitness <= b.biased_fitness { a.sol.clone() } else { b.sol.clone() }
+    }
+
+    /** (re)generates the initial population */
+    fn initialize_population<SC:StoppingCriterion>(&mut self, stopping_criterion:&SC) {
+        // a cancelled search keeps its population
+        if stopping_criterion.is_finished() { return; }
+        self.feasible = SubPopulation::new();
+        self.infeasible = SubPopulation::new();
+        for _ in 0..4*self.params.mu {
+            if stopping_criterion.is_finished() { break; }
+            let mut sol = self.space.lock().random_solution(&mut self.rng);
+            self.educate(&mut sol, 1., stopping_criterion);
+            let individual = self.evaluate(sol);
+            self.record_feasibility(&individual);
+            self.add_individual(individual);
+        }
+        self.update_biased_fitnesses();
+    }
+
+    fn update_biased_fitnesses(&mut self) {
+        self.feasible.update_biased_fitness(self.params.nb_elite, self.params.nb_close);
+        self.infeasible.update_biased_fitness(self.params.nb_elite, self.params.nb_close);
+    }
+
+    fn log_iteration(&self) {
+        if let Some(logger) = self.logger.upgrade() {
+            if let Some(id) = self.logging_id_iter {
+                logger.update_metric(id, Metric::LargeNumber(self.nb_iter as f64));
+            }
+            if let Some(id) = self.logging_id_pop {
+                logger.update_metric(id, Metric::Text(
+                    format!("{}/{}", self.feasible.len(), self.infeasible.len())
+                ));
+            }
+            if let Some(id) = self.logging_id_best {
+                logger.update_metric(id, match self.manager.best_val() {
+                    None => Metric::Text("-".to_string()),
+                    Some(v) => Metric::Text(format!("{:.2}", v)),
+                });
+            }
+            logger.request_logging();
+        }
+    }
+}
+
+
+impl<Sol, Space, LS, H:SpaceHandle<Space>> SearchAlgorithm<Sol, f64> for HybridGeneticSearch<Sol, Space, LS, H>
+where
+    Sol: Clone,
+    Space: HgsSpace<Sol>,
+    LS: LocalSearch<Sol>,
+{
+    fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
+        // the penalized costs are minimized (registers the warm starts)
+        self.manager.set_sense(ObjectiveSense::Minimize);
+        self.initialize_population(&stopping_criterion);
+        let mut nb_iter_non_improving:usize = 0;
+        while !stopping_criterion.is_finished() {
+            if self.feasible.len() + self.infeasible.len() == 0 { break; }
+            // selection and crossover
+            let p1 = self.binary_tournament();
+            let p2 = self.binary_tournament();
+            let mut offspring = self.space.lock().crossover(&p1, &p2, &mut self.rng);
+            // education
+            self.educate(&mut offspring, 1., &stopping_criterion);
+            let individual = self.evaluate(offspring.clone());
+            self.record_feasibility(&individual);
+            let is_feasible = individual.is_feasible();
+            let mut is_new_best = self.add_individual(individual);
+            // stops before the repair if the search was cancelled during the education
+            if stopping_criterion.is_finished() { break; }
+            // repair (part of) the infeasible offspring
+            if !is_feasible && self.rng.gen::<f64>() < self.params.repair_probability {
+                self.educate(&mut offspring, 10., &stopping_criterion);
+                let repaired = self.evaluate(offspring);
+                if repaired.is_feasible() {
+                    is_new_best = self.add_individual(repaired) || is_new_best;
+                }
+            }
+            self.update_biased_fitnesses();
+            // updating count of nb iterations that are not improving
+            if is_new_best {
+                nb_iter_non_improving = 0;
+            } else {
+                nb_iter_non_improving += 1;
+            }
+            self.nb_iter += 1;
+            self.manager.notify_iteration_end();
+            // penalty management
+            if self.nb_iter.is_multiple_of(self.params.penalty_management_frequency) {
+                self.manage_penalties();
+            }
+            // display information
+            if self.nb_iter.is_multiple_of(self.params.log_frequency) {
+                self.log_iteration();
+            }
+            // diversification: restart the population if the search stagnates
+            if nb_iter_non_improving >= self.params.nb_iter_no_improvement {
+                self.nb_restarts += 1;
+                nb_iter_non_improving = 0;
+                self.manager.notify_restart(&format!("restart {}", self.nb_restarts));
+                self.initialize_population(&stopping_criterion);
+            }
+        }
+        self.log_iteration();
+        // the genetic algorithm does not prove optimality
+        self.manager.finish(false);
+    }
+
+    fn get_manager(&mut self) -> &mut SearchManager<Sol, f64> { &mut self.manager }
+
+    fn status(&self) -> SearchStatus { self.manager.status() }
+
+    fn report(&self, report:&mut SearchReport) {
+        report.status = Some(self.status());
+        report.push(ReportSection::new("hgs")
+            .with_counter("nb_iter", self.nb_iter as u64)
+            .with_counter("nb_restarts", self.nb_restarts as u64)
+            .with_counter("nb_feasible", self.feasible.len() as u64)
+            .with_counter("nb_infeasible", self.infeasible.len() as u64)
+            .with_value("penalties", &self.penalties)
+            .with_parameters(&self.params)
+            .with_parameter("seed", self.seed)
+            .with_timing("searched", self.manager.elapsed_time().as_secs_f64())
+        );
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+    use crate::test_spaces::without_timings;
+
+    #[test]
+    fn broken_pairs_identical() {
+        assert_eq!(broken_pairs_distance(&[0,1,2,3], &[0,1,2,3]), 0.);
+    }
+
+    #[test]
+    fn broken_pairs_reversed() {
+        assert_eq!(broken_pairs_distance(&[0,1,2,3], &[3,2,1,0]), 1.);
+    }
+
+    #[test]
+    fn broken_pairs_partial() {
+        // (0,1) and (2,3) are kept, (1,2) is broken
+        let d = broken_pairs_distance(&[0,1,2,3], &[2,3,0,1]);
+        assert!((d - 1./3.).abs() < 1e-9);
+    }
+
+    /// permutations of 0..8: the cost is the displacement of the elements, the constraint
+    /// requires the first element to be smaller than the second one
+    #[derive(Debug, Default)]
+    struct Permutations {
+        /// number of distances computed
+        nb_distances: usize,
+    }
+
+    impl HgsSpace<Vec<usize>> for Permutations {
+        fn random_solution<R:Rng>(&mut self, rng:&mut R) -> Vec<usize> {
+            let mut res:Vec<usize> = (0..8).collect();
+            res.shuffle(rng);
+            res
+        }
+
+        fn crossover<R:Rng>(&mut self, p1:&Vec<usize>, p2:&Vec<usize>, rng:&mut R) -> Vec<usize> {
+            let prefix = &p1[..rng.gen_range(1..8)];
+            let mut res = prefix.to_vec();
+            res.extend(p2.iter().filter(|e| !prefix.contains(e)));
+            res
+        }
+
+        fn cost(&mut self, sol:&Vec<usize>) -> f64 {
+            sol.iter().enumerate().map(|(i, e)| (i as f64 - *e as f64).abs()).sum()
+        }
+
+        fn violations(&mut self, sol:&Vec<usize>) -> Vec<f64> {
+            vec![if sol[0] > sol[1] { 1. } else { 0. }]
+        }
+
+        fn distance(&mut self, a:&Vec<usize>, b:&Vec<usize>) -> f64 {
+            self.nb_distances += 1;
+            broken_pairs_distance(a, b)
+        }
+    }
+
+    /// education that does not change the solutions
+    #[derive(Debug)]
+    struct NoEducation;
+
+    impl LocalSearch<Vec<usize>> for NoEducation {
+        fn improve<SC:StoppingCriterion>(&mut self, _sol:&mut Vec<usize>, _stopping_criterion:SC) -> bool { false }
+    }
+
+    /// stops after a given number of checks (deterministic, unlike time limits)
+    #[derive(Debug, Clone)]
+    struct CheckBudget(Rc<std::cell::Cell<usize>>);
+
+    impl StoppingCriterion for CheckBudget {
+        fn is_finished(&self) -> bool {
+            let remaining = self.0.get();
+            self.0.set(remaining.saturating_sub(1));
+            remaining == 0
+        }
+    }
+
+    /// individual with a given solution, cost and violation (penalty 1)
+    fn individual(sol:Vec<usize>, cost:f64, violation:f64) -> Individual<Vec<usize>> {
+        let mut res = Individual {
+            sol, cost, violations: vec![violation], penalized_cost: 0., biased_fitness: 0., is_clone: false,
+            id: 0, proximity: Vec::new(),
+        };
+        res.update_penalized_cost(&[1.]);
+        res
+    }
+
+    #[test]
+    fn biased_fitness() {
+        let mut space = Permutations::default();
+        let mut population = SubPopulation::new();
+        population.insert(individual(vec![3,2,1,0], 3., 0.), &mut space);
+        population.insert(individual(vec![0,1,2,3], 2., 0.), &mut space);
+        population.insert(individual(vec![0,1,2,3], 1., 0.), &mut space);
+        // cost ranks: 0, 1/2, 1. diversity ranks (closest individual): 1/2, 1, 0. elite factor: 2/3
+        population.update_biased_fitness(1, 1);
+        let fitnesses:Vec<(f64, f64, bool)> = population.individuals.iter().map(|i| (i.cost, i.biased_fitness, i.is_clone)).collect();
+        let expected = [(1., 1./3., true), (2., 7./6., true), (3., 1., false)];
+        for (f, e) in fitnesses.iter().zip(expected.iter()) {
+            assert_eq!((f.0, f.2), (e.0, e.2));
+            assert!((f.1 - e.1).abs() < 1e-9);
+        }
+        // the clone with the worst biased fitness is removed first, then the worst individual
+        population.select_survivors(2, 1, 1);
+        assert_eq!(population.individuals.iter().map(|i| i.cost).collect::<Vec<_>>(), vec![1., 3.]);
+        assert_eq!(population.individuals.iter().map(|i| i.proximity.len()).collect::<Vec<_>>(), vec![1, 1]);
+        population.select_survivors(1, 1, 1);
+        assert_eq!(population.individuals.iter().map(|i| i.cost).collect::<Vec<_>>(), vec![1.]);
+        // the distance between two individuals is computed once, when the second one is inserted
+        assert_eq!(space.nb_distances, 3);
+    }
+
+    /// evaluates a solution and inserts it in the population
+    fn add(algo:&mut HybridGeneticSearch<Vec<usize>, Permutations, NoEducation>, sol:Vec<usize>) -> bool {
+        let individual = algo.evaluate(sol);
+        algo.add_individual(individual)
+    }
+
+    #[test]
+    fn population_management() {
+        let params = HgsParams { mu: 2, lambda: 1, nb_elite: 1, nb_close: 1, ..HgsParams::default() };
+        let mut algo = HybridGeneticSearch::new(Rc::new(RefCell::new(Permutations::default())), NoEducation, params);
+        algo.manager.set_sense(ObjectiveSense::Minimize);
+        // feasible individuals go to the feasible subpopulation and update the best known solution
+        assert!(add(&mut algo, vec![0,2,1,3,4,5,6,7]));
+        assert!(add(&mut algo, vec![0,1,2,3,4,5,6,7]));
+        assert!(!add(&mut algo, vec![7,6,5,4,3,2,1,0]));
+        assert!(!add(&mut algo, vec![1,0,2,3,4,5,6,7]));
+        assert_eq!(algo.infeasible.len(), 2);
+        for sol in [vec![0,1,3,2,4,5,6,7], vec![0,1,2,3,4,5,7,6]] {
+            assert!(!add(&mut algo, sol));
+        }
+        // the subpopulation is reduced to mu individuals when it exceeds mu+lambda
+        assert_eq!(algo.feasible.len(), 2);
+        assert_eq!(*algo.manager.best_val(), Some(0.));
+        assert_eq!(algo.feasible.individuals[0].sol, vec![0,1,2,3,4,5,6,7]);
+    }
+
+    #[test]
+    fn penalty_adaptation() {
+        let params = HgsParams { initial_penalty: 0.5, target_feasible: 0.2, ..HgsParams::default() };
+        let mut algo = HybridGeneticSearch::new(Rc::new(RefCell::new(Permutations::default())), NoEducation, params);
+        let mut x = algo.evaluate(vec![1,0,2,3,4,5,6,7]);
+        x.cost = 0.;
+        x.violations = vec![10.];
+        let mut y = algo.evaluate(vec![2,0,1,3,4,5,6,7]);
+        y.cost = 5.;
+        y.violations = vec![1.];
+        x.update_penalized_cost(algo.penalties());
+        y.update_penalized_cost(algo.penalties());
+        algo.add_individual(x);
+        algo.add_individual(y);
+        assert_eq!(algo.infeasible.individuals.iter().map(|i| i.cost).collect::<Vec<_>>(), vec![0., 5.]);
+        // too few individuals satisfy the constraint: the penalty increases
+        algo.feasibility_stats = vec![(10, 0)];
+        algo.manage_penalties();
+        assert!((algo.penalties()[0] - 0.6).abs() < 1e-9);
+        // the infeasible individuals are evaluated with the new penalty and sorted again
+        assert_eq!(algo.infeasible.individuals.iter().map(|i| i.cost).collect::<Vec<_>>(), vec![5., 0.]);
+        assert_eq!(algo.feasibility_stats, vec![(0, 0)]);
+        // close to the target: the penalty is kept
+        algo.feasibility_stats = vec![(10, 2)];
+        algo.manage_penalties();
+        assert!((algo.penalties()[0] - 0.6).abs() < 1e-9);
+        // too many individuals satisfy the constraint: the penalty decreases (down to 0.1)
+        for _ in 0..20 {
+            algo.feasibility_stats = vec![(10, 10)];
+            algo.manage_penalties();
+        }
+        assert!((algo.penalties()[0] - 0.1).abs() < 1e-9);
+    }
+
+    fn seeded_run(seed:Option<u64>) -> serde_json::Value {
+        let params = HgsParams { mu: 4, lambda: 4, nb_elite: 1, nb_close: 2, ..HgsParams::default() };
+        let mut algo = HybridGeneticSearch::new(Rc::new(RefCell::new(Permutations::default())), NoEducation, params);
+        if let Some(s) = seed { algo = algo.with_seed(s); }
+        algo.run(CheckBudget(Rc::new(std::cell::Cell::new(500))));
+        let mut json = serde_json::json!({});
+        algo.json_statistics(&mut json);
+        json["best"] = serde_json::json!(algo.manager.best());
+        without_timings(json)
+    }
+
+    #[test]
+    fn seeded_runs() {
+        assert_eq!(seeded_run(Some(7)), seeded_run(Some(7)));
+        assert_eq!(seeded_run(Some(7))["hgs_seed"], serde_json::json!(7));
+        assert_eq!(seeded_run(None), seeded_run(Some(DEFAULT_SEED)));
+        assert!(seeded_run(Some(7))["best"].is_array());
+    }
+}
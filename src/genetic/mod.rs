@@ -1 +1,2 @@
-// pub mod simple;
\ No newline at end of file
+/** Hybrid Genetic Search (HGS) with diversity-managed population and adaptive penalties */
+pub mod hgs;
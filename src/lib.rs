@@ -25,6 +25,12 @@
 // #![warn(clippy::cast_precision_loss)]
 // #![warn(clippy::cast_sign_loss)]
 
+// the derive macros refer to this crate as `::dogs` (also from inside it)
+extern crate self as dogs;
+
+/// re-exported for the code generated by the derive macros
+pub use serde_json;
+
 // files
 /**
 SearchAlgorithm trait definition + StoppingCriterion trait with some useful stopping criteria
@@ -36,18 +42,46 @@ Implementation of the search manager. Keeps the best-known objective and state o
 */
 pub mod search_manager;
 
+/**
+SearchReport: structured statistics of a search (one section per combinator), exported to JSON or CSV
+*/
+pub mod search_report;
+
+/**
+SearchConfig: declarative description (JSON) of a search (algorithm, combinator stack, stopping
+criteria) assembled at runtime
+*/
+pub mod search_config;
+
 /**
 Various search space related traits (SearchSpace, GuidedSpace, *etc.*)
 */
 pub mod search_space;
 
+/**
+SpaceHandle trait: shared ownership of the search space by the algorithms (`Rc<RefCell<_>>` or
+`Arc<Mutex<_>>` to run searches on worker threads)
+*/
+pub mod space_handle;
+
+/**
+Implementation of the Pareto manager. Keeps the non-dominated goals of a bi-objective search.
+*/
+pub mod pareto_manager;
+
+/**
+Objective trait: objective values (integers, floats, lexicographic tuples) usable by the statistics
+and bounding combinators
+*/
+pub mod objective;
+
 /**
 Implements the metric logger. Allows the algorithm to display logs of its performance through time.
 */
 pub mod metric_logger;
 
 /**
-Search space decorator traits + procedural macros
+Search space decorator traits + procedural macros + description of the combinator stacks
 */
 pub mod search_combinator;
 
@@ -79,3 +113,7 @@ various useful data-structures for search algorithms
 */
 pub mod data_structures;
 
+/** search spaces (and helpers) shared by the unit tests of the modules */
+#[cfg(test)]
+mod test_spaces;
+
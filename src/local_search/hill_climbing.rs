@@ -0,0 +1,99 @@
+use std::marker::PhantomData;
+
+use crate::search_algorithm::StoppingCriterion;
+use crate::local_search::local_search_space::{LocalSearch, Neighborhood};
+
+/**
+Defines the move selection policy of the hill climbing.
+*/
+#[derive(Debug, Clone)]
+pub enum ImprovementPolicy {
+    /// applies the first improving move found
+    FirstImprovement,
+    /// evaluates every move and applies the best one
+    BestImprovement,
+}
+
+/**
+Hill climbing local search. Applies improving moves of the neighborhood until no improving move
+exists (local optimum) or the stopping criterion is reached.
+*/
+#[derive(Debug)]
+pub struct HillClimbing<Nbh, M> {
+    neighborhood: Nbh,
+    policy: ImprovementPolicy,
+    /// maximum number of moves applied in each call of improve (None: no limit)
+    max_iter: Option<u64>,
+    nb_moves: u64,
+    phantom_m: PhantomData<M>,
+}
+
+impl<Nbh, M> HillClimbing<Nbh, M> {
+    /** builds the hill climbing given a neighborhood and a move selection policy */
+    pub fn new(neighborhood:Nbh, policy:ImprovementPolicy) -> Self {
+        Self {
+            neighborhood,
+            policy,
+            max_iter: None,
+            nb_moves: 0,
+            phantom_m: PhantomData,
+        }
+    }
+
+    /** limits the number of moves applied in each call of improve (iteration budget) */
+    pub fn with_max_iterations(mut self, max_iter:u64) -> Self {
+        self.max_iter = Some(max_iter);
+        self
+    }
+
+    /** returns the number of moves applied so far */
+    pub fn nb_moves(&self) -> u64 { self.nb_moves }
+
+    /** returns the underlying neighborhood */
+    pub fn neighborhood(&mut self) -> &mut Nbh { &mut self.neighborhood }
+}
+
+impl<Sol, Nbh, M> LocalSearch<Sol> for HillClimbing<Nbh, M>
+where Nbh: Neighborhood<Sol, M> {
+    fn improve<SC:StoppingCriterion>(&mut self, sol:&mut Sol, stopping_criterion:SC) -> bool {
+        let mut improved = false;
+        let mut nb_iter:u64 = 0;
+        while !stopping_criterion.is_finished() && self.max_iter.is_none_or(|m| nb_iter < m) {
+            // select an improving move
+            let mut selected:Option<(M, f64)> = None;
+            for m in self.neighborhood.moves(sol) {
+                // stops scanning the neighborhood if the search is cancelled (the best move found so far is applied)
+                if stopping_criterion.is_finished() { break; }
+                let delta = self.neighborhood.delta(sol, &m);
+                if delta >= 0. { continue; }
+                match (&self.policy, &selected) {
+                    (ImprovementPolicy::FirstImprovement, _) => {
+                        selected = Some((m, delta));
+                        break;
+                    },
+                    (ImprovementPolicy::BestImprovement, Some((_, best_delta))) => {
+                        if delta < *best_delta { selected = Some((m, delta)); }
+                    },
+                    (ImprovementPolicy::BestImprovement, None) => {
+                        selected = Some((m, delta));
+                    }
+                }
+            }
+            // apply it, or stop if the solution is a local optimum
+            match selected {
+                None => { break; },
+                Some((m, _)) => {
+                    self.neighborhood.apply(sol, &m);
+                    self.nb_moves += 1;
+                    nb_iter += 1;
+                    improved = true;
+                }
+            }
+        }
+        improved
+    }
+
+    fn set_penalties(&mut self, penalties:&[f64]) {
+        self.neighborhood.set_penalties(penalties);
+    }
+}
@@ -0,0 +1,48 @@
+use crate::search_algorithm::StoppingCriterion;
+
+/**
+Defines a local search, *i.e.* an improvement procedure applied to a complete solution.
+Used for instance to educate individuals in genetic algorithms or to improve new-best-known
+solutions found by a tree search.
+*/
+pub trait LocalSearch<Sol> {
+    /**
+    improves the solution (in place) until a local optimum is reached or the stopping criterion
+    is met. Returns true iff the solution was improved.
+    */
+    fn improve<SC:StoppingCriterion>(&mut self, sol:&mut Sol, stopping_criterion:SC) -> bool;
+
+    /**
+    sets the penalty coefficients used to evaluate constraint violations
+    (does nothing if the local search does not handle infeasible solutions)
+    */
+    fn set_penalties(&mut self, _penalties:&[f64]) {}
+}
+
+
+/**
+Defines a neighborhood over solutions. A neighborhood is defined by a set of moves that can be
+evaluated (delta evaluation) and applied to a solution.
+*/
+pub trait Neighborhood<Sol, M> {
+    /**
+    returns the moves applicable to the solution
+    */
+    fn moves(&mut self, sol:&Sol) -> Vec<M>;
+
+    /**
+    returns the variation of the (penalized) objective if the move is applied.
+    A negative value indicates an improving move.
+    */
+    fn delta(&mut self, sol:&Sol, m:&M) -> f64;
+
+    /**
+    applies the move to the solution
+    */
+    fn apply(&mut self, sol:&mut Sol, m:&M);
+
+    /**
+    sets the penalty coefficients used in the delta evaluation of infeasible solutions
+    */
+    fn set_penalties(&mut self, _penalties:&[f64]) {}
+}
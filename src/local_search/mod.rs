@@ -0,0 +1,8 @@
+/** local search trait (improvement procedure) and neighborhood definitions */
+pub mod local_search_space;
+
+/** generic hill climbing (first or best improvement) over a neighborhood */
+pub mod hill_climbing;
+
+/** path relinking between solutions represented as permutations or decision sequences */
+pub mod path_relinking;
@@ -0,0 +1,113 @@
+/**
+Defines a search space able to relink two solutions (used by the elite pool combinator to
+intensify the search between pool members).
+*/
+pub trait PathRelinkingSpace<Sol, B> {
+    /**
+    explores the path from the solution `from` to the solution `to` and returns the best
+    intermediate solution found along it with its cost (None if the path is empty).
+    The functions relink_sequence and relink_permutation help implementing it.
+    */
+    fn relink(&mut self, from:&Sol, to:&Sol) -> Option<(Sol, B)>;
+}
+
+
+/**
+Greedy path relinking between two decision sequences of the same length.
+A move sets a position of the current sequence to the value of the guiding sequence (`to`).
+At each step, the move leading to the best intermediate sequence (evaluated by `eval`, the
+smaller, the better) is applied. Returns the best intermediate sequence (distinct from both
+`from` and `to`) and its evaluation.
+*/
+pub fn relink_sequence<T, F>(from:&[T], to:&[T], eval:F) -> Option<(Vec<T>, f64)>
+where T:Clone+PartialEq, F:FnMut(&[T])->f64 {
+    debug_assert_eq!(from.len(), to.len());
+    greedy_relinking(from, to, eval, |current, target, i| {
+        current[i] = target[i].clone();
+    })
+}
+
+
+/**
+Greedy path relinking between two permutations of the same elements.
+A move places, at some position, the element of the guiding permutation (`to`) by swapping it
+with its current position. At each step, the move leading to the best intermediate permutation
+(evaluated by `eval`, the smaller, the better) is applied. Returns the best intermediate
+permutation (distinct from both `from` and `to`) and its evaluation.
+*/
+pub fn relink_permutation<T, F>(from:&[T], to:&[T], eval:F) -> Option<(Vec<T>, f64)>
+where T:Clone+PartialEq, F:FnMut(&[T])->f64 {
+    debug_assert_eq!(from.len(), to.len());
+    greedy_relinking(from, to, eval, |current, target, i| {
+        if let Some(j) = current.iter().position(|e| *e == target[i]) {
+            current.swap(i, j);
+        }
+    })
+}
+
+
+/**
+greedy relinking given a move operator that fixes the i-th position of the current solution
+to the value of the target solution.
+*/
+fn greedy_relinking<T, F, M>(from:&[T], to:&[T], mut eval:F, apply:M) -> Option<(Vec<T>, f64)>
+where T:Clone+PartialEq, F:FnMut(&[T])->f64, M:Fn(&mut Vec<T>, &[T], usize) {
+    let mut current:Vec<T> = from.to_vec();
+    let mut res:Option<(Vec<T>, f64)> = None;
+    loop {
+        let differences:Vec<usize> = (0..current.len())
+            .filter(|i| current[*i] != to[*i])
+            .collect();
+        // stops before reaching the guiding solution
+        if differences.len() <= 1 { break; }
+        let mut selected:Option<(Vec<T>, f64)> = None;
+        for i in differences {
+            let mut candidate = current.clone();
+            apply(&mut candidate, to, i);
+            if candidate == to { continue; }
+            let v = eval(&candidate);
+            if selected.as_ref().is_none_or(|(_,best_v)| v < *best_v) {
+                selected = Some((candidate, v));
+            }
+        }
+        match selected {
+            None => { break; },
+            Some((candidate, v)) => {
+                if res.as_ref().is_none_or(|(_,best_v)| v < *best_v) {
+                    res = Some((candidate.clone(), v));
+                }
+                current = candidate;
+            }
+        }
+    }
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_intermediate() {
+        // counts the number of ones (the smaller, the better)
+        let res = relink_sequence(&[1,1,1], &[0,0,0], |s| s.iter().sum::<i32>() as f64);
+        let (sol, v) = res.unwrap();
+        assert_eq!(v, 1.);
+        assert_eq!(sol.iter().sum::<i32>(), 1);
+    }
+
+    #[test]
+    fn permutation_intermediate() {
+        let res = relink_permutation(&[0,1,2,3], &[3,2,1,0], |s| s[0] as f64);
+        let (sol, v) = res.unwrap();
+        assert_eq!(v, 0.);
+        assert_eq!(sol.len(), 4);
+        assert_ne!(sol, vec![3,2,1,0]);
+    }
+
+    #[test]
+    fn adjacent_solutions() {
+        assert!(relink_sequence(&[0,1], &[0,0], |_| 0.).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use std::cell::RefCell;
+use std::sync::Mutex;
 use std::time::SystemTime;
 
 /**
@@ -16,6 +16,8 @@ pub enum Metric {
     Text(String),
     /// int pretty print: example: 1000000 -> "1.000.000"
     Int(i64),
+    /// float (3 decimals): example: 1.5 -> "1.500"
+    Float(f64),
 }
 
 /**
@@ -31,6 +33,7 @@ pub fn metric_to_string(m:&Metric) -> String {
         },
         Metric::Time(n) => { format!("  {:.3}", n) },
         Metric::Text(s) => { s.clone() },
+        Metric::Float(n) => { format!("{:.3}", n) },
         Metric::Int(n) => {
             let mut res:String = String::new();
             let mut tmp:i64 = *n;
@@ -54,7 +57,7 @@ pub fn metric_to_string(m:&Metric) -> String {
 
 
 /**
- * Implements a logger.
+ * Implements a logger (thread-safe: it can be shared with Arc between several searches).
  * It allows components to register headers and update values to display
  * **workflow:**
  *  1. a component registers to the MetricLogger. It provides the data names and the MetricLogger returns their IDs.
@@ -63,8 +66,9 @@ pub fn metric_to_string(m:&Metric) -> String {
  */
 #[derive(Debug)]
 pub struct MetricLogger {
-    headers: RefCell<Vec<String>>,  // maintains header order
-    metrics: RefCell<Vec<Metric>>, // maintains every up-to-date entry
+    headers: Mutex<Vec<String>>,  // maintains header order
+    metrics: Mutex<Vec<Metric>>, // maintains every up-to-date entry
+    description: Mutex<Option<String>>,  // displayed above the headers (e.g. combinator stack)
     t_start: SystemTime,
 }
 
@@ -75,8 +79,9 @@ impl Default for MetricLogger {
         headers.push(format!("{:<15}"," time (s)"));
         metrics.push(Metric::Empty);
         Self {
-            headers: RefCell::new(headers),
-            metrics: RefCell::new(metrics),
+            headers: Mutex::new(headers),
+            metrics: Mutex::new(metrics),
+            description: Mutex::new(None),
             t_start: SystemTime::now(),
         }
     }
@@ -89,8 +94,8 @@ impl MetricLogger {
      */
     pub fn request_logging(&self) {
         // display other metrics
-        let headers = self.headers.borrow();
-        let mut metrics = self.metrics.borrow_mut();
+        let headers = self.headers.lock().unwrap();
+        let mut metrics = self.metrics.lock().unwrap();
         // add time to metrics
         metrics[0] = Metric::Time(self.t_start.elapsed().unwrap().as_secs_f32());
         // for each entry in the metrics array, display it
@@ -107,8 +112,8 @@ impl MetricLogger {
      */
     pub fn register_headers(&self, headers:Vec<String>) -> Vec<usize> {
         let mut res = Vec::new();
-        let mut headers_ref = self.headers.borrow_mut();
-        let mut metrics_ref = self.metrics.borrow_mut();
+        let mut headers_ref = self.headers.lock().unwrap();
+        let mut metrics_ref = self.metrics.lock().unwrap();
         for h in headers {
             res.push(headers_ref.len());
             headers_ref.push(h);
@@ -121,21 +126,39 @@ impl MetricLogger {
      * updates a metric for further logging
      */
     pub fn update_metric(&self, id: usize, metric: Metric) {
-        let mut metrics = self.metrics.borrow_mut();
+        let mut metrics = self.metrics.lock().unwrap();
         metrics[id] = metric;
     }
 
 
     /**
-     * Displays metric headers. Should be called at the beginning of the search
+     * sets a description of the search displayed above the headers. For instance, the
+     * combinator stack: `logger.set_description(&space.describe().to_string())`
+     */
+    pub fn set_description(&self, description:&str) {
+        *self.description.lock().unwrap() = Some(description.to_string());
+    }
+
+    /**
+     * returns the description of the search (if any)
+     */
+    pub fn description(&self) -> Option<String> {
+        self.description.lock().unwrap().clone()
+    }
+
+    /**
+     * Displays metric headers (and the description of the search if any). Should be called
+     * at the beginning of the search
      */
     pub fn display_headers(&self) {
-        let mut size:usize = 0;
-        for h in self.headers.borrow().iter() {
-            print!("{}", h);
-            size += h.len();
+        let mut lines = Vec::new();
+        if let Some(description) = self.description.lock().unwrap().as_ref() {
+            lines.push(description.clone());
         }
-        println!();
-        println!("{}",("-".repeat(size)));
+        let headers = self.headers.lock().unwrap().concat();
+        let size = headers.len();
+        lines.push(headers);
+        lines.push("-".repeat(size));
+        println!("{}", lines.join("\n"));
     }
 }
\ No newline at end of file
@@ -0,0 +1,254 @@
+use std::fmt::Debug;
+
+use ordered_float::OrderedFloat;
+use serde::Serialize;
+
+use crate::metric_logger::{Metric, metric_to_string};
+use crate::search_space::ObjectiveSense;
+
+/**
+Objective values that can be used by the statistics and bounding combinators: they are totally
+ordered, serialized in the JSON statistics, displayed by the logger, and provide a relative gap
+between a primal value and a dual bound.
+Implemented for integers, OrderedFloat and lexicographic tuples (compared component by component).
+*/
+pub trait Objective: Ord+Copy+Debug+Serialize {
+    /** metric displayed by the logger */
+    fn to_metric(&self) -> Metric;
+
+    /**
+    relative gap between self (primal value) and a dual bound given the objective sense
+    (0 if the gap is closed, infinity if it cannot be measured)
+    */
+    fn gap(&self, dual:&Self, sense:ObjectiveSense) -> f64;
+
+    /** human-readable representation (used to display statistics) */
+    fn to_display(&self) -> String { metric_to_string(&self.to_metric()) }
+
+    /** number of components (one per level for lexicographic objectives) */
+    fn nb_components() -> usize where Self:Sized { 1 }
+
+    /** metric of each component (displayed in separate logger columns) */
+    fn component_metrics(&self) -> Vec<Metric> { vec![self.to_metric()] }
+
+    /** JSON value of each component */
+    fn component_values(&self) -> Vec<serde_json::Value> { vec![serde_json::json!(self)] }
+}
+
+macro_rules! impl_integer_objective {
+    ($($t:ty),*) => {
+        $(
+            // the casts are trivial for i64
+            #[allow(trivial_numeric_casts)]
+            impl Objective for $t {
+                fn to_metric(&self) -> Metric { Metric::Int(*self as i64) }
+
+                fn gap(&self, dual:&Self, sense:ObjectiveSense) -> f64 {
+                    sense.gap(*self as f64, *dual as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_objective!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Objective for OrderedFloat<f64> {
+    fn to_metric(&self) -> Metric { Metric::Float(self.0) }
+
+    fn gap(&self, dual:&Self, sense:ObjectiveSense) -> f64 { sense.gap(self.0, dual.0) }
+}
+
+impl Objective for OrderedFloat<f32> {
+    fn to_metric(&self) -> Metric { Metric::Float(f64::from(self.0)) }
+
+    fn gap(&self, dual:&Self, sense:ObjectiveSense) -> f64 { sense.gap(f64::from(self.0), f64::from(dual.0)) }
+}
+
+/**
+lexicographic objectives (for instance (makespan, flowtime)): the bound and g-cost of a node
+provide a value for each level, the gap is measured on the first component that differs.
+Nested tuples are flattened in the components.
+*/
+impl<A:Objective, B:Objective> Objective for (A, B) {
+    fn to_metric(&self) -> Metric {
+        Metric::Text(format!("({}, {})", self.0.to_display(), self.1.to_display()))
+    }
+
+    fn gap(&self, dual:&Self, sense:ObjectiveSense) -> f64 {
+        if self.0 != dual.0 { self.0.gap(&dual.0, sense) } else { self.1.gap(&dual.1, sense) }
+    }
+
+    fn nb_components() -> usize { A::nb_components() + B::nb_components() }
+
+    fn component_metrics(&self) -> Vec<Metric> {
+        let mut res = self.0.component_metrics();
+        res.extend(self.1.component_metrics());
+        res
+    }
+
+    fn component_values(&self) -> Vec<serde_json::Value> {
+        let mut res = self.0.component_values();
+        res.extend(self.1.component_values());
+        res
+    }
+}
+
+/// lexicographic objectives with three levels
+impl<A:Objective, B:Objective, C:Objective> Objective for (A, B, C) {
+    fn to_metric(&self) -> Metric {
+        Metric::Text(format!(
+            "({}, {}, {})", self.0.to_display(), self.1.to_display(), self.2.to_display()
+        ))
+    }
+
+    fn gap(&self, dual:&Self, sense:ObjectiveSense) -> f64 {
+        if self.0 != dual.0 { self.0.gap(&dual.0, sense) }
+        else if self.1 != dual.1 { self.1.gap(&dual.1, sense) }
+        else { self.2.gap(&dual.2, sense) }
+    }
+
+    fn nb_components() -> usize { A::nb_components() + B::nb_components() + C::nb_components() }
+
+    fn component_metrics(&self) -> Vec<Metric> {
+        let mut res = self.0.component_metrics();
+        res.extend(self.1.component_metrics());
+        res.extend(self.2.component_metrics());
+        res
+    }
+
+    fn component_values(&self) -> Vec<serde_json::Value> {
+        let mut res = self.0.component_values();
+        res.extend(self.1.component_values());
+        res.extend(self.2.component_values());
+        res
+    }
+}
+
+/**
+guide values that can be converted to a floating point number (the ant colony optimization and
+the pheromone combinator mix the guides with the pheromone).
+Implemented for integers, floats and OrderedFloat.
+*/
+pub trait GuideValue {
+    /** floating point value of the guide */
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! impl_guide_value {
+    ($($t:ty),*) => {
+        $(
+            // the casts are trivial for f64
+            #[allow(trivial_numeric_casts)]
+            impl GuideValue for $t {
+                fn to_f64(&self) -> f64 { *self as f64 }
+            }
+        )*
+    };
+}
+
+impl_guide_value!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl GuideValue for OrderedFloat<f64> {
+    fn to_f64(&self) -> f64 { self.0 }
+}
+
+impl GuideValue for OrderedFloat<f32> {
+    fn to_f64(&self) -> f64 { f64::from(self.0) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion};
+    use crate::search_manager::SearchStatus;
+    use crate::search_space::{SearchSpace, GuidedSpace, IteratorNeighborGeneration};
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::combinators::stats::StatTsCombinator;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+
+    #[test]
+    fn integer_gap() {
+        assert_eq!(110.gap(&100, ObjectiveSense::Minimize), 10./110.);
+        assert_eq!(100.gap(&110, ObjectiveSense::Maximize), 0.1);
+        assert_eq!(0.gap(&0, ObjectiveSense::Minimize), 0.);
+    }
+
+    #[test]
+    fn float_metric() {
+        let v = OrderedFloat(1.5);
+        assert_eq!(metric_to_string(&v.to_metric()), "1.500");
+        assert_eq!(v.gap(&OrderedFloat(1.5), ObjectiveSense::Minimize), 0.);
+    }
+
+    #[test]
+    fn lexicographic() {
+        let a = (10, OrderedFloat(3.));
+        let b = (10, OrderedFloat(2.));
+        assert!(b < a);
+        assert_eq!(a.gap(&b, ObjectiveSense::Minimize), 1./3.);
+        assert_eq!(a.to_display(), "(10, 3.000)");
+        assert_eq!(serde_json::json!(a), serde_json::json!([10, 3.0]));
+    }
+
+    #[test]
+    fn components() {
+        assert_eq!(<(i32, (i64, u8))>::nb_components(), 3);
+        let v = (1, (2, 3));
+        assert_eq!(v.component_values(), vec![serde_json::json!(1), serde_json::json!(2), serde_json::json!(3)]);
+        assert_eq!(v.component_metrics().len(), 3);
+    }
+
+    /// binary tree with a lexicographic cost: the first child costs (0,3), the second one (1,0)
+    #[derive(Debug)]
+    struct LexTree {
+        depth: usize,
+    }
+
+    impl SearchSpace<(usize, (i64, i64)), (i64, i64)> for LexTree {
+        fn initial(&mut self) -> (usize, (i64, i64)) { (0, (0, 0)) }
+
+        fn bound(&mut self, n:&(usize, (i64, i64))) -> (i64, i64) { n.1 }
+
+        fn goal(&mut self, n:&(usize, (i64, i64))) -> bool { n.0 == self.depth }
+
+        fn g_cost(&mut self, n:&(usize, (i64, i64))) -> (i64, i64) { n.1 }
+    }
+
+    impl GuidedSpace<(usize, (i64, i64)), (i64, i64)> for LexTree {
+        fn guide(&mut self, n:&(usize, (i64, i64))) -> (i64, i64) { n.1 }
+    }
+
+    impl IteratorNeighborGeneration<(usize, (i64, i64))> for LexTree {
+        type Cursor = std::vec::IntoIter<(usize, (i64, i64))>;
+
+        fn neighbors_cursor(&mut self, n:&mut (usize, (i64, i64))) -> Self::Cursor {
+            if n.0 == self.depth { return Vec::new().into_iter(); }
+            let (primary, secondary) = n.1;
+            vec![(n.0+1, (primary, secondary+3)), (n.0+1, (primary+1, secondary))].into_iter()
+        }
+
+        fn next_neighbor_from(&mut self, _n:&mut (usize, (i64, i64)), cursor:&mut Self::Cursor) -> Option<(usize, (i64, i64))> {
+            cursor.next()
+        }
+    }
+
+    #[test]
+    fn lexicographic_search() {
+        let lex_space = Rc::new(RefCell::new(StatTsCombinator::new(PruningCombinator::new(LexTree { depth: 3 }))));
+        let mut algo:DepthFirstSearch<_, (i64, i64), (i64, i64), _> = DepthFirstSearch::new(lex_space.clone());
+        algo.run(NeverStoppingCriterion::default());
+        assert_eq!(algo.status(), SearchStatus::Optimal);
+        assert_eq!(*algo.get_manager().best_val(), Some((0, 9)));
+        let mut manager_json = serde_json::json!({});
+        algo.get_manager().json_statistics(&mut manager_json);
+        assert_eq!(manager_json["primal_components"], serde_json::json!([0, 9]));
+        let mut space_json = serde_json::json!({});
+        lex_space.borrow().json_statistics(&mut space_json);
+        assert_eq!(space_json["best_primal"], serde_json::json!([0, 9]));
+        assert!(space_json["pruning_nb_pruned"].as_u64().unwrap() > 0);
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::search_manager::SearchProgress;
+
+/**
+ * point of a Pareto front: a non-dominated goal, its objectives and the time it was found
+ */
+#[derive(Debug, Clone)]
+pub struct ParetoPoint<N> {
+    /// goal node
+    pub node: N,
+    /// objectives (both minimized)
+    pub objectives: [f64; 2],
+    /// time the point was found (since the beginning of the search)
+    pub time: Duration,
+}
+
+/**
+ * returns true if a weakly dominates b (a is at least as good as b on both objectives)
+ */
+pub fn weakly_dominates(a:&[f64; 2], b:&[f64; 2]) -> bool {
+    a[0] <= b[0] && a[1] <= b[1]
+}
+
+/**
+ * hypervolume of a set of points (both objectives minimized) given a reference point:
+ * area dominated by the points and bounded by the reference point.
+ * Points that do not dominate the reference point are ignored.
+ */
+pub fn hypervolume_2d(points:&[[f64; 2]], reference:[f64; 2]) -> f64 {
+    let mut sorted:Vec<[f64; 2]> = points.iter()
+        .filter(|p| p[0] < reference[0] && p[1] < reference[1])
+        .copied()
+        .collect();
+    sorted.sort_by(|a,b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    let mut res = 0.;
+    let mut prev_y = reference[1];
+    for p in sorted {
+        if p[1] < prev_y {
+            res += (reference[0] - p[0]) * (prev_y - p[1]);
+            prev_y = p[1];
+        }
+    }
+    res
+}
+
+/**
+ * handles the Pareto front (archive of non-dominated goals) of a bi-objective search.
+ * Records the evolution of the hypervolume (given a reference point) through time.
+ */
+#[derive(Debug)]
+pub struct ParetoManager<N> {
+    t_start: Instant,
+    archive: Vec<ParetoPoint<N>>,
+    reference: [f64; 2],
+    /// (time, hypervolume) each time the archive is updated
+    hypervolume_history: Vec<(Duration, f64)>,
+    nb_insertions: u64,
+    nb_rejections: u64,
+    progress: Arc<SearchProgress<f64>>,
+}
+
+impl<N> ParetoManager<N> {
+    /**
+     * builds an empty archive given the reference point used to compute the hypervolume
+     * (for instance an upper bound of both objectives)
+     */
+    pub fn new(reference:[f64; 2]) -> Self {
+        Self {
+            t_start: Instant::now(),
+            archive: Vec::new(),
+            reference,
+            hypervolume_history: Vec::new(),
+            nb_insertions: 0,
+            nb_rejections: 0,
+            progress: Arc::new(SearchProgress::default()),
+        }
+    }
+
+    /**
+     * returns the progress of the search (number of expanded and generated nodes), shared with
+     * the stopping criteria
+     */
+    pub fn progress(&self) -> Arc<SearchProgress<f64>> { self.progress.clone() }
+
+    /**
+     * registers a node expansion that generated nb_generated children
+     */
+    pub fn notify_expansion(&mut self, nb_generated: usize) {
+        self.progress.notify_expansion(nb_generated);
+    }
+
+    /**
+     * returns the non-dominated points (sorted by increasing first objective)
+     */
+    pub fn archive(&self) -> &[ParetoPoint<N>] { &self.archive }
+
+    /**
+     * number of non-dominated points
+     */
+    pub fn len(&self) -> usize { self.archive.len() }
+
+    /**
+     * returns true iff the archive is empty
+     */
+    pub fn is_empty(&self) -> bool { self.archive.is_empty() }
+
+    /**
+     * returns the reference point used to compute the hypervolume
+     */
+    pub fn reference(&self) -> [f64; 2] { self.reference }
+
+    /**
+     * number of points inserted in the archive (including the ones dominated afterwards)
+     */
+    pub fn nb_insertions(&self) -> u64 { self.nb_insertions }
+
+    /**
+     * number of goals rejected by the archive (weakly dominated)
+     */
+    pub fn nb_rejections(&self) -> u64 { self.nb_rejections }
+
+    /**
+     * returns the elapsed time since the beginning of the search
+     */
+    pub fn elapsed_time(&self) -> Duration { self.t_start.elapsed() }
+
+    /**
+     * returns true iff the objectives are weakly dominated by a point of the archive
+     */
+    pub fn is_dominated(&self, objectives:&[f64; 2]) -> bool {
+        self.archive.iter().any(|p| weakly_dominates(&p.objectives, objectives))
+    }
+
+    /**
+     * inserts a goal in the archive if it is not weakly dominated, and removes the points it
+     * dominates. Returns true iff the goal was inserted.
+     */
+    pub fn insert(&mut self, node:N, objectives:[f64; 2]) -> bool {
+        if self.is_dominated(&objectives) {
+            self.nb_rejections += 1;
+            return false;
+        }
+        self.archive.retain(|p| !weakly_dominates(&objectives, &p.objectives));
+        let pos = self.archive.iter()
+            .position(|p| objectives[0] < p.objectives[0])
+            .unwrap_or(self.archive.len());
+        let time = self.t_start.elapsed();
+        self.archive.insert(pos, ParetoPoint { node, objectives, time });
+        self.nb_insertions += 1;
+        let hv = self.hypervolume();
+        self.hypervolume_history.push((time, hv));
+        true
+    }
+
+    /**
+     * hypervolume of the archive
+     */
+    pub fn hypervolume(&self) -> f64 {
+        let points:Vec<[f64; 2]> = self.archive.iter().map(|p| p.objectives).collect();
+        hypervolume_2d(&points, self.reference)
+    }
+
+    /**
+     * evolution of the hypervolume through time (one entry per archive update)
+     */
+    pub fn hypervolume_history(&self) -> &[(Duration, f64)] { &self.hypervolume_history }
+
+    /**
+     * exports the Pareto front (objectives and time each point was found) and the hypervolume
+     * through time
+     */
+    pub fn json_statistics(&self, json:&mut serde_json::Value) {
+        json["pareto_front"] = json!(self.archive.iter().map(|p| json!({
+            "objectives": p.objectives,
+            "time": p.time.as_secs_f32(),
+        })).collect::<Vec<serde_json::Value>>());
+        json["pareto_reference"] = json!(self.reference);
+        json["hypervolume"] = json!(self.hypervolume());
+        json["hypervolume_history"] = json!(self.hypervolume_history.iter().map(|(t,hv)| json!({
+            "time": t.as_secs_f32(),
+            "hypervolume": hv,
+        })).collect::<Vec<serde_json::Value>>());
+        json["pareto_nb_insertions"] = json!(self.nb_insertions);
+        json["pareto_nb_rejections"] = json!(self.nb_rejections);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hypervolume() {
+        assert_eq!(hypervolume_2d(&[[1., 3.], [2., 1.]], [4., 4.]), 3. + 2. * 2.);
+        assert_eq!(hypervolume_2d(&[[5., 1.]], [4., 4.]), 0.);
+    }
+
+    #[test]
+    fn keeps_non_dominated_points() {
+        let mut manager = ParetoManager::new([10., 10.]);
+        assert!(manager.insert("a", [2., 5.]));
+        assert!(manager.insert("b", [5., 2.]));
+        assert!(!manager.insert("c", [6., 6.]));
+        assert!(!manager.insert("a'", [2., 5.]));
+        assert!(manager.insert("d", [1., 4.]));
+        let nodes:Vec<&str> = manager.archive().iter().map(|p| p.node).collect();
+        assert_eq!(nodes, vec!["d", "b"]);
+        assert_eq!(manager.hypervolume_history().len(), 3);
+        assert_eq!(manager.hypervolume(), 9.*6. + 5.*2.);
+    }
+}
@@ -1,10 +1,16 @@
-use std::time::SystemTime;
+use std::time::{Duration, Instant};
 use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use serde_json::json;
 
-use crate::search_manager::SearchManager;
+use crate::search_manager::{SearchManager, SearchProgress, SearchStatus};
+use crate::search_report::SearchReport;
+use crate::objective::Objective;
 
 /**
  * Stopping criterion trait
@@ -17,6 +23,13 @@ pub trait StoppingCriterion:Clone {
     fn is_finished(&self) -> bool;
 }
 
+/**
+seed of the random number generators of the randomized algorithms (greedy tie-breaking, ant
+colony, hybrid genetic search) when no other seed is given (with_seed): runs are reproducible
+by default
+*/
+pub const DEFAULT_SEED:u64 = 0;
+
 /**
 stopping criterion that never stops
 */
@@ -28,12 +41,13 @@ impl StoppingCriterion for NeverStoppingCriterion {
 }
 
 /**
- * stops the search after a given amount of time searching
+ * stops the search after a given amount of time searching (wall-clock time measured with a
+ * monotonic clock)
  */
 #[derive(Debug, Clone)]
 pub struct TimeStoppingCriterion {
     /// starting time 
-    t_start: SystemTime,
+    t_start: Instant,
     /// maximum time after the beginning
     t_max: f32,
 }
@@ -44,7 +58,7 @@ impl TimeStoppingCriterion {
     */
     pub fn new(t_max:f32) -> Self {
         Self {
-            t_start: SystemTime::now(),
+            t_start: Instant::now(),
             t_max,
         }
     }
@@ -53,10 +67,414 @@ impl TimeStoppingCriterion {
 
 impl StoppingCriterion for TimeStoppingCriterion {
     fn is_finished(&self) -> bool {
-        self.t_start.elapsed().unwrap().as_secs_f32() >= self.t_max
+        self.t_start.elapsed().as_secs_f32() >= self.t_max
+    }
+}
+
+/**
+ * CPU clock measured by a CpuTimeStoppingCriterion
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuClock {
+    /// CPU time of the process: the time of all its threads is summed
+    Process,
+    /// CPU time of the thread reading the clock
+    Thread,
+}
+
+impl CpuClock {
+    /** returns the CPU time consumed so far, None if it is not available */
+    pub fn read(self) -> Option<Duration> {
+        match self {
+            CpuClock::Process => process_cpu_time(),
+            CpuClock::Thread => thread_cpu_time(),
+        }
+    }
+}
+
+/** reads a clock_gettime clock */
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn read_clock(clock_id:libc::clockid_t) -> Option<Duration> {
+    let mut t = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: t is a valid timespec owned by this function
+    let res = unsafe { libc::clock_gettime(clock_id, &mut t) };
+    if res != 0 { return None; }
+    Some(Duration::new(t.tv_sec as u64, t.tv_nsec as u32))
+}
+
+/**
+ * returns the CPU time (user + system) consumed by the process so far, summed over all its
+ * threads. Uses clock_gettime(CLOCK_PROCESS_CPUTIME_ID) on Unix, returns None if it is not
+ * available.
+ */
+#[cfg(unix)]
+pub fn process_cpu_time() -> Option<Duration> { read_clock(libc::CLOCK_PROCESS_CPUTIME_ID) }
+
+/**
+ * returns the CPU time (user + system) consumed by the calling thread so far.
+ * Uses clock_gettime(CLOCK_THREAD_CPUTIME_ID) on Unix, returns None if it is not available.
+ */
+#[cfg(unix)]
+pub fn thread_cpu_time() -> Option<Duration> { read_clock(libc::CLOCK_THREAD_CPUTIME_ID) }
+
+/**
+ * returns the CPU time consumed by the process so far (not available on this platform).
+ */
+#[cfg(not(unix))]
+pub fn process_cpu_time() -> Option<Duration> { None }
+
+/**
+ * returns the CPU time consumed by the calling thread so far (not available on this platform).
+ */
+#[cfg(not(unix))]
+pub fn thread_cpu_time() -> Option<Duration> { None }
+
+/**
+ * stops the search after a given amount of CPU time (not counting the time the process is
+ * descheduled). Falls back to the wall-clock time if the CPU time is not available (see
+ * process_cpu_time). The CPU time is read at most every 10 milliseconds.
+ *
+ * new() measures the CPU time of the process, which sums the time of all its threads: when
+ * several searches run in parallel, each of them consumes its budget faster. thread() measures
+ * the CPU time of a single thread: it must be created (and checked) on the thread running the
+ * search.
+ */
+#[derive(Debug)]
+pub struct CpuTimeStoppingCriterion {
+    /// clock measured
+    clock: CpuClock,
+    /// CPU time when the criterion was created
+    cpu_start: Option<Duration>,
+    /// wall-clock starting time (fallback)
+    t_start: Instant,
+    /// maximum CPU time (in seconds)
+    t_max: f32,
+    /// last CPU time read and when it was read
+    last_read: Mutex<(Instant, Duration)>,
+}
+
+impl Clone for CpuTimeStoppingCriterion {
+    fn clone(&self) -> Self {
+        Self {
+            clock: self.clock,
+            cpu_start: self.cpu_start,
+            t_start: self.t_start,
+            t_max: self.t_max,
+            last_read: Mutex::new(*self.last_read.lock().unwrap()),
+        }
+    }
+}
+
+impl CpuTimeStoppingCriterion {
+    /** stops after t_max seconds of CPU time of the process */
+    pub fn new(t_max:f32) -> Self { Self::with_clock(t_max, CpuClock::Process) }
+
+    /** stops after t_max seconds of CPU time of the calling thread */
+    pub fn thread(t_max:f32) -> Self { Self::with_clock(t_max, CpuClock::Thread) }
+
+    /** stops after t_max seconds of CPU time measured by the clock */
+    pub fn with_clock(t_max:f32, clock:CpuClock) -> Self {
+        let now = Instant::now();
+        let cpu_start = clock.read();
+        Self {
+            clock,
+            cpu_start,
+            t_start: now,
+            t_max,
+            last_read: Mutex::new((now, cpu_start.unwrap_or_default())),
+        }
+    }
+
+    /** CPU time consumed since the creation of the criterion */
+    pub fn elapsed(&self) -> Duration {
+        match self.cpu_start {
+            None => self.t_start.elapsed(),
+            Some(start) => {
+                let mut last_read = self.last_read.lock().unwrap();
+                if last_read.0.elapsed() >= Duration::from_millis(10) {
+                    let cpu = self.clock.read().unwrap_or(last_read.1);
+                    *last_read = (Instant::now(), cpu);
+                }
+                last_read.1.saturating_sub(start)
+            }
+        }
+    }
+}
+
+impl StoppingCriterion for CpuTimeStoppingCriterion {
+    fn is_finished(&self) -> bool {
+        self.elapsed().as_secs_f32() >= self.t_max
+    }
+}
+
+/**
+ * time budget that can be paused and resumed (for instance to exclude some processing from
+ * the budget). Its clones share the same budget, also across threads.
+ * A portfolio or a large neighborhood search can give a sub-search a slice of the remaining
+ * time using slice().
+ */
+#[derive(Debug, Clone)]
+pub struct PausableTimeStoppingCriterion {
+    /// maximum time (in seconds)
+    t_max: f32,
+    /// time consumed before the last resume and time of the last resume (None if paused)
+    state: Arc<Mutex<(Duration, Option<Instant>)>>,
+}
+
+impl PausableTimeStoppingCriterion {
+    /** creates a running budget of t_max seconds */
+    pub fn new(t_max:f32) -> Self {
+        Self {
+            t_max,
+            state: Arc::new(Mutex::new((Duration::default(), Some(Instant::now())))),
+        }
+    }
+
+    /** pauses the budget (does nothing if it is already paused) */
+    pub fn pause(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(t) = state.1.take() {
+            state.0 += t.elapsed();
+        }
+    }
+
+    /** resumes the budget (does nothing if it is already running) */
+    pub fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.1.is_none() {
+            state.1 = Some(Instant::now());
+        }
+    }
+
+    /** true iff the budget is paused */
+    pub fn is_paused(&self) -> bool { self.state.lock().unwrap().1.is_none() }
+
+    /** time consumed so far */
+    pub fn elapsed(&self) -> Duration {
+        let state = self.state.lock().unwrap();
+        match state.1 {
+            None => state.0,
+            Some(t) => state.0 + t.elapsed(),
+        }
+    }
+
+    /** remaining time (in seconds) */
+    pub fn remaining(&self) -> f32 {
+        (self.t_max - self.elapsed().as_secs_f32()).max(0.)
+    }
+
+    /** creates a new (running) budget of t_max seconds, limited by the remaining time */
+    pub fn slice(&self, t_max:f32) -> Self {
+        Self::new(t_max.min(self.remaining()))
+    }
+}
+
+impl StoppingCriterion for PausableTimeStoppingCriterion {
+    fn is_finished(&self) -> bool {
+        self.elapsed().as_secs_f32() >= self.t_max
     }
 }
 
+/**
+ * stops the search when it is cancelled from outside (another thread, a signal handler, etc.).
+ * The cancellation flag can be shared using token(), for instance:
+ * ```
+ * use std::sync::atomic::Ordering;
+ * use dogs::search_algorithm::{CancellationStoppingCriterion, StoppingCriterion};
+ *
+ * let stopping_criterion = CancellationStoppingCriterion::new();
+ * let token = stopping_criterion.token();
+ * std::thread::spawn(move || token.store(true, Ordering::Relaxed)).join().unwrap();
+ * assert!(stopping_criterion.is_finished());
+ * ```
+ */
+#[derive(Debug, Clone, Default)]
+pub struct CancellationStoppingCriterion {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationStoppingCriterion {
+    /** creates a stopping criterion that is not cancelled yet */
+    pub fn new() -> Self { Self::default() }
+
+    /** creates a stopping criterion from an existing cancellation flag */
+    pub fn from_token(cancelled:Arc<AtomicBool>) -> Self { Self { cancelled } }
+
+    /** returns the cancellation flag (storing true in it cancels the search) */
+    pub fn token(&self) -> Arc<AtomicBool> { self.cancelled.clone() }
+
+    /** cancels the search */
+    pub fn cancel(&self) { self.cancelled.store(true, Ordering::Relaxed); }
+}
+
+impl StoppingCriterion for CancellationStoppingCriterion {
+    fn is_finished(&self) -> bool { self.cancelled.load(Ordering::Relaxed) }
+}
+
+/**
+ * stops the search after a given number of expanded (or generated) nodes
+ */
+#[derive(Debug, Clone)]
+pub struct NodeBudgetStoppingCriterion<B> {
+    progress: Arc<SearchProgress<B>>,
+    max_nodes: u64,
+    count_generated: bool,
+}
+
+impl<B> NodeBudgetStoppingCriterion<B> {
+    /** stops after max_expanded node expansions of the search using the manager */
+    pub fn expanded<N>(manager:&SearchManager<N,B>, max_expanded:u64) -> Self
+    where N:Clone, B:PartialOrd+Copy {
+        Self { progress: manager.progress(), max_nodes: max_expanded, count_generated: false }
+    }
+
+    /** stops after max_generated generated nodes of the search using the manager */
+    pub fn generated<N>(manager:&SearchManager<N,B>, max_generated:u64) -> Self
+    where N:Clone, B:PartialOrd+Copy {
+        Self { progress: manager.progress(), max_nodes: max_generated, count_generated: true }
+    }
+
+    /** stops after max_expanded node expansions recorded by the progress (for instance the
+    progress of a ParetoManager) */
+    pub fn expanded_from(progress:Arc<SearchProgress<B>>, max_expanded:u64) -> Self {
+        Self { progress, max_nodes: max_expanded, count_generated: false }
+    }
+}
+
+impl<B:PartialOrd+Copy> StoppingCriterion for NodeBudgetStoppingCriterion<B> {
+    fn is_finished(&self) -> bool {
+        let nb_nodes = if self.count_generated {
+            self.progress.nb_generated()
+        } else {
+            self.progress.nb_expanded()
+        };
+        nb_nodes >= self.max_nodes
+    }
+}
+
+/**
+ * stops the search if the best known solution was not improved for some time or for some number
+ * of node expansions (counted from the beginning of the search if no solution is known)
+ */
+#[derive(Debug, Clone)]
+pub struct StagnationStoppingCriterion<B> {
+    progress: Arc<SearchProgress<B>>,
+    t_max: Option<f32>,
+    max_expanded: Option<u64>,
+}
+
+impl<B> StagnationStoppingCriterion<B> {
+    /** stops if no improvement was found during t_max seconds */
+    pub fn time<N>(manager:&SearchManager<N,B>, t_max:f32) -> Self
+    where N:Clone, B:PartialOrd+Copy {
+        Self { progress: manager.progress(), t_max: Some(t_max), max_expanded: None }
+    }
+
+    /** stops if no improvement was found during max_expanded node expansions */
+    pub fn expanded<N>(manager:&SearchManager<N,B>, max_expanded:u64) -> Self
+    where N:Clone, B:PartialOrd+Copy {
+        Self { progress: manager.progress(), t_max: None, max_expanded: Some(max_expanded) }
+    }
+}
+
+impl<B:PartialOrd+Copy> StoppingCriterion for StagnationStoppingCriterion<B> {
+    fn is_finished(&self) -> bool {
+        self.t_max.is_some_and(|t| self.progress.time_since_improvement().as_secs_f32() >= t)
+        || self.max_expanded.is_some_and(|e| self.progress.expanded_since_improvement() >= e)
+    }
+}
+
+/**
+ * stops the search as soon as the best known value reaches a target value
+ */
+#[derive(Debug, Clone)]
+pub struct TargetStoppingCriterion<B> {
+    progress: Arc<SearchProgress<B>>,
+    target: B,
+}
+
+impl<B:PartialOrd+Copy> TargetStoppingCriterion<B> {
+    /** stops when the search using the manager finds a solution at least as good as target */
+    pub fn new<N:Clone>(manager:&SearchManager<N,B>, target:B) -> Self {
+        Self { progress: manager.progress(), target }
+    }
+}
+
+impl<B:PartialOrd+Copy> StoppingCriterion for TargetStoppingCriterion<B> {
+    fn is_finished(&self) -> bool {
+        self.progress.best_val().is_some_and(|v| !self.progress.sense().is_better(&self.target, &v))
+    }
+}
+
+/**
+ * stops the search when the relative gap between the best known value and the dual bound
+ * (|primal-dual|/|primal|) is below a threshold. The dual bound is provided by the search space
+ * (for instance by the BoundingCombinator).
+ */
+#[derive(Debug, Clone)]
+pub struct GapStoppingCriterion<B> {
+    progress: Arc<SearchProgress<B>>,
+    max_gap: f64,
+}
+
+impl<B:Objective> GapStoppingCriterion<B> {
+    /** stops when the relative gap of the search using the manager is at most max_gap
+    (for instance 0.01 for 1%) */
+    pub fn new<N:Clone>(manager:&SearchManager<N,B>, max_gap:f64) -> Self {
+        Self { progress: manager.progress(), max_gap }
+    }
+}
+
+impl<B:Objective> StoppingCriterion for GapStoppingCriterion<B> {
+    fn is_finished(&self) -> bool {
+        match (self.progress.best_val(), self.progress.dual_bound()) {
+            (Some(best), Some(bound)) => {
+                best.gap(&bound, self.progress.sense()) <= self.max_gap
+            },
+            _ => false
+        }
+    }
+}
+
+/**
+ * stops the search as soon as one of the two stopping criteria is reached
+ * (can be nested to combine more criteria)
+ */
+#[derive(Debug, Clone)]
+pub struct AnyStoppingCriterion<SC1, SC2> {
+    a: SC1,
+    b: SC2,
+}
+
+impl<SC1:StoppingCriterion, SC2:StoppingCriterion> AnyStoppingCriterion<SC1, SC2> {
+    /** stops as soon as a or b is reached */
+    pub fn new(a:SC1, b:SC2) -> Self { Self { a, b } }
+}
+
+impl<SC1:StoppingCriterion, SC2:StoppingCriterion> StoppingCriterion for AnyStoppingCriterion<SC1, SC2> {
+    fn is_finished(&self) -> bool { self.a.is_finished() || self.b.is_finished() }
+}
+
+/**
+ * stops the search when both stopping criteria are reached
+ * (can be nested to combine more criteria)
+ */
+#[derive(Debug, Clone)]
+pub struct AllStoppingCriterion<SC1, SC2> {
+    a: SC1,
+    b: SC2,
+}
+
+impl<SC1:StoppingCriterion, SC2:StoppingCriterion> AllStoppingCriterion<SC1, SC2> {
+    /** stops when both a and b are reached */
+    pub fn new(a:SC1, b:SC2) -> Self { Self { a, b } }
+}
+
+impl<SC1:StoppingCriterion, SC2:StoppingCriterion> StoppingCriterion for AllStoppingCriterion<SC1, SC2> {
+    fn is_finished(&self) -> bool { self.a.is_finished() && self.b.is_finished() }
+}
+
 /**
  * A search algorithm has a "run" method that runs until a stopping_criterion is reached
  */
@@ -73,14 +491,150 @@ pub trait SearchAlgorithm<N, B> {
     fn get_manager(&mut self) -> &mut SearchManager<N,B>;
 
     /**
-     * returns true if the optimal value is found (thus we can stop the search). False by default
+     * warm start: seeds the search with a known solution and its value. It is notified to the
+     * search space (SearchSpace::notify_incumbent) when the search starts
+     */
+    fn warm_start(&mut self, node:N, value:B) where N:Clone, B:PartialOrd+Copy {
+        self.get_manager().warm_start(node, value);
+    }
+
+    /**
+     * warm start: seeds the search with an upper bound of the optimal value (a lower bound for
+     * maximization problems), for instance the value of a solution found by another method. It is notified to the search space
+     * (SearchSpace::notify_incumbent) when the search starts. If no solution of the search
+     * improves it, the status can be Optimal while no solution node is known
      */
-    fn is_optimal(&self) -> bool { false }
+    fn warm_start_value(&mut self, value:B) where N:Clone, B:PartialOrd+Copy {
+        self.get_manager().set_upper_bound(value);
+    }
 
     /**
-    provides various statistics of events that occured during the search.
+     * returns the status of the search (Unknown by default)
+     */
+    fn status(&self) -> SearchStatus { SearchStatus::Unknown }
+
+    /**
+     * returns true if the optimal value is found and proven (thus we can stop the search)
+     */
+    fn is_optimal(&self) -> bool { self.status() == SearchStatus::Optimal }
+
+    /**
+    provides various statistics of events that occured during the search
+    (the report of the algorithm written as flat statistics, see SearchReport::write_statistics).
     */
-    fn json_statistics(&self, json:&mut serde_json::Value) { json["is_optimal"] = json!(self.is_optimal()) }
+    fn json_statistics(&self, json:&mut serde_json::Value) {
+        SearchReport::statistics(|r| self.report(r), json);
+    }
+
+    /**
+    registers the status of the search (and the statistics of the algorithm) in a search report.
+    The search space adds the sections of the combinators (SearchSpace::report).
+    */
+    fn report(&self, report:&mut SearchReport) {
+        report.status = Some(self.status());
+    }
+}
+
+/**
+ * outcome of a step of a stepwise search
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// the step budget is consumed, the search can continue
+    Running,
+    /// nothing is left to explore (finish() should be called)
+    Finished,
+}
+
+/**
+ * A stepwise search can be run a few node expansions at a time (for instance to embed it in an
+ * event loop or a cooperative scheduler). Its state (beam, open list, stack, etc.) persists
+ * between the steps:
+ *  - init() starts the search
+ *  - step(max_expansions) expands at most max_expansions nodes
+ *  - finish() ends the search (and proves optimality if nothing is left to explore)
+ */
+pub trait StepwiseSearch<N, B>: SearchAlgorithm<N, B> {
+    /**
+     * starts the search (the previous state is discarded)
+     */
+    fn init(&mut self);
+
+    /**
+     * expands at most max_expansions nodes
+     */
+    fn step(&mut self, max_expansions:usize) -> StepOutcome;
+
+    /**
+     * ends the search (can be called before the search is finished)
+     */
+    fn finish(&mut self);
+
+    /**
+     * runs one node expansion at a time until the stopping_criterion is reached
+     * (SearchAlgorithm::run of the stepwise searches)
+     */
+    fn run_stepwise<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
+        self.init();
+        while !stopping_criterion.is_finished() && self.step(1) == StepOutcome::Running {}
+        self.finish();
+    }
+}
+
+/**
+ * A checkpointable search can export its state (frontier, best known solution and state of the
+ * search space) and resume from it later, for instance after a reboot of the machine.
+ * resume() replaces init(): the search then continues with step() and finish() as if it was
+ * never interrupted.
+ */
+pub trait CheckpointableSearch<N, B>: StepwiseSearch<N, B> {
+    /**
+     * exports the state of the search
+     */
+    fn checkpoint(&self) -> serde_json::Value;
+
+    /**
+     * restores the state of the search from a checkpoint (built by checkpoint())
+     */
+    fn resume(&mut self, checkpoint:&serde_json::Value) -> Result<(), serde_json::Error>;
+
+    /**
+     * writes a checkpoint in a file. The file is replaced at once: the previous checkpoint
+     * stays valid if the process stops while writing.
+     */
+    fn save_checkpoint(&self, path:&Path) -> io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, self.checkpoint().to_string())?;
+        fs::rename(&tmp, path)
+    }
+
+    /**
+     * resumes the search from a checkpoint file
+     */
+    fn load_checkpoint(&mut self, path:&Path) -> io::Result<()> {
+        let checkpoint:serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        self.resume(&checkpoint)?;
+        Ok(())
+    }
+
+    /**
+     * runs until the stopping_criterion is reached, and writes a checkpoint in a file every
+     * period (and when the search stops). If the file exists, the search resumes from it.
+     */
+    fn run_with_checkpoints<SC:StoppingCriterion>(&mut self, stopping_criterion:SC, path:&Path, period:Duration)
+    -> io::Result<()> {
+        if path.exists() { self.load_checkpoint(path)?; } else { self.init(); }
+        let mut last_checkpoint = Instant::now();
+        while !stopping_criterion.is_finished() && self.step(1) == StepOutcome::Running {
+            if last_checkpoint.elapsed() >= period {
+                self.save_checkpoint(path)?;
+                last_checkpoint = Instant::now();
+            }
+        }
+        self.save_checkpoint(path)?;
+        self.finish();
+        Ok(())
+    }
 }
 
 /**
@@ -88,9 +642,406 @@ pub trait SearchAlgorithm<N, B> {
  (for instance beam search, MBA*, etc.)
  useful for iterative beam search, iterative MBA*, etc.
  */
-pub trait BuildableWithInteger<Space> {
+pub trait BuildableWithInteger<Space, H=Rc<RefCell<Space>>> {
     /**
     constructor taking an integer as a parameter.
     */
-    fn create_with_integer(s:Rc<RefCell<Space>>, d:usize) -> Self;
-}
\ No newline at end of file
+    fn create_with_integer(s:H, d:usize) -> Self;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_space::{SearchSpace, ObjectiveSense};
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::combinators::stats::StatTsCombinator;
+    use crate::combinators::solution_pool::SolutionPoolCombinator;
+    use crate::combinators::lds::LDSCombinator;
+    use crate::combinators::helper::discrepancy::LinearDiscrepancy;
+    use crate::combinators::tabu::TabuCombinator;
+    use crate::combinators::helper::tabu_tenure::FullTabuTenure;
+    use crate::tree_search::beam_search::{BeamSearch, IterativeBeamSearch, create_iterative_beam_search};
+    use crate::tree_search::beam_search_pe::PEBeamSearch;
+    use crate::tree_search::best_first::BestFirstSearch;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+    use crate::test_spaces::{Node, BinaryTree, space, run};
+
+    /// runs the search max_expansions node expansions at a time
+    fn run_by_steps<A:StepwiseSearch<Node,i64>>(mut algo:A, max_expansions:usize) -> SearchStatus {
+        algo.init();
+        while algo.step(max_expansions) == StepOutcome::Running {}
+        algo.finish();
+        assert_eq!(*algo.get_manager().best_val(), Some(0));
+        algo.status()
+    }
+
+    #[test]
+    fn stepwise() {
+        assert_eq!(run_by_steps(BestFirstSearch::new(space(true)), 3), SearchStatus::Optimal);
+        assert_eq!(run_by_steps(DepthFirstSearch::new(space(true)), 1), SearchStatus::Optimal);
+        assert_eq!(run_by_steps(BeamSearch::new(space(true), 100), 4), SearchStatus::Optimal);
+        assert_eq!(run_by_steps(BeamSearch::new(space(true), 1), 4), SearchStatus::Feasible);
+        assert_eq!(run_by_steps(PEBeamSearch::new(space(true), 100), 2), SearchStatus::Optimal);
+        assert_eq!(run_by_steps(create_iterative_beam_search(space(true), 1., 2.), 5), SearchStatus::Optimal);
+        // the open list persists between the steps
+        let mut bfs:BestFirstSearch<Node, i64, i64, _> = BestFirstSearch::new(space(true));
+        bfs.init();
+        assert_eq!(bfs.step(2), StepOutcome::Running);
+        assert_eq!(bfs.get_manager().progress().nb_expanded(), 2);
+        assert_eq!(bfs.step(100), StepOutcome::Finished);
+        assert_eq!(bfs.get_manager().progress().nb_expanded(), 15);
+        // the search can be finished before the end
+        let mut dfs:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(space(true));
+        dfs.init();
+        assert_eq!(dfs.step(4), StepOutcome::Running);
+        dfs.finish();
+        assert_eq!(dfs.status(), SearchStatus::Feasible);
+    }
+
+    #[test]
+    fn heuristic_layers() {
+        // exploring a tree restricted by a heuristic layer does not prove optimality
+        fn lds(discrepancies:f64) -> Rc<RefCell<LDSCombinator<BinaryTree, LinearDiscrepancy, i64, i64>>> {
+            Rc::new(RefCell::new(LDSCombinator::new(BinaryTree::new(ObjectiveSense::Minimize), discrepancies, LinearDiscrepancy::default())))
+        }
+        fn outcome<A:SearchAlgorithm<N,i64>, N:Clone>(mut algo:A) -> (SearchStatus, Option<i64>) {
+            algo.run(NeverStoppingCriterion::default());
+            (algo.status(), *algo.get_manager().best_val())
+        }
+        let feasible = (SearchStatus::Feasible, Some(0));
+        assert_eq!(outcome(DepthFirstSearch::<_, _, i64, _>::new(lds(0.))), feasible);
+        assert_eq!(outcome(BestFirstSearch::<_, _, i64, _>::new(lds(1.))), feasible);
+        assert_eq!(outcome(BeamSearch::<_, _, i64, _>::new(lds(1.), 100)), feasible);
+        // the iterative search does not stop once its beam is wide enough to explore the whole tree
+        let mut iterative:IterativeBeamSearch<_, _, i64, _> = create_iterative_beam_search(lds(1.), 1., 2.);
+        iterative.init();
+        for _ in 0..8 { assert_eq!(iterative.step(1000), StepOutcome::Running); }
+        iterative.finish();
+        assert_eq!(iterative.status(), SearchStatus::Feasible);
+        // the tabu list forbids the best leaves
+        let tabu:TabuCombinator<_, i64, _, i64> = TabuCombinator::new(BinaryTree::new(ObjectiveSense::Minimize), FullTabuTenure::default());
+        assert_eq!(outcome(DepthFirstSearch::<_, _, i64, _>::new(Rc::new(RefCell::new(tabu)))), (SearchStatus::Feasible, Some(3)));
+    }
+
+    /// binary tree with statistics and pruning (both have a state to checkpoint)
+    type StatefulTree = StatTsCombinator<PruningCombinator<BinaryTree, i64>, i64>;
+
+    fn stateful_space() -> Rc<RefCell<StatefulTree>> {
+        let tree = BinaryTree { depth: 4, feasible: true, sense: ObjectiveSense::Minimize };
+        Rc::new(RefCell::new(StatTsCombinator::new(PruningCombinator::new(tree))))
+    }
+
+    /// stateful tree that also keeps the 3 best goals reached
+    type PooledTree = SolutionPoolCombinator<StatefulTree, Node, i64, ()>;
+
+    fn pooled_space() -> Rc<RefCell<PooledTree>> {
+        let tree = BinaryTree { depth: 4, feasible: true, sense: ObjectiveSense::Minimize };
+        Rc::new(RefCell::new(SolutionPoolCombinator::new(StatTsCombinator::new(PruningCombinator::new(tree)), 3)))
+    }
+
+    /// returns the status, the number of expanded nodes and the search statistics of a search
+    /// (except the times)
+    fn outcome<A:SearchAlgorithm<Node,i64>, Space:SearchSpace<Node,i64>>(algo:&mut A, space:&Rc<RefCell<Space>>) -> (SearchStatus, u64, serde_json::Value) {
+        let mut json = serde_json::json!({});
+        space.borrow().json_statistics(&mut json);
+        for key in ["time_searched", "nodes_generated_per_sec"] { json[key].take(); }
+        for point in json["primal_pareto_diagram"].as_array_mut().unwrap() { point["time"].take(); }
+        (algo.status(), algo.get_manager().progress().nb_expanded(), json)
+    }
+
+    /// interrupts a search after max_expansions, then resumes it in another search (and search
+    /// space built by new_space). Checks that it behaves as the uninterrupted search, and returns
+    /// the uninterrupted and resumed search spaces.
+    fn check_resume_with<Space, A, F>(new_space:fn() -> Rc<RefCell<Space>>, build:F, max_expansions:usize) -> (Rc<RefCell<Space>>, Rc<RefCell<Space>>)
+    where Space:SearchSpace<Node,i64>, A:CheckpointableSearch<Node,i64>, F:Fn(Rc<RefCell<Space>>) -> A {
+        let uninterrupted_space = new_space();
+        let mut uninterrupted = build(uninterrupted_space.clone());
+        uninterrupted.run(NeverStoppingCriterion::default());
+        let expected = outcome(&mut uninterrupted, &uninterrupted_space);
+        let mut interrupted = build(new_space());
+        interrupted.init();
+        assert_eq!(interrupted.step(max_expansions), StepOutcome::Running);
+        let checkpoint:serde_json::Value = serde_json::from_str(&interrupted.checkpoint().to_string()).unwrap();
+        let resumed_space = new_space();
+        let mut resumed = build(resumed_space.clone());
+        resumed.resume(&checkpoint).unwrap();
+        while resumed.step(1) == StepOutcome::Running {}
+        resumed.finish();
+        assert_eq!(outcome(&mut resumed, &resumed_space), expected);
+        (uninterrupted_space, resumed_space)
+    }
+
+    /// check_resume_with on the binary tree with statistics and pruning
+    fn check_resume<A, F>(build:F, max_expansions:usize)
+    where A:CheckpointableSearch<Node,i64>, F:Fn(Rc<RefCell<StatefulTree>>) -> A {
+        check_resume_with(stateful_space, build, max_expansions);
+    }
+
+    #[test]
+    fn checkpoint_resume() {
+        check_resume(BestFirstSearch::<_, _, i64, _>::new, 5);
+        check_resume(DepthFirstSearch::<_, _, i64, _>::new, 7);
+        check_resume(|s| BeamSearch::<_, _, i64, _>::new(s, 3), 4);
+        check_resume(|s| create_iterative_beam_search::<_, _, i64, _, _>(s, 1., 2.), 3);
+        // the solution pool is restored
+        let (uninterrupted_space, resumed_space) = check_resume_with(pooled_space, DepthFirstSearch::<_, _, i64, _>::new, 5);
+        let pool_values = |space:&Rc<RefCell<PooledTree>>| space.borrow().pool().iter().map(|e| e.value).collect::<Vec<_>>();
+        assert_eq!(pool_values(&resumed_space), pool_values(&uninterrupted_space));
+        assert_eq!(resumed_space.borrow().pool().len(), 2);
+        // a corrupted elapsed time is rejected instead of panicking
+        let mut interrupted:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(pooled_space());
+        interrupted.init();
+        interrupted.step(3);
+        for key in ["stats", "solution_pool"] {
+            let mut checkpoint = interrupted.checkpoint();
+            checkpoint["space"][key]["elapsed"] = serde_json::json!(-1.);
+            let mut resumed:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(pooled_space());
+            assert!(resumed.resume(&checkpoint).is_err());
+        }
+        // the search resumes from the checkpoint file (if it exists)
+        let path = std::env::temp_dir().join(format!("dogs_checkpoint_{}.json", std::process::id()));
+        let mut first:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(stateful_space());
+        let budget = NodeBudgetStoppingCriterion::expanded(first.get_manager(), 6);
+        first.run_with_checkpoints(budget, &path, Duration::from_secs(3600)).unwrap();
+        assert_eq!(first.status(), SearchStatus::Feasible);
+        let mut second:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(stateful_space());
+        second.run_with_checkpoints(NeverStoppingCriterion::default(), &path, Duration::from_secs(3600)).unwrap();
+        assert_eq!(second.status(), SearchStatus::Optimal);
+        assert!(second.get_manager().progress().nb_expanded() > 6);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn warm_start() {
+        // the upper bound is notified to the pruning combinator: only the optimal solution is found
+        let pruned_space = Rc::new(RefCell::new(PruningCombinator::new(BinaryTree::new(ObjectiveSense::Minimize))));
+        let mut dfs:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(pruned_space.clone());
+        dfs.warm_start_value(1);
+        assert_eq!(run(dfs, NeverStoppingCriterion::default()), SearchStatus::Optimal);
+        let mut json = serde_json::json!({});
+        pruned_space.borrow().json_statistics(&mut json);
+        assert_eq!(json["pruning_primal_bound"], serde_json::json!(0));
+        // nothing improves an optimal upper bound: the warm start value is proven optimal,
+        // but no solution node is known
+        let mut bfs:BestFirstSearch<Node, i64, i64, _> = BestFirstSearch::new(space(true));
+        bfs.warm_start_value(0);
+        bfs.run(NeverStoppingCriterion::default());
+        assert_eq!(bfs.status(), SearchStatus::Optimal);
+        assert!(bfs.get_manager().best().is_none());
+        assert_eq!(bfs.get_manager().incumbent_val(), Some(0));
+        // the warm start is registered in the performance profile of the statistics
+        let stats_space = Rc::new(RefCell::new(StatTsCombinator::new(BinaryTree::new(ObjectiveSense::Minimize))));
+        let mut stats_bfs:BestFirstSearch<Node, i64, i64, _> = BestFirstSearch::new(stats_space.clone());
+        stats_bfs.warm_start_value(0);
+        stats_bfs.run(NeverStoppingCriterion::default());
+        let mut stats = serde_json::json!({});
+        stats_space.borrow().json_statistics(&mut stats);
+        assert_eq!(stats["best_primal"], serde_json::json!(0));
+        assert_eq!(stats["primal_pareto_diagram"][0]["primal"], serde_json::json!(0));
+        // the warm starts given before the search are compared with the sense of the search space
+        let max_space = Rc::new(RefCell::new(BinaryTree::new(ObjectiveSense::Maximize)));
+        let mut max_dfs:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(max_space);
+        max_dfs.warm_start_value(1);
+        max_dfs.warm_start_value(3);
+        max_dfs.warm_start(Node { depth: 3, cost: 2, nb_children_generated: 0 }, 2);
+        max_dfs.run(NeverStoppingCriterion::default());
+        assert_eq!(max_dfs.status(), SearchStatus::Optimal);
+        assert!(max_dfs.get_manager().best().is_none());
+        assert_eq!(max_dfs.get_manager().incumbent_val(), Some(3));
+    }
+
+    /// manager of a search that did not start yet
+    fn manager(sense:ObjectiveSense) -> SearchManager<Node, i64> {
+        let mut res = SearchManager::default();
+        res.set_sense(sense);
+        res
+    }
+
+    fn leaf() -> Node { Node { depth: 3, cost: 0, nb_children_generated: 0 } }
+
+    #[test]
+    fn node_budget_criterion() {
+        let mut m = manager(ObjectiveSense::Minimize);
+        let expanded = NodeBudgetStoppingCriterion::expanded(&m, 3);
+        let generated = NodeBudgetStoppingCriterion::generated(&m, 4);
+        assert!(!expanded.is_finished() && !generated.is_finished());
+        m.notify_expansion(2);
+        assert!(!expanded.is_finished() && !generated.is_finished());
+        m.notify_expansion(2);
+        assert!(!expanded.is_finished() && generated.is_finished());
+        m.notify_expansion(0);
+        assert!(expanded.is_finished());
+        // the budget can also be read from a shared progress
+        assert!(NodeBudgetStoppingCriterion::expanded_from(m.progress(), 3).is_finished());
+        assert!(!NodeBudgetStoppingCriterion::expanded_from(m.progress(), 4).is_finished());
+    }
+
+    #[test]
+    fn stagnation_criterion() {
+        let mut m = manager(ObjectiveSense::Minimize);
+        let expanded = StagnationStoppingCriterion::expanded(&m, 2);
+        let time = StagnationStoppingCriterion::time(&m, 0.05);
+        // counted from the beginning of the search if no solution is known
+        m.notify_expansion(1);
+        assert!(!expanded.is_finished());
+        m.notify_expansion(1);
+        assert!(expanded.is_finished());
+        // an improvement resets both counters
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(time.is_finished());
+        m.update_best(leaf(), 10);
+        assert!(!expanded.is_finished() && !time.is_finished());
+        m.notify_expansion(1);
+        m.update_best(leaf(), 12); // not an improvement
+        m.notify_expansion(1);
+        assert!(expanded.is_finished());
+    }
+
+    #[test]
+    fn target_criterion() {
+        let mut m = manager(ObjectiveSense::Minimize);
+        let target = TargetStoppingCriterion::new(&m, 5);
+        assert!(!target.is_finished());
+        m.update_best(leaf(), 7);
+        assert!(!target.is_finished());
+        m.update_best(leaf(), 5);
+        assert!(target.is_finished());
+        // maximization: the target is reached from below
+        let mut maximization = manager(ObjectiveSense::Maximize);
+        let max_target = TargetStoppingCriterion::new(&maximization, 5);
+        maximization.update_best(leaf(), 3);
+        assert!(!max_target.is_finished());
+        maximization.update_best(leaf(), 7);
+        assert!(max_target.is_finished());
+        // a warm start value reaching the target stops the search
+        let mut warm_started = manager(ObjectiveSense::Maximize);
+        warm_started.set_upper_bound(5);
+        assert!(TargetStoppingCriterion::new(&warm_started, 5).is_finished());
+    }
+
+    #[test]
+    fn gap_criterion() {
+        let mut m = manager(ObjectiveSense::Minimize);
+        let gap = GapStoppingCriterion::new(&m, 0.01);
+        m.update_dual_bound(95);
+        assert!(!gap.is_finished()); // no solution
+        m.update_best(leaf(), 100);
+        assert!(!gap.is_finished()); // 5%
+        m.update_dual_bound(99);
+        assert!(gap.is_finished()); // 1%
+        // maximization: the dual bound is an upper bound
+        let mut maximization = manager(ObjectiveSense::Maximize);
+        let max_gap = GapStoppingCriterion::new(&maximization, 0.01);
+        maximization.update_best(leaf(), 100);
+        assert!(!max_gap.is_finished()); // no dual bound
+        maximization.update_dual_bound(110);
+        assert!(!max_gap.is_finished()); // 10%
+        maximization.update_dual_bound(120); // not tighter: ignored
+        assert!(!max_gap.is_finished());
+        maximization.update_dual_bound(101);
+        assert!(max_gap.is_finished()); // 1%
+    }
+
+    #[test]
+    fn combined_criteria() {
+        let mut m = manager(ObjectiveSense::Minimize);
+        let any = AnyStoppingCriterion::new(NodeBudgetStoppingCriterion::expanded(&m, 1), TargetStoppingCriterion::new(&m, 0));
+        let all = AllStoppingCriterion::new(NodeBudgetStoppingCriterion::expanded(&m, 1), TargetStoppingCriterion::new(&m, 0));
+        assert!(!any.is_finished() && !all.is_finished());
+        // only the target is reached
+        m.update_best(leaf(), 0);
+        assert!(any.is_finished() && !all.is_finished());
+        // both are reached
+        m.notify_expansion(2);
+        assert!(any.is_finished() && all.is_finished());
+        // only the budget is reached
+        let mut no_solution = manager(ObjectiveSense::Minimize);
+        let budget_or_target = AnyStoppingCriterion::new(NodeBudgetStoppingCriterion::expanded(&no_solution, 1), TargetStoppingCriterion::new(&no_solution, 0));
+        no_solution.notify_expansion(2);
+        assert!(budget_or_target.is_finished());
+        // nested combinations
+        let nested = AnyStoppingCriterion::new(NeverStoppingCriterion::default(), AllStoppingCriterion::new(budget_or_target, NodeBudgetStoppingCriterion::expanded(&no_solution, 2)));
+        assert!(!nested.is_finished());
+        no_solution.notify_expansion(2);
+        assert!(nested.is_finished());
+    }
+
+    #[test]
+    fn cpu_time() {
+        let t0 = process_cpu_time().unwrap();
+        let stopping_criterion = CpuTimeStoppingCriterion::new(0.02);
+        assert!(!CpuTimeStoppingCriterion::new(1000.).is_finished());
+        // the budget is consumed by computing
+        let mut x:u64 = 0;
+        while !stopping_criterion.is_finished() { x = x.wrapping_add(1); }
+        assert!(x > 0);
+        assert!(stopping_criterion.elapsed() >= Duration::from_millis(20));
+        assert!(process_cpu_time().unwrap() >= t0 + Duration::from_millis(20));
+        assert!(CpuTimeStoppingCriterion::new(0.).is_finished());
+    }
+
+    #[test]
+    fn thread_cpu_time_in_parallel() {
+        // the criteria can be sent to worker threads
+        fn assert_send<T:Send+Sync>(_:&T) {}
+        assert_send(&CpuTimeStoppingCriterion::new(1.));
+        assert_send(&PausableTimeStoppingCriterion::new(1.));
+        // two threads computing in parallel: each of them consumes its own thread budget
+        let process_start = process_cpu_time().unwrap();
+        let workers:Vec<_> = (0..2).map(|_| std::thread::spawn(|| {
+            let stopping_criterion = CpuTimeStoppingCriterion::thread(0.05);
+            let mut x:u64 = 0;
+            while !stopping_criterion.is_finished() { x = x.wrapping_add(1); }
+            assert!(x > 0);
+            stopping_criterion.elapsed()
+        })).collect();
+        for worker in workers {
+            let elapsed = worker.join().unwrap();
+            assert!(elapsed >= Duration::from_millis(50) && elapsed < Duration::from_millis(100));
+        }
+        // the process clock sums both threads
+        assert!(process_cpu_time().unwrap() >= process_start + Duration::from_millis(100));
+    }
+
+    #[test]
+    fn pausable_time() {
+        let stopping_criterion = PausableTimeStoppingCriterion::new(0.05);
+        // the clones share the budget
+        let clone = stopping_criterion.clone();
+        clone.pause();
+        assert!(stopping_criterion.is_paused());
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(!stopping_criterion.is_finished());
+        assert!(stopping_criterion.elapsed() < Duration::from_millis(50));
+        // a slice is limited by the remaining time
+        assert!(stopping_criterion.slice(10.).remaining() <= 0.05);
+        stopping_criterion.resume();
+        assert!(!clone.is_paused());
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(clone.is_finished());
+        assert_eq!(stopping_criterion.remaining(), 0.);
+    }
+
+    #[test]
+    fn cancellation_from_another_thread() {
+        // a search of a huge tree, cancelled by the main thread once it found a solution
+        let tree = BinaryTree { depth: 40, feasible: true, sense: ObjectiveSense::Minimize };
+        let mut dfs:DepthFirstSearch<Node, i64, i64, _, _> = DepthFirstSearch::new(Arc::new(std::sync::Mutex::new(tree)));
+        let progress = dfs.get_manager().progress();
+        let stopping_criterion = CancellationStoppingCriterion::new();
+        let worker_criterion = stopping_criterion.clone();
+        let worker = std::thread::spawn(move || {
+            dfs.run(worker_criterion);
+            (dfs.status(), dfs.get_manager().best().clone(), *dfs.get_manager().best_val())
+        });
+        while progress.best_val().is_none() || progress.nb_expanded() < 1000 {
+            std::thread::yield_now();
+        }
+        stopping_criterion.cancel();
+        let (status, best, best_val) = worker.join().unwrap();
+        // the incumbent is kept, but optimality is not proven
+        assert_eq!(status, SearchStatus::Feasible);
+        assert_eq!(best_val, Some(0));
+        assert_eq!(best.map(|n| n.depth), Some(40));
+    }
+}
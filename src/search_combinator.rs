@@ -1,3 +1,8 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+
 /**
 defines a search space decorator trait.
 A search space decorator allows 
@@ -5,4 +10,180 @@ A search space decorator allows
 pub trait SearchSpaceCombinator<S> {
     /** gets the underlying search space */
     fn unwrap(&self) -> &S;
-}
\ No newline at end of file
+}
+
+/**
+derives the search space traits of a decorator by forwarding them to the wrapped space
+(the field `s`, or the field marked `#[combinator(inner)]`). Traits implemented by hand are
+listed in `#[combinator(overrides(...))]`.
+*/
+pub use dogs_derive::SearchSpaceCombinator;
+
+
+/** layer of a search space stack (a combinator or the problem) and its parameters */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerDescription {
+    /// name of the layer (for instance "Pruning")
+    pub name: String,
+    /// parameters of the layer
+    #[serde(default)]
+    pub parameters: BTreeMap<String, serde_json::Value>,
+}
+
+impl LayerDescription {
+    /** builds a layer description without parameters */
+    pub fn new(name:&str) -> Self {
+        Self { name: name.to_string(), parameters: BTreeMap::new() }
+    }
+
+    /** adds a parameter to the layer */
+    pub fn with_parameter<T:Serialize>(mut self, key:&str, v:T) -> Self {
+        self.parameters.insert(key.to_string(), serde_json::json!(v));
+        self
+    }
+}
+
+impl fmt::Display for LayerDescription {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.parameters.is_empty() {
+            let params:Vec<String> = self.parameters.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            write!(f, "({})", params.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ordered list of the layers of a search space (outermost combinator first, the problem last),
+built by SearchSpace::describe. Displayed as "Stats > Pruning > GcostDominance > Problem"
+(with the parameters of the layers between parentheses).
+*/
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StackDescription {
+    /// layers (outermost first)
+    pub layers: Vec<LayerDescription>,
+}
+
+impl StackDescription {
+    /** adds a layer below the current ones */
+    pub fn push(&mut self, layer:LayerDescription) {
+        self.layers.push(layer);
+    }
+
+    /** returns the names of the layers (outermost first) */
+    pub fn names(&self) -> Vec<&str> {
+        self.layers.iter().map(|l| l.name.as_str()).collect()
+    }
+}
+
+impl fmt::Display for StackDescription {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        let layers:Vec<String> = self.layers.iter().map(|l| l.to_string()).collect();
+        write!(f, "{}", layers.join(" > "))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_space::{SearchSpace, GuidedSpace, IteratorNeighborGeneration, PartialNeighborGeneration, ObjectiveSense, DecisionSpace, Identifiable};
+    use crate::search_report::SearchReport;
+    use crate::combinators::tabu::TabuCombinator;
+    use crate::combinators::helper::tabu_tenure::FullTabuTenure;
+    use crate::combinators::node_features::{NodeFeaturesCombinator, DepthNode};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use crate::metric_logger::MetricLogger;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion};
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::combinators::stats::StatTsCombinator;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+    use crate::test_spaces::{Node, BinaryTree};
+
+    #[test]
+    fn stack_display() {
+        let mut stack = StackDescription::default();
+        stack.push(LayerDescription::new("Stats"));
+        stack.push(LayerDescription::new("LDS").with_parameter("allowed_discrepancies", 2.));
+        stack.push(LayerDescription::new("Problem"));
+        assert_eq!(stack.names(), vec!["Stats", "LDS", "Problem"]);
+        assert_eq!(stack.to_string(), "Stats > LDS(allowed_discrepancies=2.0) > Problem");
+    }
+
+    /// binary tree with floating point bounds and guides (the other traits are forwarded)
+    #[derive(Debug, SearchSpaceCombinator)]
+    #[combinator(overrides(SearchSpace, GuidedSpace))]
+    struct FloatTree {
+        #[combinator(inner)]
+        tree: BinaryTree,
+    }
+
+    impl SearchSpace<Node, f64> for FloatTree {
+        fn initial(&mut self) -> Node { self.tree.initial() }
+
+        fn bound(&mut self, n:&Node) -> f64 { self.tree.bound(n) as f64 }
+
+        fn goal(&mut self, n:&Node) -> bool { self.tree.goal(n) }
+
+        fn g_cost(&mut self, n:&Node) -> f64 { self.tree.g_cost(n) as f64 }
+    }
+
+    impl GuidedSpace<Node, f64> for FloatTree {
+        fn guide(&mut self, n:&Node) -> f64 { self.tree.guide(n) as f64 }
+    }
+
+    #[test]
+    fn derived_combinators() {
+        let mut float_tree = FloatTree { tree: BinaryTree::new(ObjectiveSense::Minimize) };
+        let mut root = float_tree.initial();
+        assert_eq!(float_tree.bound(&root), 0.);
+        assert_eq!(float_tree.id(&mut root), 0);
+        assert_eq!(float_tree.decision(&root), Some(0));
+        assert_eq!(float_tree.unwrap().depth, 3);
+        // node features with partial neighbor generation
+        let mut features:NodeFeaturesCombinator<_, f64, f64> = NodeFeaturesCombinator::new(float_tree);
+        let mut depth_root = DepthNode { node: features.initial().node, depth: 0 };
+        let mut children = Vec::new();
+        while let Some(child) = features.next_neighbor(&mut depth_root) { children.push(child); }
+        assert_eq!(children.iter().map(|c| (c.depth, c.node.cost)).collect::<Vec<_>>(), vec![(1, 0), (1, 1)]);
+        let mut report = SearchReport::default();
+        features.report(&mut report);
+        assert_eq!(report.counter("node_features", "nb_nodes"), Some(2));
+        assert_eq!(features.describe().to_string(), "NodeFeatures > Problem");
+        // tabu search: the child taking the decision of its parent is skipped (both generations)
+        for partial in [true, false] {
+            let mut tabu:TabuCombinator<_, i64, _, i64> = TabuCombinator::new(BinaryTree::new(ObjectiveSense::Minimize), FullTabuTenure::default());
+            let mut tabu_root = tabu.initial();
+            let costs:Vec<i64> = if partial {
+                std::iter::from_fn(|| tabu.next_neighbor(&mut tabu_root)).map(|c| c.cost).collect()
+            } else {
+                tabu.neighbors_iter(&mut tabu_root).map(|c| c.cost).collect()
+            };
+            assert_eq!(costs, vec![1]);
+            assert_eq!(tabu.guide(&tabu_root), 0);
+        }
+    }
+
+    #[test]
+    fn plain_run_description() {
+        // the stack is displayed by the logger bound to the statistics combinator
+        let logger = Arc::new(MetricLogger::default());
+        let stats = StatTsCombinator::<_, i64>::new(PruningCombinator::<_, i64>::new(BinaryTree::new(ObjectiveSense::Minimize)))
+            .bind_logger(Arc::downgrade(&logger));
+        assert_eq!(logger.description(), Some("Stats > Pruning > Problem".to_string()));
+        // and recorded by the reports and the statistics of a search run without configuration
+        let space = Rc::new(RefCell::new(stats));
+        let mut algo:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(space.clone());
+        algo.run(NeverStoppingCriterion::default());
+        let mut report = SearchReport::default();
+        algo.report(&mut report);
+        space.borrow().report(&mut report);
+        assert_eq!(report.stack.unwrap().names(), vec!["Stats", "Pruning", "Problem"]);
+        let mut json = serde_json::json!({});
+        space.borrow().json_statistics(&mut json);
+        assert_eq!(json["stack"], serde_json::json!("Stats > Pruning > Problem"));
+    }
+}
@@ -0,0 +1,618 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+use std::vec::IntoIter;
+
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+
+use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, IteratorNeighborGeneration, Identifiable, ObjectiveSense, CheckpointSpace};
+use crate::search_combinator::{SearchSpaceCombinator, StackDescription};
+use crate::search_algorithm::{SearchAlgorithm, AnyStoppingCriterion, TimeStoppingCriterion, NodeBudgetStoppingCriterion};
+use crate::search_manager::SearchStatus;
+use crate::search_report::{SearchReport, ReportSection};
+use crate::objective::Objective;
+use crate::combinators::stats::StatTsCombinator;
+use crate::combinators::pruning::PruningCombinator;
+use crate::combinators::gcost_dominance::GcostDominanceTsCombinator;
+use crate::combinators::lds::LDSCombinator;
+use crate::combinators::helper::discrepancy::{DiscrepancyNode, LinearDiscrepancy};
+use crate::tree_search::beam_search::{BeamSearch, create_iterative_beam_search};
+use crate::tree_search::beam_search_pe::{PEBeamSearch, create_iterative_pce_beam_search};
+use crate::tree_search::best_first::BestFirstSearch;
+use crate::tree_search::depth_first::DepthFirstSearch;
+use crate::tree_search::greedy::Greedy;
+
+
+/**
+ * search algorithm of a configuration (the "kind" field selects the algorithm)
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlgorithmConfig {
+    /// beam search of a given width
+    BeamSearch {
+        /// beam width
+        width: usize,
+    },
+    /// iterative beam search (the width grows geometrically)
+    IterativeBeamSearch {
+        /// initial beam width
+        #[serde(default = "default_d_init")]
+        d_init: f64,
+        /// growth factor of the beam width
+        #[serde(default = "default_growth")]
+        growth: f64,
+    },
+    /// partial expansion beam search of a given width
+    PeBeamSearch {
+        /// beam width
+        width: usize,
+    },
+    /// iterative partial expansion beam search (the width grows geometrically)
+    IterativePeBeamSearch {
+        /// initial beam width
+        #[serde(default = "default_d_init")]
+        d_init: f64,
+        /// growth factor of the beam width
+        #[serde(default = "default_growth")]
+        growth: f64,
+    },
+    /// best first search
+    BestFirst,
+    /// depth first search
+    DepthFirst,
+    /// limited discrepancy search: depth first search restricted to the nodes with few
+    /// discrepancies (the i-th best child of a node adds i discrepancies)
+    Lds {
+        /// number of allowed discrepancies
+        discrepancies: f64,
+    },
+    /// greedy (ties are broken randomly)
+    Greedy {
+        /// seed of the random number generator (DEFAULT_SEED otherwise)
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+}
+
+fn default_d_init() -> f64 { 1. }
+
+fn default_growth() -> f64 { 2. }
+
+/**
+ * combinator of a configuration (the "kind" field selects the combinator).
+ * The layers of a dynamic stack share the node type of the problem, so only the combinators
+ * that keep it can be configured (the limited discrepancy search is an algorithm kind).
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CombinatorConfig {
+    /// statistics (StatTsCombinator)
+    Stats,
+    /// pruning by the best known solution (PruningCombinator)
+    Pruning {
+        /// relative gap to the best known solution under which nodes are kept
+        /// (PruningCombinator::with_tolerance)
+        #[serde(default)]
+        tolerance: Option<f64>,
+    },
+    /// g-cost dominance (GcostDominanceTsCombinator, requires identifiable nodes)
+    GcostDominance,
+}
+
+/**
+ * stopping criteria of a configuration (the search stops as soon as one of them is reached)
+ */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StoppingConfig {
+    /// time limit (in seconds)
+    #[serde(default)]
+    pub time_limit: Option<f32>,
+    /// maximum number of expanded nodes
+    #[serde(default)]
+    pub expanded: Option<u64>,
+    /// maximum number of generated nodes
+    #[serde(default)]
+    pub generated: Option<u64>,
+}
+
+/**
+ * declarative description of a search: algorithm, combinators (from the outermost one to the
+ * innermost one) and stopping criteria. For instance:
+ * ```json
+ * {
+ *     "algorithm": {"kind": "iterative_beam_search", "d_init": 1, "growth": 2},
+ *     "combinators": [{"kind": "stats"}, {"kind": "pruning", "tolerance": 0.01}, {"kind": "gcost_dominance"}],
+ *     "stopping": {"time_limit": 10}
+ * }
+ * ```
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchConfig {
+    /// search algorithm
+    pub algorithm: AlgorithmConfig,
+    /// combinators (the first one is the outermost)
+    #[serde(default)]
+    pub combinators: Vec<CombinatorConfig>,
+    /// stopping criteria
+    #[serde(default)]
+    pub stopping: StoppingConfig,
+}
+
+
+/**
+ * error raised while reading or applying a configuration
+ */
+#[derive(Debug)]
+pub enum ConfigError {
+    /// the configuration file cannot be read
+    Io(io::Error),
+    /// the configuration is not valid
+    Parse(serde_json::Error),
+    /// the search space does not provide a feature required by the configuration
+    Unsupported(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "cannot read the configuration: {}", e),
+            ConfigError::Parse(e) => write!(f, "invalid configuration: {}", e),
+            ConfigError::Unsupported(msg) => write!(f, "unsupported configuration: {}", msg),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(e:io::Error) -> Self { ConfigError::Io(e) }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e:serde_json::Error) -> Self { ConfigError::Parse(e) }
+}
+
+
+/**
+ * cursor of the neighbor enumeration of a dynamic stack (the cursor of its outermost layer)
+ */
+pub type DynamicCursor = Box<dyn Any>;
+
+/**
+ * layer of a stack built from a configuration. The layers are stored as trait objects, so
+ * stacks of any shape share the same type (DynamicStack).
+ */
+pub trait DynamicSpace<N, B, G, Id>: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N> + CheckpointSpace {
+    /** returns true iff the nodes are identifiable (required by the dominance combinator) */
+    fn identifies_nodes(&self) -> bool;
+
+    /** returns the identifier of a node (None if the nodes are not identifiable) */
+    fn node_id(&self, n:&mut N) -> Option<Id>;
+
+    /** starts the (lazy) enumeration of the children of a node (IteratorNeighborGeneration) */
+    fn dynamic_cursor(&mut self, n:&mut N) -> DynamicCursor;
+
+    /** returns the next child of the enumeration (None if there is none) */
+    fn next_dynamic_neighbor(&mut self, n:&mut N, cursor:&mut DynamicCursor) -> Option<N>;
+}
+
+/// implements the neighbor enumeration of a dynamic layer by its IteratorNeighborGeneration
+macro_rules! dynamic_neighbors {
+    () => {
+        fn dynamic_cursor(&mut self, n:&mut N) -> DynamicCursor {
+            Box::new(IteratorNeighborGeneration::neighbors_cursor(self, n))
+        }
+
+        fn next_dynamic_neighbor(&mut self, n:&mut N, cursor:&mut DynamicCursor) -> Option<N> {
+            let cursor = cursor.downcast_mut().expect("the cursor was created by another layer");
+            IteratorNeighborGeneration::next_neighbor_from(self, n, cursor)
+        }
+    };
+}
+
+/**
+ * search space stack built from a configuration
+ */
+pub type DynamicStack<N, B, G, Id> = Box<dyn DynamicSpace<N, B, G, Id>>;
+
+impl<N, B, G, Id> SearchSpace<N,B> for DynamicStack<N, B, G, Id> {
+    fn initial(&mut self) -> N { (**self).initial() }
+
+    fn bound(&mut self, n:&N) -> B { (**self).bound(n) }
+
+    fn goal(&mut self, n:&N) -> bool { (**self).goal(n) }
+
+    fn g_cost(&mut self, n:&N) -> B { (**self).g_cost(n) }
+
+    fn handle_new_best(&mut self, n:N) -> N { (**self).handle_new_best(n) }
+
+    fn notify_incumbent(&mut self, value:B) { (**self).notify_incumbent(value) }
+
+    fn start_search(&mut self, msg:String) { (**self).start_search(msg) }
+
+    fn restart(&mut self, msg:String) { (**self).restart(msg) }
+
+    fn stop_search(&mut self, msg:String) { (**self).stop_search(msg) }
+
+    fn display_statistics(&self) { (**self).display_statistics() }
+
+    fn json_statistics(&self, json:&mut serde_json::Value) { (**self).json_statistics(json) }
+
+    fn report_sections(&self, report:&mut SearchReport) { (**self).report_sections(report) }
+
+    fn describe_layers(&self, stack:&mut StackDescription) { (**self).describe_layers(stack) }
+
+    fn dual_bound(&self) -> Option<B> { (**self).dual_bound() }
+
+    fn sense(&self) -> ObjectiveSense { (**self).sense() }
+
+    fn is_exact(&self) -> bool { (**self).is_exact() }
+
+    fn request_log_header(&self, res:Vec<String>) { (**self).request_log_header(res) }
+
+    fn request_logging(&self, res:Vec<String>) { (**self).request_logging(res) }
+}
+
+impl<N, B, G, Id> GuidedSpace<N,G> for DynamicStack<N, B, G, Id> {
+    fn guide(&mut self, n:&N) -> G { (**self).guide(n) }
+}
+
+impl<N, B, G, Id> IteratorNeighborGeneration<N> for DynamicStack<N, B, G, Id> {
+    /// the children are enumerated lazily by the outermost layer
+    type Cursor = DynamicCursor;
+
+    fn neighbors_cursor(&mut self, n:&mut N) -> Self::Cursor { (**self).dynamic_cursor(n) }
+
+    fn next_neighbor_from(&mut self, n:&mut N, cursor:&mut Self::Cursor) -> Option<N> {
+        (**self).next_dynamic_neighbor(n, cursor)
+    }
+}
+
+impl<N, B, G, Id> CheckpointSpace for DynamicStack<N, B, G, Id> {
+    fn save_state(&self, json:&mut serde_json::Value) { (**self).save_state(json) }
+
+    fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> { (**self).load_state(json) }
+}
+
+impl<N, B, G, Id> Identifiable<N, Id> for DynamicStack<N, B, G, Id> {
+    fn id(&self, n:&mut N) -> Id {
+        self.node_id(n).expect("the nodes of the search space are not identifiable")
+    }
+}
+
+
+/**
+ * innermost layer of a dynamic stack: the search space of the problem (and how to identify its
+ * nodes if it can)
+ */
+#[derive(Debug, SearchSpaceCombinator)]
+#[combinator(overrides(IteratorNeighborGeneration))]
+pub struct ProblemLayer<S, N, Id> {
+    s: S,
+    ids: Option<fn(&S, &mut N) -> Id>,
+}
+
+impl<S, N, Id> ProblemLayer<S, N, Id> {
+    /** builds the layer (the nodes are not identifiable) */
+    pub fn new(s:S) -> Self { Self { s, ids: None } }
+
+    /** builds the layer of a search space that identifies its nodes */
+    pub fn identifiable(s:S) -> Self where S:Identifiable<N, Id> {
+        Self { s, ids: Some(<S as Identifiable<N, Id>>::id) }
+    }
+}
+
+impl<S:TotalNeighborGeneration<N>, N, Id> IteratorNeighborGeneration<N> for ProblemLayer<S, N, Id> {
+    /// the children are generated by the search space when the cursor is created
+    type Cursor = IntoIter<N>;
+
+    fn neighbors_cursor(&mut self, n:&mut N) -> Self::Cursor { self.s.neighbors(n).into_iter() }
+
+    fn next_neighbor_from(&mut self, _n:&mut N, cursor:&mut Self::Cursor) -> Option<N> { cursor.next() }
+}
+
+impl<S, N, B, G, Id> DynamicSpace<N, B, G, Id> for ProblemLayer<S, N, Id>
+where
+    S: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N> + CheckpointSpace,
+    N: 'static,
+{
+    fn identifies_nodes(&self) -> bool { self.ids.is_some() }
+
+    fn node_id(&self, n:&mut N) -> Option<Id> { self.ids.map(|id| id(&self.s, n)) }
+
+    dynamic_neighbors!();
+}
+
+/// implements DynamicSpace for a combinator wrapping a dynamic stack
+macro_rules! dynamic_layer {
+    ($combinator:ty) => {
+        impl<N, B, G, Id> DynamicSpace<N, B, G, Id> for $combinator
+        where
+            N: Clone + 'static,
+            B: Objective + DeserializeOwned + 'static,
+            G: 'static,
+            Id: Hash + Eq + Serialize + DeserializeOwned + 'static,
+        {
+            fn identifies_nodes(&self) -> bool { self.unwrap().identifies_nodes() }
+
+            fn node_id(&self, n:&mut N) -> Option<Id> { self.unwrap().node_id(n) }
+
+            dynamic_neighbors!();
+        }
+    };
+}
+
+dynamic_layer!(StatTsCombinator<DynamicStack<N, B, G, Id>, B>);
+dynamic_layer!(PruningCombinator<DynamicStack<N, B, G, Id>, B>);
+dynamic_layer!(GcostDominanceTsCombinator<DynamicStack<N, B, G, Id>, Id, B>);
+
+
+/**
+ * outcome of a search run from a configuration
+ */
+#[derive(Debug)]
+pub struct ConfiguredRun<N, B> {
+    /// status of the search
+    pub status: SearchStatus,
+    /// best known solution (if any)
+    pub best: Option<N>,
+    /// value of the best known solution (if any)
+    pub best_val: Option<B>,
+    /// report of the search (configuration, algorithm and combinator sections)
+    pub report: SearchReport,
+}
+
+impl SearchConfig {
+    /** reads a configuration from a JSON value */
+    pub fn from_json(json:&serde_json::Value) -> Result<Self, ConfigError> {
+        Ok(serde_json::from_value(json.clone())?)
+    }
+
+    /** reads a configuration from a JSON file */
+    pub fn load<P:AsRef<Path>>(path:P) -> Result<Self, ConfigError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /**
+     * wraps a search space (the innermost layer) in the combinators of the configuration
+     */
+    pub fn build_stack<N, B, G, Id>(&self, base:DynamicStack<N, B, G, Id>) -> Result<DynamicStack<N, B, G, Id>, ConfigError>
+    where
+        N: Clone + 'static,
+        B: Objective + DeserializeOwned + 'static,
+        G: 'static,
+        Id: Hash + Eq + Serialize + DeserializeOwned + 'static,
+    {
+        let mut res = base;
+        for c in self.combinators.iter().rev() {
+            res = match c {
+                CombinatorConfig::Stats => Box::new(StatTsCombinator::new(res)),
+                CombinatorConfig::Pruning { tolerance: None } => Box::new(PruningCombinator::new(res)),
+                CombinatorConfig::Pruning { tolerance: Some(t) } => Box::new(PruningCombinator::new(res).with_tolerance(*t)),
+                CombinatorConfig::GcostDominance => {
+                    if !res.identifies_nodes() {
+                        return Err(ConfigError::Unsupported(
+                            "the gcost_dominance combinator requires identifiable nodes (ProblemLayer::identifiable)".to_string()
+                        ));
+                    }
+                    Box::new(GcostDominanceTsCombinator::new(res))
+                },
+            };
+        }
+        Ok(res)
+    }
+
+    /**
+     * builds the stack and the algorithm of the configuration, runs the search until one of the
+     * stopping criteria is reached and returns its outcome
+     */
+    pub fn run<N, B, G, Id>(&self, base:DynamicStack<N, B, G, Id>) -> Result<ConfiguredRun<N, B>, ConfigError>
+    where
+        N: Clone + 'static,
+        B: Objective + DeserializeOwned + fmt::Display + 'static,
+        G: Ord + Clone + 'static,
+        Id: Hash + Eq + Serialize + DeserializeOwned + 'static,
+    {
+        let stack = self.build_stack(base)?;
+        if let AlgorithmConfig::Lds { discrepancies } = self.algorithm {
+            // the discrepancies are stored in the nodes (the nodes of the problem are returned)
+            let lds = Rc::new(RefCell::new(LDSCombinator::new(stack, discrepancies, LinearDiscrepancy::default())));
+            let outcome = self.run_algorithm(DepthFirstSearch::<DiscrepancyNode<N>, B, G, _>::new(lds.clone()), &lds);
+            return Ok(ConfiguredRun {
+                status: outcome.status,
+                best: outcome.best.map(|n| n.node),
+                best_val: outcome.best_val,
+                report: outcome.report,
+            });
+        }
+        let space = Rc::new(RefCell::new(stack));
+        let res = match self.algorithm {
+            AlgorithmConfig::BeamSearch { width } => {
+                self.run_algorithm(BeamSearch::<N, B, G, _>::new(space.clone(), width), &space)
+            },
+            AlgorithmConfig::IterativeBeamSearch { d_init, growth } => {
+                self.run_algorithm(create_iterative_beam_search::<N, B, G, _, _>(space.clone(), d_init, growth), &space)
+            },
+            AlgorithmConfig::PeBeamSearch { width } => {
+                self.run_algorithm(PEBeamSearch::<N, B, G, _>::new(space.clone(), width), &space)
+            },
+            AlgorithmConfig::IterativePeBeamSearch { d_init, growth } => {
+                self.run_algorithm(create_iterative_pce_beam_search::<N, B, G, _, _>(space.clone(), d_init, growth), &space)
+            },
+            AlgorithmConfig::BestFirst => {
+                self.run_algorithm(BestFirstSearch::<N, B, G, _>::new(space.clone()), &space)
+            },
+            AlgorithmConfig::DepthFirst => {
+                self.run_algorithm(DepthFirstSearch::<N, B, G, _>::new(space.clone()), &space)
+            },
+            AlgorithmConfig::Greedy { seed } => {
+                let mut algo = Greedy::<N, B, G, _>::new(space.clone());
+                if let Some(s) = seed { algo = algo.with_seed(s); }
+                self.run_algorithm(algo, &space)
+            },
+            AlgorithmConfig::Lds { .. } => unreachable!("the limited discrepancy search is run above"),
+        };
+        Ok(res)
+    }
+
+    /// runs an algorithm with the stopping criteria of the configuration
+    fn run_algorithm<N, B, S, A>(&self, mut algo:A, space:&Rc<RefCell<S>>) -> ConfiguredRun<N, B>
+    where N:Clone, B:PartialOrd+Copy, S:SearchSpace<N, B>, A:SearchAlgorithm<N, B> {
+        let stopping_criterion = AnyStoppingCriterion::new(
+            TimeStoppingCriterion::new(self.stopping.time_limit.unwrap_or(f32::INFINITY)),
+            AnyStoppingCriterion::new(
+                NodeBudgetStoppingCriterion::expanded(algo.get_manager(), self.stopping.expanded.unwrap_or(u64::MAX)),
+                NodeBudgetStoppingCriterion::generated(algo.get_manager(), self.stopping.generated.unwrap_or(u64::MAX)),
+            ),
+        );
+        algo.run(stopping_criterion);
+        let mut report = SearchReport::default();
+        report.push(ReportSection::new("config")
+            .with_parameter("algorithm", &self.algorithm)
+            .with_parameter("combinators", &self.combinators)
+            .with_parameter("stopping", &self.stopping)
+        );
+        algo.report(&mut report);
+        space.borrow().report(&mut report);
+        let status = algo.status();
+        let manager = algo.get_manager();
+        ConfiguredRun {
+            status,
+            best: manager.best().clone(),
+            best_val: *manager.best_val(),
+            report,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_spaces::{Node, BinaryTree};
+
+    /// runs the search described by a configuration on the binary tree (identified by depth or not)
+    fn run_config(config:serde_json::Value, identifiable:bool) -> Result<ConfiguredRun<Node, i64>, ConfigError> {
+        let tree = BinaryTree::new(ObjectiveSense::Minimize);
+        let base = if identifiable { ProblemLayer::identifiable(tree) } else { ProblemLayer::new(tree) };
+        SearchConfig::from_json(&config)?.run::<Node, i64, i64, usize>(Box::new(base))
+    }
+
+    #[test]
+    fn configured_search() {
+        let outcome = run_config(serde_json::json!({
+            "algorithm": {"kind": "iterative_beam_search", "growth": 3},
+            "combinators": [{"kind": "stats"}, {"kind": "pruning"}, {"kind": "gcost_dominance"}],
+            "stopping": {"time_limit": 10},
+        }), true).unwrap();
+        assert_eq!((outcome.status, outcome.best_val), (SearchStatus::Optimal, Some(0)));
+        // the report records the configuration and the combinators in the stack order
+        let names:Vec<&str> = outcome.report.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["config", "stats", "pruning", "gcost_dominance"]);
+        assert_eq!(outcome.report.section("config").unwrap().parameters["algorithm"]["d_init"], serde_json::json!(1.));
+        assert_eq!(outcome.report.stack.as_ref().unwrap().to_string(), "Stats > Pruning > GcostDominance > Problem");
+        // each algorithm kind can be configured
+        for algorithm in [
+            serde_json::json!({"kind": "beam_search", "width": 10}),
+            serde_json::json!({"kind": "best_first"}),
+            serde_json::json!({"kind": "depth_first"}),
+            serde_json::json!({"kind": "greedy", "seed": 3}),
+            serde_json::json!({"kind": "pe_beam_search", "width": 10}),
+            serde_json::json!({"kind": "iterative_pe_beam_search"}),
+            serde_json::json!({"kind": "lds", "discrepancies": 3}),
+        ] {
+            let config = serde_json::json!({"algorithm": algorithm, "combinators": [{"kind": "pruning"}]});
+            assert_eq!(run_config(config, false).unwrap().best_val, Some(0));
+        }
+        // stopping criteria
+        let interrupted = run_config(serde_json::json!({
+            "algorithm": {"kind": "depth_first"}, "stopping": {"expanded": 2},
+        }), false).unwrap();
+        assert_eq!(interrupted.status, SearchStatus::NoSolution);
+        // the dominance combinator requires identifiable nodes
+        assert!(matches!(run_config(serde_json::json!({
+            "algorithm": {"kind": "depth_first"}, "combinators": [{"kind": "gcost_dominance"}],
+        }), false), Err(ConfigError::Unsupported(_))));
+        // unknown combinators are rejected
+        assert!(matches!(run_config(serde_json::json!({
+            "algorithm": {"kind": "depth_first"}, "combinators": [{"kind": "unknown"}],
+        }), true), Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn combinator_options() {
+        let config = SearchConfig::from_json(&serde_json::json!({
+            "algorithm": {"kind": "depth_first"},
+            "combinators": [{"kind": "stats"}, {"kind": "pruning", "tolerance": 0.5}],
+        })).unwrap();
+        assert_eq!(config.combinators[1], CombinatorConfig::Pruning { tolerance: Some(0.5) });
+        let base:DynamicStack<Node, i64, i64, usize> = Box::new(ProblemLayer::new(BinaryTree::new(ObjectiveSense::Maximize)));
+        let stack = config.build_stack(base).unwrap();
+        assert_eq!(stack.describe().layers[1].parameters["tolerance"], serde_json::json!(0.5));
+        // the combinators that change the node type cannot be configured
+        for combinator in [
+            serde_json::json!({"kind": "bounding"}),
+            serde_json::json!({"kind": "lds", "discrepancies": 2}),
+            serde_json::json!({"kind": "tabu"}),
+        ] {
+            let rejected = serde_json::json!({"algorithm": {"kind": "depth_first"}, "combinators": [{"kind": "stats"}, combinator]});
+            assert!(matches!(run_config(rejected, true), Err(ConfigError::Parse(_))));
+        }
+    }
+
+    #[test]
+    fn pruning_tolerance() {
+        // the nodes of a maximization tree worse than the best known value by at most the tolerance are kept
+        let nb_pruned = |pruning:serde_json::Value| {
+            let config = serde_json::json!({"algorithm": {"kind": "depth_first"}, "combinators": [{"kind": "stats"}, pruning]});
+            let base = ProblemLayer::new(BinaryTree::new(ObjectiveSense::Maximize));
+            let outcome = SearchConfig::from_json(&config).unwrap().run::<Node, i64, i64, usize>(Box::new(base)).unwrap();
+            assert_eq!((outcome.status, outcome.best_val), (SearchStatus::Optimal, Some(3)));
+            outcome.report.counter("pruning", "nb_pruned").unwrap()
+        };
+        let strict = nb_pruned(serde_json::json!({"kind": "pruning"}));
+        let tolerant = nb_pruned(serde_json::json!({"kind": "pruning", "tolerance": 0.5}));
+        assert_eq!((strict, tolerant), (4, 3));
+        assert_eq!(nb_pruned(serde_json::json!({"kind": "pruning", "tolerance": 1.})), 0);
+    }
+
+    #[test]
+    fn limited_discrepancy_search() {
+        // without discrepancies, only the leftmost branch is explored
+        let greedy = run_config(serde_json::json!({
+            "algorithm": {"kind": "lds", "discrepancies": 0}, "combinators": [{"kind": "stats"}],
+        }), false).unwrap();
+        assert_eq!((greedy.status, greedy.best_val), (SearchStatus::Feasible, Some(0)));
+        assert_eq!(greedy.report.stack.as_ref().unwrap().to_string(), "LDS(allowed_discrepancies=0.0) > Stats > Problem");
+        let mut json = serde_json::json!({});
+        greedy.report.write_statistics(&mut json);
+        assert_eq!(json["nb_expanded"], serde_json::json!(4));
+    }
+
+    #[test]
+    fn lazy_neighbors() {
+        // the children are enumerated by the outermost layer (and filtered one at a time)
+        let config = SearchConfig::from_json(&serde_json::json!({
+            "algorithm": {"kind": "depth_first"}, "combinators": [{"kind": "stats"}, {"kind": "pruning"}],
+        })).unwrap();
+        assert_eq!(config.combinators[1], CombinatorConfig::Pruning { tolerance: None });
+        let base:DynamicStack<Node, i64, i64, usize> = Box::new(ProblemLayer::new(BinaryTree::new(ObjectiveSense::Minimize)));
+        let mut stack = config.build_stack(base).unwrap();
+        let mut root = stack.initial();
+        let mut cursor = stack.neighbors_cursor(&mut root);
+        assert_eq!(stack.next_neighbor_from(&mut root, &mut cursor).map(|c| c.cost), Some(0));
+        // the incumbent prunes the remaining child
+        stack.notify_incumbent(1);
+        assert!(stack.next_neighbor_from(&mut root, &mut cursor).is_none());
+        let costs:Vec<i64> = stack.neighbors_iter(&mut root).map(|c| c.cost).collect();
+        assert_eq!(costs, vec![0]);
+    }
+}
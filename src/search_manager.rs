@@ -1,5 +1,201 @@
 use std::cmp::{PartialOrd};
-use std::time::{Duration, SystemTime};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+
+use crate::search_space::{SearchSpace, ObjectiveSense, ToSolution};
+use crate::objective::Objective;
+
+
+/**
+ * thread-safe Cell (the progress can be read by stopping criteria from other threads).
+ * Used for the values that are rarely updated (the counters are atomics).
+ */
+struct SyncCell<T>(Mutex<T>);
+
+impl<T> SyncCell<T> {
+    fn new(v:T) -> Self { Self(Mutex::new(v)) }
+}
+
+impl<T:Copy> SyncCell<T> {
+    fn get(&self) -> T { *self.0.lock().unwrap() }
+
+    fn set(&self, v:T) { *self.0.lock().unwrap() = v; }
+}
+
+
+/**
+ * progress of a search (number of expanded nodes, best known value, dual bound, etc.).
+ * It is shared between the search manager and the stopping criteria that depend on it.
+ */
+pub struct SearchProgress<B> {
+    t_start: Instant,
+    sense: SyncCell<ObjectiveSense>,
+    nb_expanded: AtomicU64,
+    nb_generated: AtomicU64,
+    best_val: SyncCell<Option<B>>,
+    dual_bound: SyncCell<Option<B>>,
+    last_improvement_time: SyncCell<Instant>,
+    last_improvement_expanded: AtomicU64,
+}
+
+impl<B> fmt::Debug for SearchProgress<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SearchProgress")
+            .field("sense", &self.sense.get())
+            .field("nb_expanded", &self.nb_expanded.load(Ordering::Relaxed))
+            .field("nb_generated", &self.nb_generated.load(Ordering::Relaxed))
+            .field("expanded_since_improvement", &(self.nb_expanded.load(Ordering::Relaxed) - self.last_improvement_expanded.load(Ordering::Relaxed)))
+            .finish()
+    }
+}
+
+impl<B> Default for SearchProgress<B> {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            t_start: now,
+            sense: SyncCell::new(ObjectiveSense::default()),
+            nb_expanded: AtomicU64::new(0),
+            nb_generated: AtomicU64::new(0),
+            best_val: SyncCell::new(None),
+            dual_bound: SyncCell::new(None),
+            last_improvement_time: SyncCell::new(now),
+            last_improvement_expanded: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<B:PartialOrd+Copy> SearchProgress<B> {
+    /** elapsed time since the beginning of the search */
+    pub fn elapsed_time(&self) -> Duration { self.t_start.elapsed() }
+
+    /** objective sense of the search */
+    pub fn sense(&self) -> ObjectiveSense { self.sense.get() }
+
+    /** number of expanded nodes */
+    pub fn nb_expanded(&self) -> u64 { self.nb_expanded.load(Ordering::Relaxed) }
+
+    /** number of generated nodes */
+    pub fn nb_generated(&self) -> u64 { self.nb_generated.load(Ordering::Relaxed) }
+
+    /** best known primal value (objective) if it exists */
+    pub fn best_val(&self) -> Option<B> { self.best_val.get() }
+
+    /** best known dual bound if it exists */
+    pub fn dual_bound(&self) -> Option<B> { self.dual_bound.get() }
+
+    /** elapsed time since the last improvement (or the beginning of the search) */
+    pub fn time_since_improvement(&self) -> Duration { self.last_improvement_time.get().elapsed() }
+
+    /** number of expanded nodes since the last improvement (or the beginning of the search) */
+    pub fn expanded_since_improvement(&self) -> u64 {
+        self.nb_expanded() - self.last_improvement_expanded.load(Ordering::Relaxed)
+    }
+
+    /** registers a node expansion that generated nb_generated children */
+    pub(crate) fn notify_expansion(&self, nb_generated:usize) {
+        self.nb_expanded.fetch_add(1, Ordering::Relaxed);
+        self.nb_generated.fetch_add(nb_generated as u64, Ordering::Relaxed);
+    }
+
+    /**
+     * registers a new best known value (ignored if it does not improve the previous one).
+     * returns true iff it improves the previous one
+     */
+    fn update_best_val(&self, v:B) -> bool {
+        if self.best_val.get().is_none_or(|b| self.sense().is_better(&v, &b)) {
+            self.best_val.set(Some(v));
+            self.last_improvement_time.set(Instant::now());
+            self.last_improvement_expanded.store(self.nb_expanded(), Ordering::Relaxed);
+            true
+        } else { false }
+    }
+
+    /** registers a dual bound (ignored if it does not improve the previous one) */
+    fn update_dual_bound(&self, b:B) {
+        if self.dual_bound.get().is_none_or(|d| self.sense().is_tighter_bound(&b, &d)) {
+            self.dual_bound.set(Some(b));
+        }
+    }
+}
+
+
+/**
+ * status of a search (proof and solution found)
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchStatus {
+    /// the search did not start yet
+    Unknown,
+    /// the search stopped without finding any solution (and without proving infeasibility)
+    NoSolution,
+    /// a solution is known, but the search did not prove its optimality
+    Feasible,
+    /// the search proved the best known solution is optimal. With a warm start value
+    /// (`warm_start_value`) that no solution of the search improves, the optimal value is
+    /// the warm start value and no solution node is known (`SearchManager::best` is None)
+    Optimal,
+    /// the search proved there is no solution
+    Infeasible,
+}
+
+/// callback called on each new best known solution (node, value, elapsed time)
+type NewBestObserver<N,B> = Box<dyn FnMut(&N, B, Duration) + Send>;
+
+/// new-best callbacks shared between a search and its inner searches
+type SharedNewBestObservers<N,B> = Arc<Mutex<Vec<NewBestObserver<N,B>>>>;
+
+/// callback called when the search restarts (restart message)
+type RestartObserver = Box<dyn FnMut(&str) + Send>;
+
+/// callback called at the end of each iteration (iteration number, incumbent value)
+type IterationObserver<B> = Box<dyn FnMut(u64, Option<B>) + Send>;
+
+/// callback called when the search stops (status, incumbent value)
+type StopObserver<B> = Box<dyn FnMut(SearchStatus, Option<B>) + Send>;
+
+/**
+ * callbacks notified by the search manager while the search runs (new best known solutions,
+ * restarts, end of iterations, end of the search). They allow to stream the solutions
+ * (to a file, a client, etc.) without writing a combinator.
+ */
+pub struct SearchObservers<N, B> {
+    /// shared with the inner searches (for instance, the iterations of an iterative search)
+    new_best: SharedNewBestObservers<N,B>,
+    restart: Vec<RestartObserver>,
+    iteration_end: Vec<IterationObserver<B>>,
+    stop: Vec<StopObserver<B>>,
+    /// number of iterations ended so far
+    nb_iterations: u64,
+}
+
+impl<N, B> fmt::Debug for SearchObservers<N, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SearchObservers")
+            .field("new_best", &self.new_best.lock().unwrap().len())
+            .field("restart", &self.restart.len())
+            .field("iteration_end", &self.iteration_end.len())
+            .field("stop", &self.stop.len())
+            .finish()
+    }
+}
+
+impl<N, B> Default for SearchObservers<N, B> {
+    fn default() -> Self {
+        Self {
+            new_best: Arc::new(Mutex::new(Vec::new())),
+            restart: Vec::new(),
+            iteration_end: Vec::new(),
+            stop: Vec::new(),
+            nb_iterations: 0,
+        }
+    }
+}
 
 
 /**
@@ -8,17 +204,36 @@ use std::time::{Duration, SystemTime};
  */
 #[derive(Debug)]
 pub struct SearchManager<N, B> {
-    t_start: SystemTime,
+    t_start: Instant,
     best: Option<N>,
     best_val: Option<B>,
+    /// incumbent value provided without a solution (warm start)
+    upper_bound: Option<B>,
+    /// true iff the objective sense was set (the warm starts are compared with it)
+    sense_known: bool,
+    /// warm starts (solution if any, value) registered before the objective sense was set
+    pending_warm_starts: Vec<(Option<N>, B)>,
+    progress: Arc<SearchProgress<B>>,
+    /// true iff the search finished
+    finished: bool,
+    /// true iff the search finished and explored the whole search space (without heuristic pruning)
+    proven: bool,
+    observers: SearchObservers<N, B>,
 }
 
 impl<N:Clone, B:PartialOrd+Copy> Default for SearchManager<N, B> {
     fn default() -> Self {
         SearchManager {
-            t_start: SystemTime::now(),
+            t_start: Instant::now(),
             best: None,
             best_val: None,
+            upper_bound: None,
+            sense_known: false,
+            pending_warm_starts: Vec::new(),
+            progress: Arc::new(SearchProgress::default()),
+            finished: false,
+            proven: false,
+            observers: SearchObservers::default(),
         }
     }
 }
@@ -35,17 +250,174 @@ impl<N:Clone, B:PartialOrd+Copy> SearchManager<N, B> {
      */
     pub fn best_val(&self) -> &Option<B> { &self.best_val }
 
+    /**
+     * returns the objective sense of the search
+     */
+    pub fn sense(&self) -> ObjectiveSense { self.progress.sense() }
+
+    /**
+     * sets the objective sense of the search (the algorithms set it to the sense of the search
+     * space when they start). The warm starts registered before are applied.
+     */
+    pub fn set_sense(&mut self, sense: ObjectiveSense) {
+        self.progress.sense.set(sense);
+        self.sense_known = true;
+        for (s, e) in std::mem::take(&mut self.pending_warm_starts) {
+            match s {
+                Some(n) => self.update_best(n, e),
+                None => self.set_upper_bound(e),
+            }
+        }
+    }
+
+    /**
+     * called when the search starts: sets the objective sense of the search space, notifies the
+     * search space that the search starts (SearchSpace::start_search) and notifies it the
+     * incumbent value (warm start) if any
+     */
+    pub fn start_search<Space:SearchSpace<N,B>>(&mut self, space: &mut Space) {
+        self.set_sense(space.sense());
+        space.start_search(String::new());
+        if let Some(v) = self.incumbent_val() { space.notify_incumbent(v); }
+    }
+
+    /**
+     * returns the incumbent value: the best known primal value or the upper bound provided by
+     * a warm start (if it is better)
+     */
+    pub fn incumbent_val(&self) -> Option<B> {
+        match (self.best_val, self.upper_bound) {
+            (Some(a), Some(b)) => if self.sense().is_better(&b, &a) { Some(b) } else { Some(a) },
+            (a, b) => a.or(b),
+        }
+    }
+
     /**
      * returns the elapsed time since the beginning of the search
      */
-    pub fn elapsed_time(&self) -> Duration { self.t_start.elapsed().unwrap() }
+    pub fn elapsed_time(&self) -> Duration { self.t_start.elapsed() }
+
+    /**
+     * returns the status of the search. A warm start value counts as a known solution: the
+     * status can be Feasible or Optimal while no solution node is known (best is None)
+     */
+    pub fn status(&self) -> SearchStatus {
+        // an upper bound provided by a warm start is considered as a known solution
+        match (self.finished, self.proven, self.best.is_some() || self.upper_bound.is_some()) {
+            (false, _, false) => SearchStatus::Unknown,
+            (false, _, true) => SearchStatus::Feasible,
+            (true, false, false) => SearchStatus::NoSolution,
+            (true, false, true) => SearchStatus::Feasible,
+            (true, true, false) => SearchStatus::Infeasible,
+            (true, true, true) => SearchStatus::Optimal,
+        }
+    }
+
+    /**
+     * called when the search finishes. proven is true iff the search explored the whole search
+     * space (it was not interrupted by the stopping criterion and did not prune nodes heuristically)
+     */
+    pub fn finish(&mut self, proven: bool) {
+        self.finished = true;
+        self.proven = proven;
+        let (status, v) = (self.status(), self.incumbent_val());
+        for f in self.observers.stop.iter_mut() { f(status, v); }
+    }
+
+    /**
+     * returns the progress of the search (shared with the stopping criteria)
+     */
+    pub fn progress(&self) -> Arc<SearchProgress<B>> { self.progress.clone() }
+
+    /**
+     * makes another manager report its progress to the progress of this manager
+     * (for instance, the progress of the inner searches of an iterative search)
+     */
+    pub fn share_progress(&self, other: &mut Self) {
+        other.progress = self.progress.clone();
+        other.observers.new_best = self.observers.new_best.clone();
+    }
+
+    /**
+     * registers a callback called on each new best known solution with the solution node,
+     * its value and the elapsed time since the beginning of the search
+     */
+    pub fn on_new_best(&mut self, f: impl FnMut(&N, B, Duration) + Send + 'static) {
+        self.observers.new_best.lock().unwrap().push(Box::new(f));
+    }
+
+    /**
+     * registers a callback called on each new best known solution with the solution built by
+     * the exporter (ToSolution), its value and the elapsed time since the beginning of the
+     * search. The exporter is owned by the callback (for instance a copy of the instance),
+     * as the search space is used by the search when the callback is called.
+     */
+    pub fn on_new_solution<Sol, S>(&mut self, mut exporter: S, mut f: impl FnMut(Sol, B, Duration) + Send + 'static)
+    where S: ToSolution<N, Sol> + Send + 'static {
+        self.on_new_best(move |n, v, t| {
+            let mut node = n.clone();
+            f(exporter.solution(&mut node), v, t);
+        });
+    }
+
+    /**
+     * registers a callback called when the search restarts (with the restart message)
+     */
+    pub fn on_restart(&mut self, f: impl FnMut(&str) + Send + 'static) {
+        self.observers.restart.push(Box::new(f));
+    }
+
+    /**
+     * registers a callback called at the end of each iteration of the search with the
+     * iteration number (starting from 1) and the incumbent value
+     */
+    pub fn on_iteration_end(&mut self, f: impl FnMut(u64, Option<B>) + Send + 'static) {
+        self.observers.iteration_end.push(Box::new(f));
+    }
+
+    /**
+     * registers a callback called when the search stops with its status and the incumbent value
+     */
+    pub fn on_stop(&mut self, f: impl FnMut(SearchStatus, Option<B>) + Send + 'static) {
+        self.observers.stop.push(Box::new(f));
+    }
+
+    /**
+     * notifies the observers that the search restarts
+     */
+    pub fn notify_restart(&mut self, msg: &str) {
+        for f in self.observers.restart.iter_mut() { f(msg); }
+    }
+
+    /**
+     * notifies the observers that an iteration of the search ended
+     */
+    pub fn notify_iteration_end(&mut self) {
+        self.observers.nb_iterations += 1;
+        let (i, v) = (self.observers.nb_iterations, self.incumbent_val());
+        for f in self.observers.iteration_end.iter_mut() { f(i, v); }
+    }
+
+    /**
+     * registers a node expansion that generated nb_generated children
+     */
+    pub fn notify_expansion(&mut self, nb_generated: usize) {
+        self.progress.notify_expansion(nb_generated);
+    }
+
+    /**
+     * registers a dual bound (ignored if it does not improve the best known dual bound)
+     */
+    pub fn update_dual_bound(&mut self, b: B) {
+        self.progress.update_dual_bound(b);
+    }
 
     /**
      * returns true if current objective is better than the best known solution objective
      */
     pub fn is_better(&self, e: B) -> bool {
-        match self.best_val {
-            Some(a) => e < a,
+        match self.incumbent_val() {
+            Some(a) => self.sense().is_better(&e, &a),
             None => true,
         }
     }
@@ -55,20 +427,202 @@ impl<N:Clone, B:PartialOrd+Copy> SearchManager<N, B> {
      */
     pub fn update_best(&mut self, s: N, e: B) {
         if self.is_better(e) {
+            // the observers are shared with the inner searches: only notifies the solutions
+            // that improve the shared progress (not the ones given by another manager)
+            if self.progress.update_best_val(e) {
+                let t = self.progress.elapsed_time();
+                for f in self.observers.new_best.lock().unwrap().iter_mut() { f(&s, e, t); }
+            }
             self.best = Some(s);
             self.best_val = Some(e);
         }
     }
 
+    /**
+     * warm start: registers a solution known before the search and its value. If the objective
+     * sense is not set yet, it is registered when the search starts (set_sense).
+     */
+    pub fn warm_start(&mut self, s: N, e: B) {
+        if self.sense_known { self.update_best(s, e); }
+        else { self.pending_warm_starts.push((Some(s), e)); }
+    }
+
+    /**
+     * warm start: registers the value of a solution known before the search (an upper bound
+     * of the optimal value, or a lower bound for maximization problems). Only solutions strictly better than it are considered as
+     * new-best-known solutions. The algorithms notify it to the search space
+     * (SearchSpace::notify_incumbent) when they start. If the objective sense is not set yet,
+     * the value is compared with the other warm starts when the search starts (set_sense).
+     */
+    pub fn set_upper_bound(&mut self, e: B) {
+        if !self.sense_known {
+            self.pending_warm_starts.push((None, e));
+        } else if self.is_better(e) {
+            self.upper_bound = Some(e);
+            self.progress.update_best_val(e);
+        }
+    }
+
+    /**
+     * returns each component of the incumbent value (one per level for lexicographic
+     * objectives)
+     */
+    pub fn incumbent_components(&self) -> Option<Vec<serde_json::Value>> where B:Objective {
+        self.incumbent_val().map(|v| v.component_values())
+    }
+
+    /**
+     * registers the incumbent value (and its components) in a json file
+     */
+    pub fn json_statistics(&self, json:&mut serde_json::Value) where B:Objective {
+        if let Some(v) = self.incumbent_val() {
+            json["primal"] = serde_json::json!(v);
+            json["primal_components"] = serde_json::json!(v.component_values());
+        }
+    }
+
+    /**
+     * exports the best known solution, the warm start value and the progress of the search
+     * (to checkpoint the search)
+     */
+    pub fn checkpoint(&self) -> serde_json::Value where N:Serialize, B:Serialize {
+        serde_json::json!({
+            "best": self.best,
+            "best_val": self.best_val,
+            "upper_bound": self.upper_bound,
+            "nb_expanded": self.progress.nb_expanded(),
+            "nb_generated": self.progress.nb_generated(),
+            "dual_bound": self.progress.dual_bound(),
+            "last_improvement_expanded": self.progress.last_improvement_expanded.load(Ordering::Relaxed),
+        })
+    }
+
+    /**
+     * restores the best known solution, the warm start value and the progress of the search
+     * from a checkpoint (the objective sense should be set before)
+     */
+    pub fn restore(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error>
+    where N:DeserializeOwned, B:DeserializeOwned {
+        self.best = serde_json::from_value(json["best"].clone())?;
+        self.best_val = serde_json::from_value(json["best_val"].clone())?;
+        self.upper_bound = serde_json::from_value(json["upper_bound"].clone())?;
+        self.finished = false;
+        self.proven = false;
+        self.progress.nb_expanded.store(serde_json::from_value(json["nb_expanded"].clone())?, Ordering::Relaxed);
+        self.progress.nb_generated.store(serde_json::from_value(json["nb_generated"].clone())?, Ordering::Relaxed);
+        self.progress.dual_bound.set(serde_json::from_value(json["dual_bound"].clone())?);
+        self.progress.best_val.set(self.incumbent_val());
+        self.progress.last_improvement_time.set(Instant::now());
+        self.progress.last_improvement_expanded.store(
+            serde_json::from_value(json["last_improvement_expanded"].clone())?, Ordering::Relaxed
+        );
+        Ok(())
+    }
+
     /**
      * updates another manager to contain the same information
      */
     pub fn give_best(&mut self, other: &mut Self) {
-        match self.best_val {
-            None => {},
-            Some(b) => {
-                other.update_best(self.best.as_ref().unwrap().clone(), b);
-            }
-        };
+        if let (Some(n), Some(b)) = (&self.best, self.best_val) {
+            other.update_best(n.clone(), b);
+        }
+        if let Some(b) = self.upper_bound {
+            other.set_upper_bound(b);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion, TimeStoppingCriterion, NodeBudgetStoppingCriterion};
+    use crate::tree_search::beam_search::{BeamSearch, create_iterative_beam_search};
+    use crate::tree_search::beam_search_pe::PEBeamSearch;
+    use crate::tree_search::best_first::BestFirstSearch;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+    use crate::tree_search::greedy::Greedy;
+    use crate::test_spaces::{Node, space, run};
+
+    #[test]
+    fn not_started() {
+        let algo:BestFirstSearch<Node, i64, i64, _> = BestFirstSearch::new(space(true));
+        assert_eq!(algo.status(), SearchStatus::Unknown);
+    }
+
+    #[test]
+    fn complete_runs() {
+        assert_eq!(run(BestFirstSearch::new(space(true)), NeverStoppingCriterion::default()), SearchStatus::Optimal);
+        assert_eq!(run(DepthFirstSearch::new(space(true)), NeverStoppingCriterion::default()), SearchStatus::Optimal);
+        assert_eq!(run(BeamSearch::new(space(true), 100), NeverStoppingCriterion::default()), SearchStatus::Optimal);
+        assert_eq!(run(PEBeamSearch::new(space(true), 100), NeverStoppingCriterion::default()), SearchStatus::Optimal);
+        assert_eq!(run(create_iterative_beam_search(space(true), 1., 2.), NeverStoppingCriterion::default()), SearchStatus::Optimal);
+    }
+
+    #[test]
+    fn heuristic_pruning() {
+        assert_eq!(run(BeamSearch::new(space(true), 1), NeverStoppingCriterion::default()), SearchStatus::Feasible);
+        assert_eq!(run(Greedy::new(space(true)), NeverStoppingCriterion::default()), SearchStatus::Feasible);
+    }
+
+    #[test]
+    fn infeasible_runs() {
+        assert_eq!(run(BestFirstSearch::new(space(false)), NeverStoppingCriterion::default()), SearchStatus::Infeasible);
+        assert_eq!(run(DepthFirstSearch::new(space(false)), NeverStoppingCriterion::default()), SearchStatus::Infeasible);
+        assert_eq!(run(create_iterative_beam_search(space(false), 1., 2.), NeverStoppingCriterion::default()), SearchStatus::Infeasible);
+        assert_eq!(run(Greedy::new(space(false)), NeverStoppingCriterion::default()), SearchStatus::NoSolution);
+    }
+
+    #[test]
+    fn time_limit_before_any_pruning() {
+        // regression: the search used to report "optimal" if the time limit was exceeded before any
+        // heuristic fathoming
+        assert_eq!(run(BestFirstSearch::new(space(true)), TimeStoppingCriterion::new(0.)), SearchStatus::NoSolution);
+        assert_eq!(run(DepthFirstSearch::new(space(true)), TimeStoppingCriterion::new(0.)), SearchStatus::NoSolution);
+        assert_eq!(run(BeamSearch::new(space(true), 100), TimeStoppingCriterion::new(0.)), SearchStatus::NoSolution);
+        assert_eq!(run(PEBeamSearch::new(space(true), 100), TimeStoppingCriterion::new(0.)), SearchStatus::NoSolution);
+        assert_eq!(run(create_iterative_beam_search(space(true), 1., 2.), TimeStoppingCriterion::new(0.)), SearchStatus::NoSolution);
+    }
+
+    #[test]
+    fn interrupted_after_first_solution() {
+        let mut beam:BeamSearch<Node, i64, i64, _> = BeamSearch::new(space(true), 100);
+        let beam_budget = NodeBudgetStoppingCriterion::expanded(beam.get_manager(), 4);
+        assert_eq!(run(beam, beam_budget), SearchStatus::Feasible);
+        let mut bfs:BestFirstSearch<Node, i64, i64, _> = BestFirstSearch::new(space(true));
+        let bfs_budget = NodeBudgetStoppingCriterion::expanded(bfs.get_manager(), 5);
+        assert_eq!(run(bfs, bfs_budget), SearchStatus::Feasible);
+    }
+
+    /// exports a node to a textual solution
+    struct CostExporter;
+
+    impl ToSolution<Node, String> for CostExporter {
+        fn solution(&mut self, n:&mut Node) -> String { format!("cost {}", n.cost) }
+    }
+
+    /// returns a callback that appends an event to the given list
+    fn recorder(events:&Arc<Mutex<Vec<String>>>) -> impl Fn(String) + Send {
+        let list = events.clone();
+        move |event| Mutex::lock(&list).unwrap().push(event)
+    }
+
+    #[test]
+    fn observers() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut algo = create_iterative_beam_search(space(true), 1., 2.);
+        let manager = algo.get_manager();
+        manager.on_new_solution(CostExporter, { let record = recorder(&events); move |sol, v, _| record(format!("best {} {}", sol, v)) });
+        manager.on_restart({ let record = recorder(&events); move |msg| record(format!("restart {}", msg)) });
+        manager.on_iteration_end({ let record = recorder(&events); move |i, v| record(format!("iteration {} {:?}", i, v)) });
+        manager.on_stop({ let record = recorder(&events); move |status, v| record(format!("stop {:?} {:?}", status, v)) });
+        algo.run(NeverStoppingCriterion::default());
+        // the solutions given by the iterative search to its iterations are not notified again
+        assert_eq!(*Mutex::lock(&events).unwrap(), vec![
+            "restart Iter D=1", "best cost 0 0", "iteration 1 Some(0)",
+            "restart Iter D=2", "iteration 2 Some(0)",
+            "restart Iter D=4", "iteration 3 Some(0)",
+            "stop Optimal Some(0)",
+        ].into_iter().map(String::from).collect::<Vec<_>>());
     }
 }
@@ -0,0 +1,518 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+
+use crate::search_manager::SearchStatus;
+use crate::search_combinator::StackDescription;
+
+
+/**
+ * point of a performance profile: counters at the time a new best solution was found
+ */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfilePoint {
+    /// elapsed time (in seconds)
+    pub time: f64,
+    /// counters (number of expanded nodes, generated nodes, etc.)
+    #[serde(default)]
+    pub counters: BTreeMap<String, u64>,
+    /// value of the solution (if any)
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+}
+
+
+/**
+ * section of a search report (one per combinator or algorithm). Keys are namespaced by the
+ * section, so two combinators can use the same key names. In the flat statistics
+ * (json_statistics), they are prefixed by the name of the section ("pruning_nb_pruned").
+ */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReportSection {
+    /// name of the section (for instance "pruning")
+    pub name: String,
+    /// integer counters (number of prunings, etc.)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub counters: BTreeMap<String, u64>,
+    /// other measured values (bounds, gaps, ratios, etc.)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub values: BTreeMap<String, serde_json::Value>,
+    /// parameters of the component
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, serde_json::Value>,
+    /// timings (in seconds)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub timings: BTreeMap<String, f64>,
+    /// performance profile (one point per new best solution)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub perf_profile: Vec<ProfilePoint>,
+    /// true if the keys are not prefixed in the flat statistics (main statistics of the search,
+    /// read by the experiment scripts, and flat statistics of a search space)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub unprefixed: bool,
+}
+
+fn is_false(b:&bool) -> bool { !*b }
+
+impl ReportSection {
+    /** creates an empty section */
+    pub fn new(name:&str) -> Self {
+        Self { name: name.to_string(), ..Self::default() }
+    }
+
+    /** adds a counter */
+    pub fn with_counter(mut self, key:&str, v:u64) -> Self {
+        self.counters.insert(key.to_string(), v);
+        self
+    }
+
+    /** adds a value */
+    pub fn with_value<T:Serialize>(mut self, key:&str, v:T) -> Self {
+        self.values.insert(key.to_string(), json!(v));
+        self
+    }
+
+    /** adds a parameter */
+    pub fn with_parameter<T:Serialize>(mut self, key:&str, v:T) -> Self {
+        self.parameters.insert(key.to_string(), json!(v));
+        self
+    }
+
+    /** adds each field of a parameter structure as a parameter */
+    pub fn with_parameters<T:Serialize>(mut self, params:&T) -> Self {
+        if let serde_json::Value::Object(map) = json!(params) {
+            self.parameters.extend(map);
+        }
+        self
+    }
+
+    /** adds a timing (in seconds) */
+    pub fn with_timing(mut self, key:&str, seconds:f64) -> Self {
+        self.timings.insert(key.to_string(), seconds);
+        self
+    }
+
+    /** sets the performance profile */
+    pub fn with_perf_profile(mut self, points:Vec<ProfilePoint>) -> Self {
+        self.perf_profile = points;
+        self
+    }
+
+    /** writes the keys of the section without prefix in the flat statistics */
+    pub fn unprefixed(mut self) -> Self {
+        self.unprefixed = true;
+        self
+    }
+
+    /**
+     * writes the section as flat statistics: "<section>_<key>" for the parameters, counters
+     * and values, "<section>_time_<key>" for the timings and "<section>_primal_pareto_diagram"
+     * for the performance profile (without the "<section>_" prefix if the section is unprefixed)
+     */
+    pub fn write_statistics(&self, json:&mut serde_json::Value) {
+        let prefix = if self.unprefixed { String::new() } else { format!("{}_", self.name) };
+        for (k, v) in &self.parameters { json[format!("{}{}", prefix, k)] = v.clone(); }
+        for (k, v) in &self.counters { json[format!("{}{}", prefix, k)] = json!(v); }
+        for (k, v) in &self.values { json[format!("{}{}", prefix, k)] = v.clone(); }
+        for (k, v) in &self.timings { json[format!("{}time_{}", prefix, k)] = json!(v); }
+        if !self.perf_profile.is_empty() {
+            json[format!("{}primal_pareto_diagram", prefix)] = profile_to_flat_json(&self.perf_profile);
+        }
+    }
+
+    /**
+     * builds a section from a flat json object (as written by json_statistics): non-negative
+     * integers become counters, performance profiles ("primal_pareto_diagram") are converted,
+     * other entries become values. The section is unprefixed (its keys are written back as is).
+     */
+    pub fn from_flat_json(name:&str, json:&serde_json::Value) -> Self {
+        let mut res = Self::new(name).unprefixed();
+        if let Some(map) = json.as_object() {
+            for (k, v) in map {
+                if k == "primal_pareto_diagram" {
+                    res.perf_profile = profile_from_flat_json(v);
+                } else if let Some(i) = v.as_u64() {
+                    res.counters.insert(k.clone(), i);
+                } else {
+                    res.values.insert(k.clone(), v.clone());
+                }
+            }
+        }
+        res
+    }
+
+    /** returns true if the section contains no information */
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty() && self.values.is_empty() && self.parameters.is_empty()
+            && self.timings.is_empty() && self.perf_profile.is_empty()
+    }
+}
+
+/// converts a performance profile to its flat format (one object per point: counters, time and primal)
+fn profile_to_flat_json(points:&[ProfilePoint]) -> serde_json::Value {
+    json!(points.iter().map(|p| {
+        let mut res = json!(p.counters);
+        res["time"] = json!(p.time);
+        if let Some(v) = &p.value { res["primal"] = v.clone(); }
+        res
+    }).collect::<Vec<_>>())
+}
+
+/// converts a performance profile written by the statistics combinator (before the reports)
+fn profile_from_flat_json(json:&serde_json::Value) -> Vec<ProfilePoint> {
+    json.as_array().map(|points| points.iter().map(|p| {
+        let mut point = ProfilePoint::default();
+        if let Some(map) = p.as_object() {
+            for (k, v) in map {
+                match (k.as_str(), v.as_u64()) {
+                    ("time", _) => point.time = v.as_f64().unwrap_or(0.),
+                    ("primal", _) => point.value = Some(v.clone()),
+                    (_, Some(i)) => { point.counters.insert(k.clone(), i); },
+                    _ => {}
+                }
+            }
+        }
+        point
+    }).collect()).unwrap_or_default()
+}
+
+
+/**
+ * structured report of a search: status of the search, description of the combinator stack
+ * and one section per component (the algorithm, then the combinators in the stack order). It
+ * can be exported to JSON or CSV, and loaded back to compare runs.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchReport {
+    /// status of the search (if the algorithm reported it)
+    #[serde(default)]
+    pub status: Option<SearchStatus>,
+    /// layers of the search space (filled by SearchSpace::report, see SearchSpace::describe)
+    #[serde(default)]
+    pub stack: Option<StackDescription>,
+    /// sections (in the stack order)
+    #[serde(default)]
+    pub sections: Vec<ReportSection>,
+}
+
+impl SearchReport {
+    /**
+     * adds a section at the end of the report. A section named after a previous one (for
+     * instance a second pruning layer) is numbered by its rank among them ("pruning#2")
+     */
+    pub fn push(&mut self, mut section:ReportSection) {
+        let numbered = format!("{}#", section.name);
+        let nb_same = self.sections.iter()
+            .filter(|s| s.name == section.name || s.name.starts_with(&numbered))
+            .count();
+        if nb_same > 0 {
+            section.name = format!("{}#{}", section.name, nb_same+1);
+        }
+        self.sections.push(section);
+    }
+
+    /** returns the section with the given name (the sections are named uniquely by push) */
+    pub fn section(&self, name:&str) -> Option<&ReportSection> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /** returns a counter of a section */
+    pub fn counter(&self, section:&str, key:&str) -> Option<u64> {
+        self.section(section).and_then(|s| s.counters.get(key).copied())
+    }
+
+    /** returns a value of a section */
+    pub fn value(&self, section:&str, key:&str) -> Option<&serde_json::Value> {
+        self.section(section).and_then(|s| s.values.get(key))
+    }
+
+    /**
+     * returns the counters that differ between two reports as
+     * (section, key, value in self, value in other)
+     */
+    pub fn counter_differences(&self, other:&Self) -> Vec<(String, String, Option<u64>, Option<u64>)> {
+        let mut res = Vec::new();
+        let mut keys:Vec<(&str, &str)> = Vec::new();
+        for s in self.sections.iter().chain(other.sections.iter()) {
+            for k in s.counters.keys() {
+                if !keys.contains(&(s.name.as_str(), k.as_str())) {
+                    keys.push((s.name.as_str(), k.as_str()));
+                }
+            }
+        }
+        for (s, k) in keys {
+            let (a, b) = (self.counter(s, k), other.counter(s, k));
+            if a != b { res.push((s.to_string(), k.to_string(), a, b)); }
+        }
+        res
+    }
+
+    /** exports the report to JSON */
+    pub fn to_json(&self) -> serde_json::Value { json!(self) }
+
+    /**
+     * writes the report as flat statistics: the status, the stack ("Stats > Pruning > Problem"),
+     * then the entries of each section (see ReportSection::write_statistics)
+     */
+    pub fn write_statistics(&self, json:&mut serde_json::Value) {
+        if let Some(status) = self.status {
+            json["status"] = json!(status);
+            json["is_optimal"] = json!(status == SearchStatus::Optimal);
+        }
+        if let Some(stack) = &self.stack {
+            json["stack"] = json!(stack.to_string());
+        }
+        for s in &self.sections { s.write_statistics(json); }
+    }
+
+    /**
+     * builds the report of a component (given its report method) and writes it as flat
+     * statistics. The components implement json_statistics with it, for instance:
+     * `SearchReport::statistics(|r| self.report(r), json)`
+     */
+    pub fn statistics<F:FnOnce(&mut Self)>(report:F, json:&mut serde_json::Value) {
+        let mut res = Self::default();
+        report(&mut res);
+        res.write_statistics(json);
+    }
+
+    /**
+     * loads a report from JSON. Flat statistics (written by json_statistics) are accepted:
+     * they are loaded as a single "statistics" section
+     */
+    pub fn from_json(json:&serde_json::Value) -> Result<Self, serde_json::Error> {
+        if json.get("sections").is_some() {
+            return serde_json::from_value(json.clone());
+        }
+        let mut flat = json.clone();
+        let mut res = Self::default();
+        if let Some(map) = flat.as_object_mut() {
+            if let Some(status) = map.remove("status") {
+                res.status = serde_json::from_value(status)?;
+            }
+            map.remove("is_optimal");
+        }
+        res.push(ReportSection::from_flat_json("statistics", &flat));
+        Ok(res)
+    }
+
+    /**
+     * exports the report to CSV: one line per entry (section, kind, key, value). The
+     * performance profile points are written as JSON values (key: index of the point)
+     */
+    pub fn to_csv(&self) -> String {
+        let mut res = String::from("section,kind,key,value\n");
+        let mut line = |section:&str, kind:&str, key:&str, value:String| {
+            let fields = [section, kind, key, value.as_str()].map(csv_field);
+            res.push_str(&fields.join(","));
+            res.push('\n');
+        };
+        if let Some(status) = self.status {
+            line("search", "status", "status", json_to_csv(&json!(status)));
+        }
+        if let Some(stack) = &self.stack {
+            line("search", "stack", "stack", stack.to_string());
+        }
+        for s in &self.sections {
+            for (k, v) in &s.counters { line(&s.name, "counter", k, v.to_string()); }
+            for (k, v) in &s.values { line(&s.name, "value", k, json_to_csv(v)); }
+            for (k, v) in &s.parameters { line(&s.name, "parameter", k, json_to_csv(v)); }
+            for (k, v) in &s.timings { line(&s.name, "timing", k, v.to_string()); }
+            for (i, p) in s.perf_profile.iter().enumerate() {
+                line(&s.name, "perf_profile", &i.to_string(), json!(p).to_string());
+            }
+        }
+        res
+    }
+
+    /** writes the report to a JSON file */
+    pub fn save_json<P:AsRef<Path>>(&self, path:P) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /** writes the report to a CSV file */
+    pub fn save_csv<P:AsRef<Path>>(&self, path:P) -> io::Result<()> {
+        fs::write(path, self.to_csv())
+    }
+
+    /** loads a report (or flat statistics) from a JSON file */
+    pub fn load_json<P:AsRef<Path>>(path:P) -> io::Result<Self> {
+        let json:serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Ok(Self::from_json(&json)?)
+    }
+}
+
+/// writes a json value in a CSV cell (strings are written without quotes)
+fn json_to_csv(v:&serde_json::Value) -> String {
+    match v {
+        serde_json::Value::String(s) => s.clone(),
+        _ => v.to_string(),
+    }
+}
+
+/// escapes a CSV field (RFC 4180)
+fn csv_field(s:&str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::search_combinator::LayerDescription;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion};
+    use crate::search_space::{SearchSpace, ObjectiveSense};
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::combinators::bounding::BoundingCombinator;
+    use crate::combinators::gcost_dominance::GcostDominanceTsCombinator;
+    use crate::combinators::stats::StatTsCombinator;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+    use crate::test_spaces::{Node, BinaryTree};
+
+    fn report() -> SearchReport {
+        let stack = StackDescription {
+            layers: vec![LayerDescription::new("Stats"), LayerDescription::new("Pruning"), LayerDescription::new("Problem")],
+        };
+        let mut res = SearchReport { status: Some(SearchStatus::Optimal), stack: Some(stack), sections: vec![] };
+        res.push(ReportSection::new("stats")
+            .with_counter("nb_expanded", 10)
+            .with_timing("searched", 0.5)
+            .with_perf_profile(vec![ProfilePoint { time: 0.1, counters: BTreeMap::new(), value: Some(json!(3)) }])
+        );
+        res.push(ReportSection::new("pruning")
+            .with_counter("nb_pruned", 2)
+            .with_value("primal_bound", 3)
+            .with_parameter("comment", "a, \"b\"")
+        );
+        res
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let r = report();
+        assert_eq!(SearchReport::from_json(&r.to_json()).unwrap(), r);
+        assert_eq!(r.counter("pruning", "nb_pruned"), Some(2));
+        assert_eq!(r.value("pruning", "primal_bound"), Some(&json!(3)));
+        assert_eq!(r.counter("stats", "nb_pruned"), None);
+    }
+
+    #[test]
+    fn flat_statistics() {
+        let flat = json!({
+            "status": "Feasible", "is_optimal": false, "nb_pruned": 4, "gap": 0.5,
+            "primal_pareto_diagram": [{"expanded": 3, "time": 0.25, "primal": 7}],
+        });
+        let r = SearchReport::from_json(&flat).unwrap();
+        assert_eq!(r.status, Some(SearchStatus::Feasible));
+        assert_eq!(r.counter("statistics", "nb_pruned"), Some(4));
+        assert_eq!(r.value("statistics", "gap"), Some(&json!(0.5)));
+        let profile = &r.section("statistics").unwrap().perf_profile;
+        assert_eq!(profile[0].time, 0.25);
+        assert_eq!(profile[0].counters["expanded"], 3);
+        assert_eq!(profile[0].value, Some(json!(7)));
+        // flat statistics are written back as they were loaded
+        let mut written = json!({});
+        r.write_statistics(&mut written);
+        assert_eq!(written, flat);
+    }
+
+    #[test]
+    fn namespaced_statistics() {
+        let tree = BinaryTree::new(ObjectiveSense::Minimize);
+        let space = Rc::new(RefCell::new(StatTsCombinator::<_, i64>::new(PruningCombinator::<_, i64>::new(
+            GcostDominanceTsCombinator::<_, usize, i64>::new(PruningCombinator::<_, i64>::new(tree))
+        ))));
+        let mut algo:DepthFirstSearch<_, i64, i64, _> = DepthFirstSearch::new(space.clone());
+        algo.run(NeverStoppingCriterion::default());
+        let mut r = SearchReport::default();
+        space.borrow().report(&mut r);
+        // two sections with the same name are numbered
+        let names:Vec<&str> = r.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["stats", "pruning", "gcost_dominance", "pruning#2"]);
+        assert!(r.section("pruning#2").is_some());
+        // the flat statistics are the report: the keys are prefixed by their section
+        let mut flat = json!({});
+        space.borrow().json_statistics(&mut flat);
+        assert_eq!(flat["pruning_nb_pruned"].as_u64(), r.counter("pruning", "nb_pruned"));
+        assert_eq!(flat["pruning#2_nb_pruned"].as_u64(), r.counter("pruning#2", "nb_pruned"));
+        assert_eq!(flat["gcost_dominance_nb_pruned"].as_u64(), r.counter("gcost_dominance", "nb_pruned"));
+        assert!(flat.get("nb_pruned").is_none());
+        // except for the main statistics
+        assert_eq!(flat["nb_expanded"].as_u64(), r.counter("stats", "nb_expanded"));
+        assert!(flat["time_searched"].is_f64());
+        assert_eq!(flat["best_primal"], json!(0));
+        assert_eq!(flat["primal_pareto_diagram"].as_array().unwrap().len(), r.section("stats").unwrap().perf_profile.len());
+        // the algorithm writes its status
+        let mut algo_flat = json!({});
+        algo.json_statistics(&mut algo_flat);
+        assert_eq!(algo_flat, json!({"status": "Optimal", "is_optimal": true}));
+    }
+
+    #[test]
+    fn csv_export() {
+        let csv = report().to_csv();
+        let lines:Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "section,kind,key,value");
+        assert_eq!(lines[1], "search,status,status,Optimal");
+        assert_eq!(lines[2], "search,stack,stack,Stats > Pruning > Problem");
+        assert!(lines.contains(&"stats,counter,nb_expanded,10"));
+        assert!(lines.contains(&"stats,timing,searched,0.5"));
+        assert!(lines.contains(&"pruning,parameter,comment,\"a, \"\"b\"\"\""));
+    }
+
+    #[test]
+    fn differences() {
+        let a = report();
+        let mut b = report();
+        b.sections[1].counters.insert("nb_pruned".to_string(), 5);
+        b.sections[0].counters.insert("nb_generated".to_string(), 1);
+        assert_eq!(a.counter_differences(&b), vec![
+            ("pruning".to_string(), "nb_pruned".to_string(), Some(2), Some(5)),
+            ("stats".to_string(), "nb_generated".to_string(), None, Some(1)),
+        ]);
+    }
+
+    #[test]
+    fn combinator_sections() {
+        let tree = BinaryTree::new(ObjectiveSense::Minimize);
+        let space = Rc::new(RefCell::new(StatTsCombinator::<_, i64>::new(BoundingCombinator::<_, i64, Node>::new(PruningCombinator::<_, i64>::new(tree)))));
+        let mut algo:DepthFirstSearch<_, i64, i64, _> = DepthFirstSearch::new(space.clone());
+        algo.run(NeverStoppingCriterion::default());
+        let mut r = SearchReport::default();
+        algo.report(&mut r);
+        space.borrow().report(&mut r);
+        // one section per combinator, in the stack order
+        assert_eq!(r.status, Some(SearchStatus::Optimal));
+        let names:Vec<&str> = r.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["stats", "bounding", "pruning"]);
+        // the stack is described (outermost layer first)
+        assert_eq!(space.borrow().describe().names(), vec!["Stats", "Bounding", "Pruning", "Problem"]);
+        // the report contains the same information as the flat statistics
+        let mut flat = json!({});
+        space.borrow().json_statistics(&mut flat);
+        assert_eq!(r.counter("pruning", "nb_pruned"), flat["pruning_nb_pruned"].as_u64());
+        assert_eq!(r.counter("stats", "nb_expanded"), flat["nb_expanded"].as_u64());
+        assert_eq!(r.value("pruning", "primal_bound"), Some(&json!(0)));
+        assert_eq!(r.value("bounding", "gap"), Some(&json!(0.)));
+        assert!(!r.section("stats").unwrap().perf_profile.is_empty());
+        // reports can be loaded back
+        let path = std::env::temp_dir().join(format!("dogs_report_{}.json", std::process::id()));
+        r.save_json(&path).unwrap();
+        let loaded = SearchReport::load_json(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        // (timings may differ in the last bits when parsed back)
+        assert!(loaded.counter_differences(&r).is_empty());
+        assert_eq!(loaded.value("bounding", "gap"), r.value("bounding", "gap"));
+        assert_eq!(loaded.sections.len(), 3);
+    }
+}
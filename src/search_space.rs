@@ -1,3 +1,51 @@
+use serde::Serialize;
+
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_combinator::{StackDescription, LayerDescription};
+
+/**
+objective sense of a search space
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum ObjectiveSense {
+    /// the smaller, the better
+    #[default]
+    Minimize,
+    /// the larger, the better
+    Maximize,
+}
+
+impl ObjectiveSense {
+    /** returns true iff a is strictly better than b */
+    pub fn is_better<B:PartialOrd>(&self, a:&B, b:&B) -> bool {
+        match self {
+            ObjectiveSense::Minimize => a < b,
+            ObjectiveSense::Maximize => a > b,
+        }
+    }
+
+    /** returns true iff the dual bound b is tighter than the dual bound d */
+    pub fn is_tighter_bound<B:PartialOrd>(&self, b:&B, d:&B) -> bool {
+        self.is_better(d, b)
+    }
+
+    /**
+    relative gap between a primal value and a dual bound (|primal-dual|/|primal|).
+    If the primal value is 0, returns 0 if the gap is closed, infinity otherwise.
+    */
+    pub fn gap(&self, primal:f64, dual:f64) -> f64 {
+        let diff = match self {
+            ObjectiveSense::Minimize => primal - dual,
+            ObjectiveSense::Maximize => dual - primal,
+        };
+        if primal == 0. {
+            if diff <= 0. { 0. } else { f64::INFINITY }
+        } else {
+            diff / primal.abs()
+        }
+    }
+}
+
 /**
 Defines a search space. A search space is a graph structure (directed or not).
 It contains nodes. The initial state is the entry point of the search procedure. 
@@ -29,6 +77,12 @@ pub trait SearchSpace<N,B> {
      */
     fn handle_new_best(&mut self, node: N) -> N { node }
 
+    /**
+     called when the algorithm receives an incumbent value from outside the search (warm start
+     with a known solution or an upper bound). Nodes that cannot improve it can be discarded.
+     */
+    fn notify_incumbent(&mut self, _value: B) {}
+
 
 
     // RESTARTING INFORMATION
@@ -61,6 +115,67 @@ pub trait SearchSpace<N,B> {
      */
     fn json_statistics(&self, _json:&mut serde_json::Value) {}
 
+    /**
+     adds the description of the stack (if the report does not have one yet, see describe) and
+     the sections of the search space (see report_sections) to a search report
+     */
+    fn report(&self, report:&mut SearchReport) {
+        if report.stack.is_none() {
+            report.stack = Some(self.describe());
+        }
+        self.report_sections(report);
+    }
+
+    /**
+     adds the sections of the search space to a search report (the combinators add their own
+     section, then the sections of the underlying space). By default, the statistics written by
+     json_statistics are added as a "space" section.
+     */
+    fn report_sections(&self, report:&mut SearchReport) {
+        let mut json = serde_json::json!({});
+        self.json_statistics(&mut json);
+        let section = ReportSection::from_flat_json("space", &json);
+        if !section.is_empty() { report.push(section); }
+    }
+
+    /**
+     adds the layers of the search space to a stack description (the combinators add their own
+     layer, then the layers of the underlying space). By default, the search space is the
+     "Problem" layer.
+     */
+    fn describe_layers(&self, stack:&mut StackDescription) {
+        stack.push(LayerDescription::new("Problem"));
+    }
+
+    /**
+     returns the ordered list of the layers of the search space (outermost combinator first),
+     for instance "Stats > Pruning > GcostDominance > Problem"
+     */
+    fn describe(&self) -> StackDescription {
+        let mut res = StackDescription::default();
+        self.describe_layers(&mut res);
+        res
+    }
+
+    /**
+     returns the best known dual bound (None if the search space does not measure it)
+     */
+    fn dual_bound(&self) -> Option<B> { None }
+
+    /**
+     returns the objective sense (minimization by default). For maximization problems, bounds
+     are upper bounds of the profit: the search manager, the pruning, bounding and statistics
+     combinators compare values accordingly (guides stay "the smaller, the better")
+     */
+    fn sense(&self) -> ObjectiveSense { ObjectiveSense::Minimize }
+
+    /**
+     returns false if the search space discards nodes heuristically (for instance the limited
+     discrepancy or tabu combinators): exploring all its nodes then does not prove optimality
+     (true by default)
+     */
+    fn is_exact(&self) -> bool { true }
+
     /**
      * requests log headers (does nothing if there is no logging decorator within the algorithm)
      */
@@ -81,6 +196,13 @@ pub trait ToSolution<N,Sol> {
      panics if the node is not a goal
     */
     fn solution(&mut self, node: &mut N) -> Sol;
+
+    /**
+     builds a node representing the given solution (for instance after improving it with a
+     local search). `node` is the node the solution was built from.
+     returns None if the search space does not support this conversion (default behavior).
+    */
+    fn solution_to_node(&mut self, _node: &N, _sol: &Sol) -> Option<N> { None }
 }
 
 /**
@@ -116,6 +238,78 @@ pub trait PartialNeighborGeneration<N> {
 }
 
 
+/**
+    implements a lazy neighbor expansion: the children of a node are generated one at a time
+    from a cursor (the state of the enumeration, kept outside the node). Allows combinators to
+    filter children (pruning, tabu, dominance) without building intermediate vectors.
+    Every IteratorNeighborGeneration is a TotalNeighborGeneration (the children are collected).
+    A search space that generates all children at once (TotalNeighborGeneration) can be wrapped
+    in a TotalAdapter (std::vec::IntoIter<N> cursor), and a partial neighbor expansion in a
+    PartialAdapter (() cursor, the state is kept in the node). See combinators::neighbor_generation.
+ */
+pub trait IteratorNeighborGeneration<N> {
+    /// state of the enumeration of the children of a node
+    type Cursor;
+
+    /**
+        starts the enumeration of the children of a given node
+     */
+    fn neighbors_cursor(&mut self, node: &mut N) -> Self::Cursor;
+
+    /**
+        returns the next neighbor of the enumeration if it exists, or None
+     */
+    fn next_neighbor_from(&mut self, node: &mut N, cursor: &mut Self::Cursor) -> Option<N>;
+
+    /**
+        iterates over the neighbors of a given node
+     */
+    fn neighbors_iter<'a>(&'a mut self, node: &'a mut N) -> NeighborIter<'a, N, Self> where Self:Sized {
+        let cursor = self.neighbors_cursor(node);
+        NeighborIter { space: self, node, cursor }
+    }
+}
+
+/**
+    iterator over the neighbors of a node (see IteratorNeighborGeneration::neighbors_iter)
+ */
+#[derive(Debug)]
+pub struct NeighborIter<'a, N, S:IteratorNeighborGeneration<N>> {
+    space: &'a mut S,
+    node: &'a mut N,
+    cursor: S::Cursor,
+}
+
+impl<'a, N, S:IteratorNeighborGeneration<N>> Iterator for NeighborIter<'a, N, S> {
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> { self.space.next_neighbor_from(self.node, &mut self.cursor) }
+}
+
+impl<N, S:IteratorNeighborGeneration<N>> TotalNeighborGeneration<N> for S {
+    fn neighbors(&mut self, node: &mut N) -> Vec<N> { self.neighbors_iter(node).collect() }
+}
+
+
+/**
+    Represents a search space whose state (statistics counters, dominance store, tabu tenure,
+    etc.) can be saved in a checkpoint and restored to resume a search. Combinators save their
+    own state and forward to the search space they wrap. Stateless search spaces can use the
+    default implementation (nothing to save).
+ */
+pub trait CheckpointSpace {
+    /**
+        registers the state of the search space in a json value
+     */
+    fn save_state(&self, _json: &mut serde_json::Value) {}
+
+    /**
+        restores the state of the search space from a json value (built by save_state)
+     */
+    fn load_state(&mut self, _json: &serde_json::Value) -> Result<(), serde_json::Error> { Ok(()) }
+}
+
+
 /**
     Allows to identify a node. Useful to implement g-cost-dominance / bucket-lists
     or tabu-search
@@ -140,6 +334,18 @@ pub trait ParetoDominanceSpace<N> {
     fn dominates(&self, a:&N, b:&N) -> bool;
 }
 
+/**
+    Represents a bi-objective search space (both objectives are minimized).
+    The objectives of a node that is not a goal are lower bounds of the objectives of the
+    goals below it (used by the Pareto beam search to prune nodes dominated by the archive).
+ */
+pub trait BiObjectiveSpace<N> {
+    /**
+        returns the objectives of a node
+     */
+    fn objectives(&self, n:&N) -> [f64; 2];
+}
+
 
 /**
     Represents a search space which there exists a bound on the distance between the root node and
@@ -172,4 +378,82 @@ pub trait DecisionSpace<N,D> {
     used in tabu search. If true, a tabu move is still autorized.
     */
     fn aspiration_criterion(&self, _n:&N) -> bool { false }
-}
\ No newline at end of file
+}
+
+/** SolutionDistanceSpace. Defines a distance between solutions
+    common usages:
+    - elite pools (keep a diverse set of good solutions)
+*/
+pub trait SolutionDistanceSpace<Sol> {
+    /// distance between two solutions (0 if they are identical)
+    fn solution_distance(&self, a:&Sol, b:&Sol) -> f64;
+}
+
+/** SolutionCostSpace. Evaluates complete solutions
+    common usages:
+    - solutions improved outside of the tree (local search) that cannot be mapped back to a node
+*/
+pub trait SolutionCostSpace<Sol, B> {
+    /// cost of the solution (comparable with the bounds of the nodes)
+    fn solution_cost(&self, sol:&Sol) -> B;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion, NodeBudgetStoppingCriterion};
+    use crate::search_manager::SearchStatus;
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::combinators::bounding::BoundingCombinator;
+    use crate::combinators::stats::StatTsCombinator;
+    use crate::tree_search::best_first::BestFirstSearch;
+    use crate::tree_search::depth_first::DepthFirstSearch;
+    use crate::test_spaces::{Node, BinaryTree};
+
+    #[test]
+    fn lazy_neighbors() {
+        // the children are generated (and pruned) only when the iterator requests them
+        let mut space = PruningCombinator::new(StatTsCombinator::new(BinaryTree::new(ObjectiveSense::Minimize)));
+        space.notify_incumbent(1);
+        let mut root = space.initial();
+        assert_eq!(space.neighbors_iter(&mut root).next().map(|c| c.cost), Some(0));
+        let mut json = serde_json::json!({});
+        space.json_statistics(&mut json);
+        assert_eq!(json["nb_generated"], serde_json::json!(1));
+        assert_eq!(json["pruning_nb_pruned"], serde_json::json!(0));
+        // the child of cost 1 cannot improve the incumbent
+        let mut other_root = space.initial();
+        assert_eq!(space.neighbors(&mut other_root).len(), 1);
+        space.json_statistics(&mut json);
+        assert_eq!(json["nb_generated"], serde_json::json!(3));
+        assert_eq!(json["pruning_nb_pruned"], serde_json::json!(1));
+    }
+
+    #[test]
+    fn maximization() {
+        let pruned_space = Rc::new(RefCell::new(PruningCombinator::new(BinaryTree::new(ObjectiveSense::Maximize))));
+        let mut dfs:DepthFirstSearch<Node, i64, i64, _> = DepthFirstSearch::new(pruned_space.clone());
+        dfs.run(NeverStoppingCriterion::default());
+        assert_eq!(dfs.status(), SearchStatus::Optimal);
+        assert_eq!(*dfs.get_manager().best_val(), Some(3));
+        assert_eq!(dfs.get_manager().progress().best_val(), Some(3));
+        let mut pruning_json = serde_json::json!({});
+        pruned_space.borrow().json_statistics(&mut pruning_json);
+        assert_eq!(pruning_json["pruning_primal_bound"], serde_json::json!(3));
+        assert!(pruning_json["pruning_nb_pruned"].as_u64().unwrap() > 0);
+        // the dual bound decreases towards the optimal value
+        let bounded_space = Rc::new(RefCell::new(BoundingCombinator::new(BinaryTree::new(ObjectiveSense::Maximize))));
+        let mut bfs:BestFirstSearch<_, i64, i64, _> = BestFirstSearch::new(bounded_space.clone());
+        bfs.warm_start_value(1);
+        let budget = NodeBudgetStoppingCriterion::expanded(bfs.get_manager(), 2);
+        bfs.run(budget);
+        let dual = bounded_space.borrow().dual_bound();
+        assert_eq!(dual, Some(3));
+        let mut bounding_json = serde_json::json!({});
+        bounded_space.borrow().json_statistics(&mut bounding_json);
+        assert_eq!(bounding_json["bounding_gap"], serde_json::json!(2.));
+    }
+}
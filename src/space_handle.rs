@@ -0,0 +1,90 @@
+use std::cell::{RefCell, RefMut};
+use std::ops::DerefMut;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/**
+shared ownership of a search space by an algorithm (and by the user that keeps a handle to read
+the statistics after the search). Also used for the state shared by the nodes of a search (for
+instance the bound set of the BoundingCombinator).
+ - `Rc<RefCell<Space>>`: single-threaded (default)
+ - `Arc<Mutex<Space>>`: thread-safe. The algorithm is `Send` if the search space is, so the search
+   can be moved to a worker thread (and several searches can run concurrently).
+*/
+pub trait SpaceHandle<Space>: Clone {
+    /// exclusive access to the search space
+    type Guard<'a>: DerefMut<Target=Space> where Self: 'a;
+
+    /**
+    returns an exclusive access to the search space (panics if it is already accessed, or if
+    the mutex is poisoned)
+    */
+    fn lock(&self) -> Self::Guard<'_>;
+
+    /** builds a handle owning the value */
+    fn from_value(value:Space) -> Self;
+}
+
+impl<Space> SpaceHandle<Space> for Rc<RefCell<Space>> {
+    type Guard<'a> = RefMut<'a, Space> where Self: 'a;
+
+    fn lock(&self) -> Self::Guard<'_> { self.borrow_mut() }
+
+    fn from_value(value:Space) -> Self { Rc::new(RefCell::new(value)) }
+}
+
+impl<Space> SpaceHandle<Space> for Arc<Mutex<Space>> {
+    type Guard<'a> = MutexGuard<'a, Space> where Self: 'a;
+
+    fn lock(&self) -> Self::Guard<'_> { Mutex::lock(self).unwrap() }
+
+    fn from_value(value:Space) -> Self { Arc::new(Mutex::new(value)) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion};
+    use crate::search_manager::SearchStatus;
+    use crate::search_space::{SearchSpace, ObjectiveSense};
+    use crate::combinators::pruning::PruningCombinator;
+    use crate::combinators::bounding::SyncBoundingCombinator;
+    use crate::combinators::stats::StatTsCombinator;
+    use crate::tree_search::beam_search::BeamSearch;
+    use crate::test_spaces::{Node, BinaryTree};
+
+    fn increment<H:SpaceHandle<u32>>(handle:&H) { *handle.lock() += 1; }
+
+    #[test]
+    fn both_handles() {
+        let rc = Rc::new(RefCell::new(0));
+        increment(&rc);
+        assert_eq!(*rc.borrow(), 1);
+        let arc = Arc::new(Mutex::new(0));
+        increment(&arc);
+        assert_eq!(*Mutex::lock(&arc).unwrap(), 1);
+    }
+
+    #[test]
+    fn worker_threads() {
+        // searches sharing their space through an Arc<Mutex<_>> can run on worker threads
+        let workers:Vec<_> = (0..4).map(|_| {
+            let tree = BinaryTree::new(ObjectiveSense::Minimize);
+            let space = Arc::new(Mutex::new(StatTsCombinator::<_, i64>::new(SyncBoundingCombinator::<_, i64, Node>::new_sync(PruningCombinator::<_, i64>::new(tree)))));
+            let mut algo:BeamSearch<_, i64, i64, _, _> = BeamSearch::new(space.clone(), 100);
+            let handle = thread::spawn(move || {
+                algo.run(NeverStoppingCriterion::default());
+                (algo.status(), *algo.get_manager().best_val())
+            });
+            (space, handle)
+        }).collect();
+        for (space, handle) in workers {
+            assert_eq!(handle.join().unwrap(), (SearchStatus::Optimal, Some(0)));
+            let mut json = serde_json::json!({});
+            Mutex::lock(&space).unwrap().json_statistics(&mut json);
+            assert_eq!(json["pruning_primal_bound"], serde_json::json!(0));
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::{Serialize, Deserialize};
+
+use crate::search_algorithm::{SearchAlgorithm, StoppingCriterion, NodeBudgetStoppingCriterion};
+use crate::search_manager::SearchStatus;
+use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, PartialNeighborGeneration, IteratorNeighborGeneration, ObjectiveSense, CheckpointSpace, DecisionSpace, Identifiable, ToSolution, SolutionDistanceSpace, SolutionCostSpace};
+
+/// node of a complete binary tree (the cost increases by 0 or 1 at each level)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Node {
+    pub(crate) depth: usize,
+    pub(crate) cost: i64,
+    pub(crate) nb_children_generated: usize,
+}
+
+/// complete binary tree of a given depth (the leaves are goals iff feasible)
+#[derive(Debug)]
+pub(crate) struct BinaryTree {
+    pub(crate) depth: usize,
+    pub(crate) feasible: bool,
+    pub(crate) sense: ObjectiveSense,
+}
+
+impl BinaryTree {
+    /// feasible binary tree of depth 3
+    pub(crate) fn new(sense:ObjectiveSense) -> Self {
+        Self { depth: 3, feasible: true, sense }
+    }
+
+    fn child(&self, n:&Node, i:usize) -> Node {
+        Node { depth: n.depth+1, cost: n.cost + i as i64, nb_children_generated: 0 }
+    }
+}
+
+impl SearchSpace<Node, i64> for BinaryTree {
+    fn initial(&mut self) -> Node { Node { depth: 0, cost: 0, nb_children_generated: 0 } }
+
+    fn bound(&mut self, n:&Node) -> i64 {
+        match self.sense {
+            ObjectiveSense::Minimize => n.cost,
+            ObjectiveSense::Maximize => n.cost + (self.depth - n.depth) as i64,
+        }
+    }
+
+    fn goal(&mut self, n:&Node) -> bool { self.feasible && n.depth == self.depth }
+
+    fn g_cost(&mut self, n:&Node) -> i64 { n.cost }
+
+    fn sense(&self) -> ObjectiveSense { self.sense }
+}
+
+impl GuidedSpace<Node, i64> for BinaryTree {
+    fn guide(&mut self, n:&Node) -> i64 {
+        match self.sense {
+            ObjectiveSense::Minimize => n.cost,
+            ObjectiveSense::Maximize => -self.bound(n),
+        }
+    }
+}
+
+impl IteratorNeighborGeneration<Node> for BinaryTree {
+    /// index of the next child
+    type Cursor = usize;
+
+    fn neighbors_cursor(&mut self, _n:&mut Node) -> usize { 0 }
+
+    fn next_neighbor_from(&mut self, n:&mut Node, i:&mut usize) -> Option<Node> {
+        if n.depth == self.depth || *i == 2 { return None; }
+        *i += 1;
+        Some(self.child(n, *i-1))
+    }
+}
+
+impl PartialNeighborGeneration<Node> for BinaryTree {
+    fn next_neighbor(&mut self, n:&mut Node) -> Option<Node> {
+        if n.depth == self.depth || n.nb_children_generated == 2 { return None; }
+        n.nb_children_generated += 1;
+        Some(self.child(n, n.nb_children_generated-1))
+    }
+}
+
+impl CheckpointSpace for BinaryTree {}
+
+impl Identifiable<Node, usize> for BinaryTree {
+    fn id(&self, n:&mut Node) -> usize { n.depth }
+}
+
+impl DecisionSpace<Node, i64> for BinaryTree {
+    fn decision(&self, n:&Node) -> Option<i64> { Some(n.cost) }
+}
+
+/// the solutions are the costs of the leaves (a solution is mapped back to a leaf)
+impl ToSolution<Node, i64> for BinaryTree {
+    fn solution(&mut self, n:&mut Node) -> i64 { n.cost }
+
+    fn solution_to_node(&mut self, _n:&Node, sol:&i64) -> Option<Node> { Some(leaf(*sol)) }
+}
+
+impl SolutionDistanceSpace<i64> for BinaryTree {
+    fn solution_distance(&self, a:&i64, b:&i64) -> f64 { (a-b).abs() as f64 }
+}
+
+impl SolutionCostSpace<i64, i64> for BinaryTree {
+    fn solution_cost(&self, sol:&i64) -> i64 { *sol }
+}
+
+/// leaf of the binary tree of depth 3 with a given cost
+pub(crate) fn leaf(cost:i64) -> Node { Node { depth: 3, cost, nb_children_generated: 0 } }
+
+/// shared binary tree of depth 3 (minimization)
+pub(crate) fn space(feasible:bool) -> Rc<RefCell<BinaryTree>> {
+    Rc::new(RefCell::new(BinaryTree { depth: 3, feasible, sense: ObjectiveSense::Minimize }))
+}
+
+/// runs the search and checks that the statistics report its status
+pub(crate) fn run<A:SearchAlgorithm<Node,i64>, SC:StoppingCriterion>(mut algo:A, stopping_criterion:SC) -> SearchStatus {
+    algo.run(stopping_criterion);
+    let status = algo.status();
+    let mut json = serde_json::json!({});
+    algo.json_statistics(&mut json);
+    assert_eq!(json["status"], serde_json::json!(status));
+    assert_eq!(json["is_optimal"], serde_json::json!(status == SearchStatus::Optimal));
+    status
+}
+
+/// tree in which all the children have the same guide (3 children per node, various costs)
+#[derive(Debug)]
+pub(crate) struct TieTree {
+    pub(crate) depth: usize,
+}
+
+impl SearchSpace<Node, i64> for TieTree {
+    fn initial(&mut self) -> Node { Node { depth: 0, cost: 0, nb_children_generated: 0 } }
+
+    fn bound(&mut self, n:&Node) -> i64 { n.cost }
+
+    fn goal(&mut self, n:&Node) -> bool { n.depth == self.depth }
+
+    fn g_cost(&mut self, n:&Node) -> i64 { n.cost }
+}
+
+impl GuidedSpace<Node, i32> for TieTree {
+    fn guide(&mut self, _n:&Node) -> i32 { 1 }
+}
+
+impl TotalNeighborGeneration<Node> for TieTree {
+    fn neighbors(&mut self, n:&mut Node) -> Vec<Node> {
+        if n.depth == self.depth { return Vec::new(); }
+        (0..3).map(|i| Node { depth: n.depth+1, cost: n.cost + ((i*7 + n.depth*3) % 5) as i64, nb_children_generated: 0 }).collect()
+    }
+}
+
+impl DecisionSpace<Node, (usize, i64)> for TieTree {
+    fn decision(&self, n:&Node) -> Option<(usize, i64)> { Some((n.depth, n.cost)) }
+}
+
+/// the solutions are the costs of the leaves (they cannot be mapped back to a node)
+impl ToSolution<Node, i64> for TieTree {
+    fn solution(&mut self, n:&mut Node) -> i64 { n.cost }
+}
+
+impl SolutionDistanceSpace<i64> for TieTree {
+    fn solution_distance(&self, a:&i64, b:&i64) -> f64 { (a-b).abs() as f64 }
+}
+
+impl SolutionCostSpace<i64, i64> for TieTree {
+    fn solution_cost(&self, sol:&i64) -> i64 { *sol }
+}
+
+/// shared tie tree of depth 8
+pub(crate) fn tie_tree() -> Rc<RefCell<TieTree>> {
+    Rc::new(RefCell::new(TieTree { depth: 8 }))
+}
+
+/// statistics of a randomized search run with a given seed (Greedy or AntColony)
+pub(crate) fn seeded_run<A:SearchAlgorithm<Node,i64>>(mut algo:A, budget:u64) -> serde_json::Value {
+    let stopping_criterion = NodeBudgetStoppingCriterion::expanded(algo.get_manager(), budget);
+    algo.run(stopping_criterion);
+    let mut json = serde_json::json!({});
+    algo.json_statistics(&mut json);
+    json["best"] = serde_json::json!(algo.get_manager().best());
+    without_timings(json)
+}
+
+/// removes the timings of flat statistics (to compare runs)
+pub(crate) fn without_timings(mut json:serde_json::Value) -> serde_json::Value {
+    if let Some(map) = json.as_object_mut() {
+        map.retain(|k, _| !k.contains("time_"));
+    }
+    json
+}
@@ -0,0 +1,370 @@
+use std::marker::PhantomData;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Weak;
+use std::hash::Hash;
+use std::fmt::Display;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::Serialize;
+
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
+use crate::search_report::{SearchReport, ReportSection};
+use crate::search_algorithm::{SearchAlgorithm, StoppingCriterion, DEFAULT_SEED};
+use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, DecisionSpace};
+use crate::metric_logger::{Metric, MetricLogger};
+use crate::objective::GuideValue;
+use crate::tree_search::helper::pheromone::{PheromoneTable, positive_guide};
+
+/**
+parameters of the ant colony optimization
+*/
+#[derive(Debug, Clone, Serialize)]
+pub struct AcoParams {
+    /// number of ants per iteration
+    pub nb_ants: usize,
+    /// importance of the pheromone
+    pub alpha: f64,
+    /// importance of the heuristic information (inverse of the guide)
+    pub beta: f64,
+    /// evaporation rate (in [0,1])
+    pub evaporation: f64,
+    /// minimum pheromone value
+    pub tau_min: f64,
+    /// maximum pheromone value
+    pub tau_max: f64,
+    /// every global_best_frequency iterations, the best known solution reinforces the pheromone
+    /// instead of the iteration best
+    pub global_best_frequency: usize,
+}
+
+impl Default for AcoParams {
+    fn default() -> Self {
+        Self {
+            nb_ants: 10,
+            alpha: 1.,
+            beta: 2.,
+            evaporation: 0.02,
+            tau_min: 0.01,
+            tau_max: 1.,
+            global_best_frequency: 10,
+        }
+    }
+}
+
+
+/**
+computes the heuristic information of the children of a node given their guides (the smaller the
+guide, the larger the heuristic information): 1/guide. The guides are first mapped to positive
+values, keeping their order (see positive_guide, also used by the pheromone combinator).
+*/
+pub fn heuristic_information(guides:&[f64]) -> Vec<f64> {
+    guides.iter().map(|g| 1. / positive_guide(*g)).collect()
+}
+
+/**
+samples an index with probability proportional to its weight (roulette wheel).
+returns None if the weights are empty.
+*/
+pub fn roulette_wheel<R:Rng>(weights:&[f64], rng:&mut R) -> Option<usize> {
+    if weights.is_empty() { return None; }
+    let total:f64 = weights.iter().sum();
+    if !(total > 0. && total.is_finite()) {
+        return Some(rng.gen_range(0..weights.len()));
+    }
+    let mut r = rng.gen::<f64>() * total;
+    for (i,w) in weights.iter().enumerate() {
+        if r < *w { return Some(i); }
+        r -= w;
+    }
+    Some(weights.len()-1)
+}
+
+
+/**
+Ant Colony Optimization (MAX-MIN Ant System). Each ant walks the tree from the root to a goal:
+children are sampled with a probability proportional to pheromone^alpha * (1/guide)^beta, the
+pheromone being indexed by the decision of the child (DecisionSpace). Non-positive guides are
+shifted (see heuristic_information).
+After each iteration, the decisions of the best ant reinforce the pheromone.
+*/
+#[derive(Debug)]
+pub struct AntColony<N, B, G, D, Space, H=Rc<RefCell<Space>>> {
+    manager: SearchManager<N, B>,
+    space: H,
+    space_phantom: PhantomData<Space>,
+    params: AcoParams,
+    pheromones: PheromoneTable<D>,
+    best_decisions: Option<Vec<D>>,
+    nb_iter: usize,
+    /// seed of the random number generator
+    seed: u64,
+    rng: StdRng,
+    logger: Weak<MetricLogger>,
+    logging_id_iter: Option<usize>,
+    logging_id_iter_best: Option<usize>,
+    g: PhantomData<G>,
+}
+
+impl<N:Clone, B:PartialOrd+Copy, G, D:Hash+Eq, Space, H:SpaceHandle<Space>> AntColony<N, B, G, D, Space, H> {
+    /** builds the ant colony given a search space and its parameters */
+    pub fn new(space: H, params:AcoParams) -> Self {
+        let pheromones = PheromoneTable::new(params.tau_min, params.tau_max, params.evaporation);
+        Self {
+            manager: SearchManager::default(),
+            space,
+            space_phantom: PhantomData,
+            params,
+            pheromones,
+            best_decisions: None,
+            nb_iter: 0,
+            seed: DEFAULT_SEED,
+            rng: StdRng::seed_from_u64(DEFAULT_SEED),
+            logger: Weak::new(),
+            logging_id_iter: None,
+            logging_id_iter_best: None,
+            g: PhantomData,
+        }
+    }
+
+    /** sets the seed of the random number generator (roulette wheel selections of the ants) */
+    pub fn with_seed(mut self, seed:u64) -> Self {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /**
+    binds the ant colony to a logger (to display the iteration number and the iteration best).
+    Takes a `std::sync::Weak` obtained with `Arc::downgrade(&logger)`.
+    */
+    pub fn bind_logger(mut self, logger_ref:Weak<MetricLogger>) -> Self {
+        if let Some(logger) = logger_ref.upgrade() {
+            // adds headers to the logger
+            let tmp = logger.register_headers([
+                format!("{:<15}","ACO iter"),
+                format!("{:<15}","iter best"),
+            ].to_vec());
+            self.logging_id_iter = Some(tmp[0]);
+            self.logging_id_iter_best = Some(tmp[1]);
+        }
+        // registers the logger
+        self.logger = logger_ref;
+        self
+    }
+
+    /** returns the pheromone table */
+    pub fn pheromones(&self) -> &PheromoneTable<D> { &self.pheromones }
+}
+
+
+impl<N, B, G, D, Space, H:SpaceHandle<Space>> AntColony<N, B, G, D, Space, H>
+where
+    N: Clone,
+    B: PartialOrd+Copy,
+    G: GuideValue,
+    D: Hash+Eq+Clone,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N> + DecisionSpace<N,D>,
+{
+    /**
+    builds a solution by walking the tree from the root. Returns the decisions taken, the
+    value of the goal reached (if any) and true iff it is a new best known solution.
+    */
+    fn construct<SC:StoppingCriterion>(&mut self, space:&mut Space, stopping_criterion:&SC) -> (Vec<D>, Option<B>, bool) {
+        let mut decisions = Vec::new();
+        let mut n = space.initial();
+        while !stopping_criterion.is_finished() {
+            if space.goal(&n) {
+                let v = space.bound(&n);
+                if self.manager.is_better(v) {
+                    let n2 = space.handle_new_best(n);
+                    let b2 = space.bound(&n2);
+                    self.manager.update_best(n2, b2);
+                    return (decisions, Some(b2), true);
+                }
+                return (decisions, Some(v), false);
+            }
+            let mut children = space.neighbors(&mut n);
+            self.manager.notify_expansion(children.len());
+            if let Some(b) = space.dual_bound() { self.manager.update_dual_bound(b); }
+            let guides:Vec<f64> = children.iter().map(|c| space.guide(c).to_f64()).collect();
+            let weights:Vec<f64> = children.iter().zip(heuristic_information(&guides)).map(|(c, eta)| {
+                let tau = match space.decision(c) {
+                    None => self.pheromones.tau_max(),
+                    Some(d) => self.pheromones.get(&d),
+                };
+                tau.powf(self.params.alpha) * eta.powf(self.params.beta)
+            }).collect();
+            match roulette_wheel(&weights, &mut self.rng) {
+                None => { break; }, // dead end
+                Some(i) => {
+                    let c = children.swap_remove(i);
+                    if let Some(d) = space.decision(&c) {
+                        decisions.push(d);
+                    }
+                    n = c;
+                }
+            }
+        }
+        (decisions, None, false)
+    }
+}
+
+
+impl<N, B, G, D, Space, H:SpaceHandle<Space>> SearchAlgorithm<N, B> for AntColony<N, B, G, D, Space, H>
+where
+    N: Clone,
+    B: PartialOrd+Copy+Display,
+    G: GuideValue,
+    D: Hash+Eq+Clone,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N> + DecisionSpace<N,D>,
+{
+    fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
+        let space_rc = self.space.clone();
+        let mut space = space_rc.lock();
+        self.manager.start_search(&mut *space);
+        while !stopping_criterion.is_finished() {
+            // each ant builds a solution
+            let mut iter_best:Option<(Vec<D>, B)> = None;
+            for _ in 0..self.params.nb_ants {
+                if stopping_criterion.is_finished() { break; }
+                let (decisions, goal_value, is_new_best) = self.construct(&mut space, &stopping_criterion);
+                if is_new_best {
+                    self.best_decisions = Some(decisions.clone());
+                }
+                if let Some(v) = goal_value {
+                    let is_iter_best = match &iter_best {
+                        None => true,
+                        Some((_, best_v)) => space.sense().is_better(&v, best_v),
+                    };
+                    if is_iter_best { iter_best = Some((decisions, v)); }
+                }
+            }
+            // pheromone update (iteration best or global best)
+            self.pheromones.evaporate();
+            self.nb_iter += 1;
+            let use_global_best = self.params.global_best_frequency > 0
+                && self.nb_iter.is_multiple_of(self.params.global_best_frequency);
+            match (&self.best_decisions, &iter_best) {
+                (Some(decisions), _) if use_global_best => {
+                    self.pheromones.reinforce(decisions, self.params.tau_max);
+                },
+                (_, Some((decisions, _))) => {
+                    self.pheromones.reinforce(decisions, self.params.tau_max);
+                },
+                _ => {}
+            }
+            self.manager.notify_iteration_end();
+            // updates logger and display statistics
+            if let Some(logger) = self.logger.upgrade() {
+                if let Some(id) = self.logging_id_iter {
+                    logger.update_metric(id, Metric::Int(self.nb_iter as i64));
+                }
+                if let Some(id) = self.logging_id_iter_best {
+                    logger.update_metric(id, match &iter_best {
+                        None => Metric::Text("-".to_string()),
+                        Some((_, v)) => Metric::Text(format!("{}", v)),
+                    });
+                }
+                logger.request_logging();
+            }
+        }
+        // the ant colony does not prove optimality
+        self.manager.finish(false);
+        space.stop_search("".to_string());
+    }
+
+    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
+
+    fn status(&self) -> SearchStatus { self.manager.status() }
+
+    fn report(&self, report:&mut SearchReport) {
+        report.status = Some(self.status());
+        report.push(ReportSection::new("aco")
+            .with_counter("nb_iter", self.nb_iter as u64)
+            .with_counter("nb_pheromones", self.pheromones.len() as u64)
+            .with_parameters(&self.params)
+            .with_parameter("seed", self.seed)
+            .with_timing("searched", self.manager.elapsed_time().as_secs_f64())
+        );
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_algorithm::{NeverStoppingCriterion, NodeBudgetStoppingCriterion};
+    use crate::tree_search::beam_search::BeamSearch;
+    use crate::combinators::neighbor_generation::TotalAdapter;
+    use crate::test_spaces::{Node, TieTree, tie_tree, seeded_run, space};
+
+    #[test]
+    fn roulette_wheel_selection() {
+        let mut rng = StdRng::seed_from_u64(DEFAULT_SEED);
+        assert_eq!(roulette_wheel(&[], &mut rng), None);
+        for _ in 0..10 {
+            assert_eq!(roulette_wheel(&[0., 2., 0.], &mut rng), Some(1));
+            // null weights: uniform selection
+            assert!(roulette_wheel(&[0., 0.], &mut rng).unwrap() < 2);
+        }
+    }
+
+    #[test]
+    fn heuristic_information_of_guides() {
+        assert_eq!(heuristic_information(&[2., 4.]), vec![0.5, 0.25]);
+        // null (or negative) guides get a finite heuristic information (the order is kept)
+        assert_eq!(heuristic_information(&[0., 1., 4.]), vec![2., 1., 0.25]);
+        assert_eq!(heuristic_information(&[1., -1.]), vec![1., 3.]);
+        assert!(heuristic_information(&[]).is_empty());
+    }
+
+    #[test]
+    fn small_tree() {
+        // with a full evaporation, only the decisions of the best known solution keep pheromone
+        // (the others are set to tau_min, close to tau_max to keep exploring)
+        let params = AcoParams { evaporation: 1., tau_min: 0.5, global_best_frequency: 1, ..AcoParams::default() };
+        let mut aco:AntColony<Node, i64, i32, (usize, i64), _> = AntColony::new(Rc::new(RefCell::new(TieTree { depth: 4 })), params);
+        let stopping_criterion = NodeBudgetStoppingCriterion::expanded(aco.get_manager(), 2000);
+        aco.run(stopping_criterion);
+        assert_eq!(aco.status(), SearchStatus::Feasible);
+        // the ants find the optimal solution (given by an exhaustive beam search)
+        let mut beam:BeamSearch<Node, i64, i32, _> = BeamSearch::new(Rc::new(RefCell::new(TotalAdapter::new(TieTree { depth: 4 }))), 100);
+        beam.run(NeverStoppingCriterion::default());
+        assert_eq!(aco.get_manager().best_val(), beam.get_manager().best_val());
+        let best = aco.get_manager().best().clone().unwrap();
+        assert_eq!(best.depth, 4);
+        let decisions = aco.best_decisions.clone().unwrap();
+        assert_eq!(decisions.len(), 4);
+        assert_eq!(decisions.last().map(|d| d.1), Some(best.cost));
+        for d in &decisions {
+            assert_eq!(aco.pheromones().get(d), 1.);
+        }
+        let other = (0..5).map(|c| (1, c)).find(|d| *d != decisions[0]).unwrap();
+        assert_eq!(aco.pheromones().get(&other), 0.5);
+        let mut json = serde_json::json!({});
+        aco.json_statistics(&mut json);
+        assert_eq!(json["aco_nb_iter"], serde_json::json!(50));
+    }
+
+    #[test]
+    fn integer_guides() {
+        // the binary tree is guided by i64 values (converted by GuideValue)
+        let mut aco:AntColony<Node, i64, i64, i64, _> = AntColony::new(space(true), AcoParams::default());
+        let stopping_criterion = NodeBudgetStoppingCriterion::expanded(aco.get_manager(), 200);
+        aco.run(stopping_criterion);
+        assert_eq!(*aco.get_manager().best_val(), Some(0));
+    }
+
+    #[test]
+    fn seeded_runs() {
+        let aco = |seed| seeded_run(AntColony::<Node, i64, i32, (usize, i64), _>::new(tie_tree(), AcoParams::default()).with_seed(seed), 200);
+        // two runs with the same seed are identical
+        for seed in 0..5 {
+            assert_eq!(aco(seed), aco(seed));
+        }
+        assert_eq!(aco(3)["aco_seed"], serde_json::json!(3));
+    }
+}
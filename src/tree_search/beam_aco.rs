@@ -0,0 +1,180 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::hash::Hash;
+use std::fmt::Display;
+
+use ordered_float::OrderedFloat;
+
+use crate::combinators::pheromone::{PheromoneCombinator, PheromoneNode};
+use crate::tree_search::beam_search::BeamSearch;
+use crate::tree_search::ant_colony::AcoParams;
+use crate::tree_search::helper::pheromone::PheromoneTable;
+use crate::tree_search::helper::iterative::IterativeSearch;
+
+/**
+beam search whose children are ranked by a mix of the guide and the pheromone of their decision
+(see PheromoneCombinator). A single beam does not learn: the pheromone is updated between beams.
+*/
+pub type PheromoneBeamSearch<N, B, D, G, Space> = BeamSearch<
+    PheromoneNode<N, D>, B, OrderedFloat<f64>, PheromoneCombinator<Space, B, D, G>
+>;
+
+/**
+Beam-ACO: repeated pheromone beam searches (see PheromoneBeamSearch). Before each beam, the
+pheromone evaporates and the decisions of the best goal found by the previous beam reinforce it.
+The beam width is constant (create_beam_aco) or grows geometrically (create_iterative_beam_aco).
+The search stops when a beam proves optimality (or when the stopping criterion is met).
+*/
+pub type BeamAco<N, B, D, G, Space> = IterativeSearch<
+    PheromoneNode<N, D>, B, PheromoneBeamSearch<N, B, D, G, Space>, PheromoneCombinator<Space, B, D, G>
+>;
+
+/**
+wraps a search space in a pheromone combinator given the ACO parameters
+(nb_ants and global_best_frequency are not used by Beam-ACO)
+*/
+pub fn create_beam_aco_space<Space, B, D, G>(space:Space, params:&AcoParams)
+-> PheromoneCombinator<Space, B, D, G>
+where D:Hash+Eq+Clone {
+    let pheromones = PheromoneTable::new(params.tau_min, params.tau_max, params.evaporation);
+    PheromoneCombinator::new(space, params.alpha, params.beta, pheromones)
+}
+
+/**
+ * creates a Beam-ACO algorithm repeating beams of a given width
+ */
+pub fn create_beam_aco<N, B, D, G, Space>(
+    space:Rc<RefCell<PheromoneCombinator<Space, B, D, G>>>, width:usize
+) -> BeamAco<N, B, D, G, Space>
+where N:Clone, B:Copy+PartialOrd+Display, D:Clone {
+    IterativeSearch::new(space, width as f64, 1.)
+}
+
+/**
+ * creates an iterative Beam-ACO algorithm (the beam width grows geometrically)
+ */
+pub fn create_iterative_beam_aco<N, B, D, G, Space>(
+    space:Rc<RefCell<PheromoneCombinator<Space, B, D, G>>>, d_init:f64, growth:f64
+) -> BeamAco<N, B, D, G, Space>
+where N:Clone, B:Copy+PartialOrd+Display, D:Clone {
+    IterativeSearch::new(space, d_init, growth)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_algorithm::{SearchAlgorithm, NeverStoppingCriterion, NodeBudgetStoppingCriterion};
+    use crate::search_manager::SearchStatus;
+    use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, IteratorNeighborGeneration, DecisionSpace};
+    use crate::combinators::neighbor_generation::TotalAdapter;
+    use crate::test_spaces::{Node, TieTree};
+
+    /// tie tree guided by the g-cost (the guide of the root and of some children is null)
+    #[derive(Debug)]
+    struct GCostTree(TieTree);
+
+    impl SearchSpace<Node, i64> for GCostTree {
+        fn initial(&mut self) -> Node { self.0.initial() }
+
+        fn bound(&mut self, n:&Node) -> i64 { self.0.bound(n) }
+
+        fn goal(&mut self, n:&Node) -> bool { self.0.goal(n) }
+
+        fn g_cost(&mut self, n:&Node) -> i64 { self.0.g_cost(n) }
+    }
+
+    impl GuidedSpace<Node, i32> for GCostTree {
+        fn guide(&mut self, n:&Node) -> i32 { n.cost as i32 }
+    }
+
+    impl TotalNeighborGeneration<Node> for GCostTree {
+        fn neighbors(&mut self, n:&mut Node) -> Vec<Node> { self.0.neighbors(n) }
+    }
+
+    impl DecisionSpace<Node, (usize, i64)> for GCostTree {
+        fn decision(&self, n:&Node) -> Option<(usize, i64)> { self.0.decision(n) }
+    }
+
+    #[test]
+    fn pheromone_update() {
+        let params = AcoParams { evaporation: 0.5, ..AcoParams::default() };
+        let space = Rc::new(RefCell::new(create_beam_aco_space(TotalAdapter::new(TieTree { depth: 4 }), &params)));
+        // the beam is wide enough to explore the whole tree: the first beam proves optimality
+        let mut beam_aco:BeamAco<Node, i64, (usize, i64), i32, _> = create_beam_aco(space.clone(), 100);
+        beam_aco.run(NeverStoppingCriterion::default());
+        assert_eq!(beam_aco.status(), SearchStatus::Optimal);
+        let mut beam:BeamSearch<Node, i64, i32, _> = BeamSearch::new(Rc::new(RefCell::new(TotalAdapter::new(TieTree { depth: 4 }))), 100);
+        beam.run(NeverStoppingCriterion::default());
+        assert_eq!(beam_aco.get_manager().best_val(), beam.get_manager().best_val());
+        // at the end of the search, the pheromone evaporates (tau_max/2) and the decisions of
+        // the best solution are reinforced (back to tau_max)
+        let best = beam_aco.get_manager().best().clone().unwrap();
+        let decisions = best.decisions();
+        assert_eq!(decisions.len(), 4);
+        let combinator = space.borrow();
+        assert_eq!(combinator.pheromones().len(), 4);
+        for d in &decisions {
+            assert_eq!(combinator.pheromones().get(d), 1.);
+        }
+        let other = (0..3).map(|c| (1, c)).find(|d| *d != decisions[0]).unwrap();
+        assert_eq!(combinator.pheromones().get(&other), 0.5);
+        let mut json = serde_json::json!({});
+        combinator.json_statistics(&mut json);
+        assert_eq!(json["pheromone_nb_updates"], serde_json::json!(1));
+    }
+
+    #[test]
+    fn repeated_beams() {
+        let params = AcoParams { evaporation: 0.5, ..AcoParams::default() };
+        let space = Rc::new(RefCell::new(create_beam_aco_space(TotalAdapter::new(TieTree { depth: 4 }), &params)));
+        // a beam of width 1 does not prove optimality: the beams are repeated until the budget
+        // is consumed, and the pheromone is updated between them
+        let mut beam_aco:BeamAco<Node, i64, (usize, i64), i32, _> = create_beam_aco(space.clone(), 1);
+        let stopping_criterion = NodeBudgetStoppingCriterion::expanded(beam_aco.get_manager(), 20);
+        beam_aco.run(stopping_criterion);
+        assert_eq!(beam_aco.status(), SearchStatus::Feasible);
+        let mut json = serde_json::json!({});
+        space.borrow().json_statistics(&mut json);
+        assert!(json["pheromone_nb_updates"].as_u64().unwrap() >= 4);
+        // the decisions of the best solution are reinforced
+        let best = beam_aco.get_manager().best().clone().unwrap();
+        for d in &best.decisions() {
+            assert_eq!(space.borrow().pheromones().get(d), 1.);
+        }
+    }
+
+    #[test]
+    fn null_guide() {
+        let params = AcoParams { evaporation: 0.5, ..AcoParams::default() };
+        let space = Rc::new(RefCell::new(create_beam_aco_space(TotalAdapter::new(GCostTree(TieTree { depth: 4 })), &params)));
+        // the guide of the root is null: it is mapped to a positive value and the pheromone still
+        // ranks the children of null guide
+        let root = space.borrow_mut().initial();
+        assert!(space.borrow_mut().guide(&root).0 > 0.);
+        let mut beam_aco:BeamAco<Node, i64, (usize, i64), i32, _> = create_beam_aco(space.clone(), 100);
+        beam_aco.run(NeverStoppingCriterion::default());
+        assert_eq!(beam_aco.status(), SearchStatus::Optimal);
+        let mut beam:BeamSearch<Node, i64, i32, _> = BeamSearch::new(Rc::new(RefCell::new(TotalAdapter::new(TieTree { depth: 4 }))), 100);
+        beam.run(NeverStoppingCriterion::default());
+        assert_eq!(beam_aco.get_manager().best_val(), beam.get_manager().best_val());
+    }
+
+    #[test]
+    fn pheromone_ranks_null_guides() {
+        // the children of the root have the guides 0, 2 and 4 (decisions (1,0), (1,2) and (1,4))
+        let children_costs = |pheromones:PheromoneTable<(usize, i64)>| {
+            let mut space = PheromoneCombinator::new(TotalAdapter::new(GCostTree(TieTree { depth: 4 })), 1., 1., pheromones);
+            let mut root = space.initial();
+            let mut children:Vec<_> = space.neighbors_iter(&mut root).collect();
+            children.sort_by_key(|c| space.guide(c));
+            children.iter().map(|c| c.node.cost).collect::<Vec<i64>>()
+        };
+        assert_eq!(children_costs(PheromoneTable::new(0.1, 1., 1.)), vec![0, 2, 4]);
+        // once its pheromone evaporated, the child of null guide is ranked after the reinforced one
+        let mut pheromones = PheromoneTable::new(0.1, 1., 1.);
+        pheromones.evaporate();
+        pheromones.reinforce(&[(1, 2)], 1.);
+        assert_eq!(children_costs(pheromones), vec![2, 0, 4]);
+    }
+}
@@ -5,9 +5,14 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::fmt::Display;
 
-use crate::search_manager::SearchManager;
-use crate::search_algorithm::{BuildableWithInteger, SearchAlgorithm, StoppingCriterion};
-use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
+use crate::search_algorithm::{BuildableWithInteger, SearchAlgorithm, StepwiseSearch, StepOutcome, StoppingCriterion, CheckpointableSearch};
+use crate::search_space::{SearchSpace, GuidedSpace, IteratorNeighborGeneration, CheckpointSpace};
 
 use crate::tree_search::helper::guided_node::GuidedNode;
 use crate::tree_search::helper::iterative::IterativeSearch;
@@ -16,108 +21,184 @@ use crate::tree_search::helper::iterative::IterativeSearch;
 beam search algorithm structure
 */
 #[derive(Debug)]
-pub struct BeamSearch<N, B, G, Space> {
+pub struct BeamSearch<N, B, G:Ord, Space, H=Rc<RefCell<Space>>> {
     manager: SearchManager<N, B>,
-    space: Rc<RefCell<Space>>,
+    space: H,
+    space_phantom: PhantomData<Space>,
     d: usize,
     heuristic_pruning_done: bool,
-    g: PhantomData<G>,
+    /// nodes of the current level that are not expanded yet
+    beam: MinMaxHeap<GuidedNode<N, G>>,
+    /// children selected for the next level
+    next_beam: MinMaxHeap<GuidedNode<N, G>>,
 }
 
-impl<N:Clone, B:PartialOrd+Copy, G, Space> BeamSearch<N, B, G, Space> {
+impl<N:Clone, B:PartialOrd+Copy, G:Ord, Space, H:SpaceHandle<Space>> BeamSearch<N, B, G, Space, H> {
     /** builds the beam search given a search space and a beam width */
-    pub fn new(space: Rc<RefCell<Space>>, d: usize) -> Self {
+    pub fn new(space: H, d: usize) -> Self {
         Self {
             manager: SearchManager::default(),
             space,
+            space_phantom: PhantomData,
             d,
             heuristic_pruning_done: false,
-            g: PhantomData,
+            beam: MinMaxHeap::new(),
+            next_beam: MinMaxHeap::new(),
         }
     }
 }
 
 
-impl<'a, N, B, G:Ord, Space> SearchAlgorithm<N, B> for BeamSearch<N, B, G, Space>
+impl<'a, N, B, G:Ord, Space, H:SpaceHandle<Space>> SearchAlgorithm<N, B> for BeamSearch<N, B, G, Space, H>
 where
     N: Clone,
     B: PartialOrd+Copy,
-    Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N>,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + IteratorNeighborGeneration<N>,
 {
     /**
      * runs until the stopping_criterion is reached
      */
     fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
-        let mut space = self.space.borrow_mut();
-        let mut beam = MinMaxHeap::with_capacity(self.d);
+        self.run_stepwise(stopping_criterion);
+    }
+
+    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
+
+    fn status(&self) -> SearchStatus { self.manager.status() }
+}
+
+impl<N, B, G:Ord, Space, H:SpaceHandle<Space>> StepwiseSearch<N, B> for BeamSearch<N, B, G, Space, H>
+where
+    N: Clone,
+    B: PartialOrd+Copy,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + IteratorNeighborGeneration<N>,
+{
+    fn init(&mut self) {
+        let mut space = self.space.lock();
+        self.manager.start_search(&mut *space);
+        self.beam = MinMaxHeap::with_capacity(self.d);
+        self.next_beam = MinMaxHeap::with_capacity(self.d);
         let root = space.initial();
         let g_root = space.guide(&root);
         self.heuristic_pruning_done = false;
-        beam.push(GuidedNode::new(root, g_root));
-        while !stopping_criterion.is_finished() && !beam.is_empty() {
-            let mut next_beam = MinMaxHeap::with_capacity(self.d);
-            while !beam.is_empty() && !stopping_criterion.is_finished() {
-                let mut n = beam.pop_min().unwrap().node;
+        self.beam.push(GuidedNode::new(root, g_root));
+    }
+
+    fn step(&mut self, max_expansions:usize) -> StepOutcome {
+        let mut space = self.space.lock();
+        for _ in 0..max_expansions {
+            // goes to the next level once the current one is expanded
+            if self.beam.is_empty() {
+                if self.next_beam.is_empty() { break; }
+                self.beam = std::mem::replace(&mut self.next_beam, MinMaxHeap::with_capacity(self.d));
+            }
+            let mut n = self.beam.pop_min().unwrap().node;
+            // check if goal
+            if space.goal(&n) {
+                // compare with best
+                let v = space.bound(&n);
+                if self.manager.is_better(v) {
+                    let n2 = space.handle_new_best(n);
+                    let b2 = space.bound(&n2);
+                    n = n2.clone();
+                    self.manager.update_best(n2, b2);
+                }
+            }
+            // children are generated (and filtered by the combinators) one at a time
+            let mut cursor = space.neighbors_cursor(&mut n);
+            let mut nb_children = 0;
+            while let Some(c) = space.next_neighbor_from(&mut n, &mut cursor) {
+                nb_children += 1;
                 // check if goal
-                if space.goal(&n) {
+                if space.goal(&c) {
                     // compare with best
-                    let v = space.bound(&n);
+                    let v = space.bound(&c);
                     if self.manager.is_better(v) {
-                        let n2 = space.handle_new_best(n);
-                        let b2 = space.bound(&n2);
-                        n = n2.clone();
-                        self.manager.update_best(n2, b2);
+                        let c2 = space.handle_new_best(c);
+                        let b2 = space.bound(&c2);
+                        self.manager.update_best(c2, b2);
                     }
+                    continue;
                 }
-                let mut children = space.neighbors(&mut n);
-                while !children.is_empty() {
-                    let c = children.pop().unwrap();
-                    // check if goal
-                    if space.goal(&c) {
-                        // compare with best
-                        let v = space.bound(&c);
-                        if self.manager.is_better(v) {
-                            let c2 = space.handle_new_best(c);
-                            let b2 = space.bound(&c2);
-                            self.manager.update_best(c2, b2);
-                        }
-                        continue;
-                    }
-                    let c_guide = space.guide(&c); // compute guide to feed the GuidedNode while inserting into next_beam
-                    if next_beam.len() < self.d {
-                        next_beam.push(GuidedNode::new(c, c_guide));
-                    } else {
-                        self.heuristic_pruning_done = true;
-                        // pop max and insert child
-                        next_beam.push_pop_max(GuidedNode::new(c, c_guide));
-                    }
+                let c_guide = space.guide(&c); // compute guide to feed the GuidedNode while inserting into next_beam
+                if self.next_beam.len() < self.d {
+                    self.next_beam.push(GuidedNode::new(c, c_guide));
+                } else {
+                    self.heuristic_pruning_done = true;
+                    // pop max and insert child
+                    self.next_beam.push_pop_max(GuidedNode::new(c, c_guide));
                 }
             }
-            beam = next_beam;
+            self.manager.notify_expansion(nb_children);
+            if let Some(b) = space.dual_bound() { self.manager.update_dual_bound(b); }
         }
+        if self.beam.is_empty() && self.next_beam.is_empty() {
+            StepOutcome::Finished
+        } else {
+            StepOutcome::Running
+        }
+    }
+
+    fn finish(&mut self) {
+        // optimality is proven iff the whole tree was explored without heuristic pruning
+        let explored = self.beam.is_empty() && self.next_beam.is_empty();
+        let mut space = self.space.lock();
+        self.manager.finish(explored && !self.heuristic_pruning_done && space.is_exact());
         space.stop_search("".to_string());
     }
+}
 
-    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
+impl<N, B, G, Space, H:SpaceHandle<Space>> CheckpointableSearch<N, B> for BeamSearch<N, B, G, Space, H>
+where
+    N: Clone+Serialize+DeserializeOwned,
+    B: PartialOrd+Copy+Serialize+DeserializeOwned,
+    G: Ord+Serialize+DeserializeOwned,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + IteratorNeighborGeneration<N> + CheckpointSpace,
+{
+    fn checkpoint(&self) -> serde_json::Value {
+        let mut space_state = json!({});
+        self.space.lock().save_state(&mut space_state);
+        // the heaps are stored in their internal order (restored identically)
+        json!({
+            "manager": self.manager.checkpoint(),
+            "space": space_state,
+            "beam": self.beam.iter().collect::<Vec<_>>(),
+            "next_beam": self.next_beam.iter().collect::<Vec<_>>(),
+            "heuristic_pruning_done": self.heuristic_pruning_done,
+        })
+    }
 
-    /**
-     * returns true if the optimal value is found (thus we can stop the search)
-     */
-    fn is_optimal(&self) -> bool { !self.heuristic_pruning_done }
+    fn resume(&mut self, checkpoint:&serde_json::Value) -> Result<(), serde_json::Error> {
+        let mut space = self.space.lock();
+        self.manager.set_sense(space.sense());
+        self.manager.restore(&checkpoint["manager"])?;
+        space.load_state(&checkpoint["space"])?;
+        let beam:Vec<GuidedNode<N, G>> = serde_json::from_value(checkpoint["beam"].clone())?;
+        let next_beam:Vec<GuidedNode<N, G>> = serde_json::from_value(checkpoint["next_beam"].clone())?;
+        self.beam = MinMaxHeap::from(beam);
+        self.next_beam = MinMaxHeap::from(next_beam);
+        self.heuristic_pruning_done = serde_json::from_value(checkpoint["heuristic_pruning_done"].clone())?;
+        Ok(())
+    }
 }
 
-impl<N, B, G, Space> BuildableWithInteger<Space> for BeamSearch<N, B, G, Space>
+impl<N, B, G:Ord, Space, H:SpaceHandle<Space>> BuildableWithInteger<Space, H> for BeamSearch<N, B, G, Space, H>
 where N:Clone, B:PartialOrd+Copy {
-    fn create_with_integer(tree: Rc<RefCell<Space>>, d:usize) -> Self {
+    fn create_with_integer(tree: H, d:usize) -> Self {
         Self::new(tree, d)
     }
 }
 
+/**
+ * iterative beam search (the beam width grows at each iteration)
+ */
+pub type IterativeBeamSearch<N, B, G, Tree, H=Rc<RefCell<Tree>>> = IterativeSearch<N, B, BeamSearch<N, B, G, Tree, H>, Tree, H>;
+
 /**
  * creates an iterative beam search algorithm
  */
-pub fn create_iterative_beam_search<N, B, G, Tree>(space:Rc<RefCell<Tree>>, d_init:f64, growth:f64)
--> IterativeSearch<N, B, BeamSearch<N, B, G, Tree>, Tree>
+pub fn create_iterative_beam_search<N, B, G:Ord, Tree, H:SpaceHandle<Tree>>(space:H, d_init:f64, growth:f64)
+-> IterativeBeamSearch<N, B, G, Tree, H>
 where N:Clone, B:Copy+PartialOrd+Display {
     IterativeSearch::new(space, d_init, growth)
 }
\ No newline at end of file
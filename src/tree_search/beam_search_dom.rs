@@ -4,7 +4,8 @@ use std::marker::PhantomData;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::search_manager::SearchManager;
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
 use crate::search_algorithm::{StoppingCriterion, SearchAlgorithm};
 use crate::tree_search::helper::guided_node::GuidedNode;
 use crate::search_space::{
@@ -23,21 +24,23 @@ pub enum BeamDominanceScheme {
 
 /** beam search with pareto-dominance scheme */
 #[derive(Debug)]
-pub struct BeamSearchDom<N, B, G, Space> {
+pub struct BeamSearchDom<N, B, G, Space, H=Rc<RefCell<Space>>> {
     manager: SearchManager<N, B>,
-    space: Rc<RefCell<Space>>,
+    space: H,
+    space_phantom: PhantomData<Space>,
     d: usize,
     heuristic_pruning_done: bool,
     beam_dominance_scheme: BeamDominanceScheme,
     g: PhantomData<G>,
 }
 
-impl<Space, N:Clone, B:PartialOrd+Copy, G:Ord> BeamSearchDom<N, B, G, Space> {
+impl<Space, N:Clone, B:PartialOrd+Copy, G:Ord, H:SpaceHandle<Space>> BeamSearchDom<N, B, G, Space, H> {
     /** builds the beam search using the search space, the beam width, and the dominance scheme */
-    pub fn new(space: Rc<RefCell<Space>>, d: usize, dom_scheme:BeamDominanceScheme) -> Self {
+    pub fn new(space: H, d: usize, dom_scheme:BeamDominanceScheme) -> Self {
         Self {
             manager: SearchManager::default(),
             space,
+            space_phantom: PhantomData,
             d,
             heuristic_pruning_done: false,
             beam_dominance_scheme: dom_scheme,
@@ -46,7 +49,7 @@ impl<Space, N:Clone, B:PartialOrd+Copy, G:Ord> BeamSearchDom<N, B, G, Space> {
     }
 }
 
-impl<'a, N, B, G, Space> SearchAlgorithm<N, B> for BeamSearchDom<N, B, G, Space>
+impl<'a, N, B, G, Space, H:SpaceHandle<Space>> SearchAlgorithm<N, B> for BeamSearchDom<N, B, G, Space, H>
 where
     N: Clone,
     B: PartialOrd+Copy,
@@ -54,7 +57,8 @@ where
     Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N> + ParetoDominanceSpace<N>,
 {
     fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
-        let mut space = self.space.borrow_mut();
+        let mut space = self.space.lock();
+        self.manager.start_search(&mut *space);
         let mut beam = MinMaxHeap::with_capacity(self.d);
         let root = space.initial();
         let g_root = space.guide(&root);
@@ -77,6 +81,8 @@ where
                     }
                 }
                 let mut children = space.neighbors(&mut n);
+                self.manager.notify_expansion(children.len());
+                if let Some(b) = space.dual_bound() { self.manager.update_dual_bound(b); }
                 while !children.is_empty() {
                     let c = children.pop().unwrap();
                     // check if goal
@@ -118,15 +124,16 @@ where
                     }
                 }
             }
+            // stop if the level was interrupted by the stopping criterion
+            if !beam.is_empty() { break; }
             beam = next_beam;
         }
+        // optimality is proven iff the whole tree was explored without heuristic pruning
+        self.manager.finish(beam.is_empty() && !self.heuristic_pruning_done && space.is_exact());
         space.stop_search("".to_string());
     }
 
     fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
 
-    /**
-     * returns true if the optimal value is found (thus we can stop the search)
-     */
-    fn is_optimal(&self) -> bool { !self.heuristic_pruning_done }
+    fn status(&self) -> SearchStatus { self.manager.status() }
 }
\ No newline at end of file
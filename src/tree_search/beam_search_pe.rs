@@ -5,49 +5,60 @@ use std::fmt::Display;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::search_manager::SearchManager;
-use crate::search_space::{SearchSpace, GuidedSpace, PartialNeighborGeneration};
-use crate::search_algorithm::{BuildableWithInteger, SearchAlgorithm, StoppingCriterion};
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
+use crate::search_space::{SearchSpace, GuidedSpace, IteratorNeighborGeneration};
+use crate::search_algorithm::{BuildableWithInteger, SearchAlgorithm, StepwiseSearch, StepOutcome, StoppingCriterion};
 
 use crate::tree_search::helper::guided_node::GuidedNode;
 use crate::tree_search::helper::iterative::IterativeSearch;
 
+/// beam of (node, enumeration of its children if it started) ordered by guide
+type PEBeam<N, G, C> = MinMaxHeap<GuidedNode<(N, Option<C>), G>>;
+
 /**
 implements a partial expansion beam search
 */
 #[derive(Debug)]
-pub struct PEBeamSearch<N, B, G, Tree> {
+pub struct PEBeamSearch<N, B, G:Ord, Tree:IteratorNeighborGeneration<N>, H=Rc<RefCell<Tree>>> {
     /// search manager
     pub manager: SearchManager<N, B>,
-    space: Rc<RefCell<Tree>>,
+    space: H,
+    space_phantom: PhantomData<Tree>,
     d: usize,
     heuristic_pruning_done: bool,
-    g: PhantomData<G>,
+    /// parents of the current level (with the enumeration of their children if it started)
+    beam: PEBeam<N, G, Tree::Cursor>,
+    /// children selected for the next level
+    next_beam: PEBeam<N, G, Tree::Cursor>,
 }
 
 
-impl<N:Clone, B:PartialOrd+Copy, G, Tree> PEBeamSearch<N, B, G, Tree> {
+impl<N:Clone, B:PartialOrd+Copy, G:Ord, Tree:IteratorNeighborGeneration<N>, H:SpaceHandle<Tree>> PEBeamSearch<N, B, G, Tree, H> {
     /** builds the algorithm using the search space and the beam width */
-    pub fn new(space: Rc<RefCell<Tree>>, d: usize) -> Self {
+    pub fn new(space: H, d: usize) -> Self {
         Self {
             manager: SearchManager::default(),
             space,
+            space_phantom: PhantomData,
             d,
             heuristic_pruning_done: false,
-            g: PhantomData,
+            beam: MinMaxHeap::new(),
+            next_beam: MinMaxHeap::new(),
         }
     }
 }
 
 
-impl<'a, N, B, G:Ord+Clone, Tree> SearchAlgorithm<N, B> for PEBeamSearch<N, B, G, Tree>
+impl<'a, N, B, G:Ord+Clone, Tree, H:SpaceHandle<Tree>> SearchAlgorithm<N, B> for PEBeamSearch<N, B, G, Tree, H>
 where
     N: Clone,
     B: PartialOrd+Copy,
-    Tree: SearchSpace<N,B> + GuidedSpace<N,G> + PartialNeighborGeneration<N>,
+    Tree: SearchSpace<N,B> + GuidedSpace<N,G> + IteratorNeighborGeneration<N>,
 {
     /**
-     * BeamSearch version that takes advantage of the PartialChildrenExpansion.
+     * BeamSearch version that takes advantage of the iterator-based neighbor generation
+     * (the enumeration of the children of each parent is kept in the beam).
      * Moreover, it takes advantage of having the beam search children sorted
      * At each level of the beam search, all parents are expanded once and kept with their last
      * children value. The algorithm expands the parent whose generated the smallest child.
@@ -56,87 +67,129 @@ where
     */
     fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC)
     where SC:StoppingCriterion,  {
-        let mut space = self.space.borrow_mut();
-        let mut beam = MinMaxHeap::with_capacity(self.d);
+        self.run_stepwise(stopping_criterion);
+    }
+
+
+    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
+
+    fn status(&self) -> SearchStatus { self.manager.status() }
+}
+
+
+impl<N, B, G:Ord+Clone, Tree, H:SpaceHandle<Tree>> StepwiseSearch<N, B> for PEBeamSearch<N, B, G, Tree, H>
+where
+    N: Clone,
+    B: PartialOrd+Copy,
+    Tree: SearchSpace<N,B> + GuidedSpace<N,G> + IteratorNeighborGeneration<N>,
+{
+    fn init(&mut self) {
+        let mut space = self.space.lock();
+        self.manager.start_search(&mut *space);
+        self.beam = MinMaxHeap::with_capacity(self.d);
+        self.next_beam = MinMaxHeap::with_capacity(self.d);
         let root = space.initial();
         let g_root = space.guide(&root);
         self.heuristic_pruning_done = false;
-        beam.push(GuidedNode::new(root, g_root));
-        // for each level of the tree
-        while !stopping_criterion.is_finished() && !beam.is_empty() {
-            let mut next_beam = MinMaxHeap::with_capacity(self.d);
-            while !beam.is_empty() && !stopping_criterion.is_finished() {
-                // extract a parent node
-                let mut n = beam.pop_min().unwrap().node;
-                // check if goal
-                if space.goal(&n) {
-                    // compare with best
-                    let v = space.bound(&n);
-                    if self.manager.is_better(v) {
-                        let n2 = space.handle_new_best(n);
-                        let b2 = space.bound(&n2);
-                        self.manager.update_best(n2, b2);
+        self.beam.push(GuidedNode::new((root, None), g_root));
+    }
+
+    /** a (partial) expansion generates one child */
+    fn step(&mut self, max_expansions:usize) -> StepOutcome {
+        let mut space = self.space.lock();
+        for _ in 0..max_expansions {
+            // goes to the next level once the current one is expanded
+            if self.beam.is_empty() {
+                if self.next_beam.is_empty() { break; }
+                self.beam = std::mem::replace(&mut self.next_beam, MinMaxHeap::with_capacity(self.d));
+            }
+            // extract a parent node (and the enumeration of its children if it started)
+            let (mut n, enumeration) = self.beam.pop_min().unwrap().node;
+            let mut cursor = match enumeration {
+                Some(started) => started,
+                None => {
+                    // check if goal
+                    if space.goal(&n) {
+                        // compare with best
+                        let v = space.bound(&n);
+                        if self.manager.is_better(v) {
+                            let n2 = space.handle_new_best(n);
+                            let b2 = space.bound(&n2);
+                            self.manager.update_best(n2, b2);
+                        }
+                        continue;
                     }
-                    continue;
+                    space.neighbors_cursor(&mut n)
                 }
-                // generate one child
-                match space.next_neighbor(&mut n) {
-                    None => {},  // if no children, do nothing (discard the node)
-                    Some(c) => {  // if a child, try to insert it
-                        // check if goal
-                        if space.goal(&c) {
-                            // compare with best
-                            let v = space.bound(&c);
-                            if self.manager.is_better(v) {
-                                let c2 = space.handle_new_best(c);
-                                let b2 = space.bound(&c2);
-                                self.manager.update_best(c2, b2);
-                            }
-                            continue;
+            };
+            // generate one child
+            let child = space.next_neighbor_from(&mut n, &mut cursor);
+            self.manager.notify_expansion(usize::from(child.is_some()));
+            if let Some(b) = space.dual_bound() { self.manager.update_dual_bound(b); }
+            match child {
+                None => {},  // if no children, do nothing (discard the node)
+                Some(c) => {  // if a child, try to insert it
+                    // check if goal
+                    if space.goal(&c) {
+                        // compare with best
+                        let v = space.bound(&c);
+                        if self.manager.is_better(v) {
+                            let c2 = space.handle_new_best(c);
+                            let b2 = space.bound(&c2);
+                            self.manager.update_best(c2, b2);
                         }
-                        // add c to next_beam
-                        let c_guide = space.guide(&c);
-                        if next_beam.len() < self.d {
-                            next_beam.push(GuidedNode::new(c, c_guide.clone()));
-                            beam.push(GuidedNode::new(n, c_guide.clone()));
-                        } else {
-                            self.heuristic_pruning_done = true;
-                            // pop max and insert child
-                            if next_beam.peek_max().unwrap().guide > c_guide {
-                                next_beam.push_pop_max(GuidedNode::new(c, c_guide.clone()));
-                                beam.push(GuidedNode::new(n, c_guide.clone()));
-                            }
+                        continue;
+                    }
+                    // add c to next_beam
+                    let c_guide = space.guide(&c);
+                    if self.next_beam.len() < self.d {
+                        self.next_beam.push(GuidedNode::new((c, None), c_guide.clone()));
+                        self.beam.push(GuidedNode::new((n, Some(cursor)), c_guide.clone()));
+                    } else {
+                        self.heuristic_pruning_done = true;
+                        // pop max and insert child
+                        if self.next_beam.peek_max().unwrap().guide > c_guide {
+                            self.next_beam.push_pop_max(GuidedNode::new((c, None), c_guide.clone()));
+                            self.beam.push(GuidedNode::new((n, Some(cursor)), c_guide.clone()));
                         }
                     }
                 }
             }
-            beam = next_beam;
         }
-        space.stop_search("".to_string());
+        if self.beam.is_empty() && self.next_beam.is_empty() {
+            StepOutcome::Finished
+        } else {
+            StepOutcome::Running
+        }
     }
 
-
-    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
-
-    /**
-     * returns true if the optimal value is found (thus we can stop the search)
-     */
-    fn is_optimal(&self) -> bool { !self.heuristic_pruning_done }
+    fn finish(&mut self) {
+        // optimality is proven iff the whole tree was explored without heuristic pruning
+        let explored = self.beam.is_empty() && self.next_beam.is_empty();
+        let mut space = self.space.lock();
+        self.manager.finish(explored && !self.heuristic_pruning_done && space.is_exact());
+        space.stop_search("".to_string());
+    }
 }
 
 
-impl<N, B, G, Tree> BuildableWithInteger<Tree> for PEBeamSearch<N, B, G, Tree>
+impl<N, B, G:Ord, Tree:IteratorNeighborGeneration<N>, H:SpaceHandle<Tree>> BuildableWithInteger<Tree, H> for PEBeamSearch<N, B, G, Tree, H>
 where N:Clone, B:PartialOrd+Copy {
-    fn create_with_integer(space: Rc<RefCell<Tree>>, d:usize) -> Self {
+    fn create_with_integer(space: H, d:usize) -> Self {
         Self::new(space, d)
     }
 }
 
+/**
+ * iterative partial expansion beam search (the beam width grows at each iteration)
+ */
+pub type IterativePEBeamSearch<N, B, G, Tree, H=Rc<RefCell<Tree>>> = IterativeSearch<N, B, PEBeamSearch<N, B, G, Tree, H>, Tree, H>;
+
 /**
  * creates an iterative beam search algorithm
  */
-pub fn create_iterative_pce_beam_search<N, B, G, Tree>(space:Rc<RefCell<Tree>>, d_init:f64, growth:f64)
--> IterativeSearch<N, B, PEBeamSearch<N, B, G, Tree>, Tree>
+pub fn create_iterative_pce_beam_search<N, B, G:Ord, Tree:IteratorNeighborGeneration<N>, H:SpaceHandle<Tree>>(space:H, d_init:f64, growth:f64)
+-> IterativePEBeamSearch<N, B, G, Tree, H>
 where N:Clone, B:Copy+PartialOrd+Display {
     IterativeSearch::new(space, d_init, growth)
 }
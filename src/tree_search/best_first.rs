@@ -4,37 +4,43 @@ use std::marker::PhantomData;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::search_manager::SearchManager;
-use crate::search_algorithm::{StoppingCriterion, SearchAlgorithm};
-use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
+use crate::search_algorithm::{StoppingCriterion, SearchAlgorithm, StepwiseSearch, StepOutcome, CheckpointableSearch};
+use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, CheckpointSpace};
 use crate::tree_search::helper::guided_node::GuidedNode;
 
 /**
 Best First Search structure
 */
 #[derive(Debug)]
-pub struct BestFirstSearch<N, B, G, Space> {
+pub struct BestFirstSearch<N, B, G:Ord, Space, H=Rc<RefCell<Space>>> {
     manager: SearchManager<N, B>,
-    space: Rc<RefCell<Space>>,
-    g: PhantomData<G>,
-    optimal_found: bool,
+    space: H,
+    space_phantom: PhantomData<Space>,
+    /// open nodes (the smallest guide first)
+    pq: BinaryHeap<Reverse<GuidedNode<N, G>>>,
 }
 
-impl<Space, N:Clone, B:PartialOrd+Copy, G:Ord> BestFirstSearch<N, B, G, Space> {
+impl<Space, N:Clone, B:PartialOrd+Copy, G:Ord, H:SpaceHandle<Space>> BestFirstSearch<N, B, G, Space, H> {
     /**
     creates a best first search given a search space.
     */
-    pub fn new(space: Rc<RefCell<Space>>) -> Self {
+    pub fn new(space: H) -> Self {
         Self {
             manager: SearchManager::default(),
             space,
-            g: PhantomData,
-            optimal_found: false,
+            space_phantom: PhantomData,
+            pq: BinaryHeap::new(),
         }
     }
 }
 
-impl<'a, N, B, G, Space> SearchAlgorithm<N, B> for BestFirstSearch<N, B, G, Space>
+impl<'a, N, B, G, Space, H:SpaceHandle<Space>> SearchAlgorithm<N, B> for BestFirstSearch<N, B, G, Space, H>
 where
     N: Clone,
     B: PartialOrd+Copy,
@@ -42,13 +48,37 @@ where
     Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N>,
 {
     fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
-        let mut space = self.space.borrow_mut();
-        let mut pq = BinaryHeap::new();
+        self.run_stepwise(stopping_criterion);
+    }
+
+    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
+
+    fn status(&self) -> SearchStatus { self.manager.status() }
+}
+
+impl<N, B, G, Space, H:SpaceHandle<Space>> StepwiseSearch<N, B> for BestFirstSearch<N, B, G, Space, H>
+where
+    N: Clone,
+    B: PartialOrd+Copy,
+    G: Ord+Clone,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N>,
+{
+    fn init(&mut self) {
+        let mut space = self.space.lock();
+        self.manager.start_search(&mut *space);
+        self.pq = BinaryHeap::new();
         let root = space.initial();
         let g_root = space.guide(&root);
-        pq.push(Reverse(GuidedNode::new(root, g_root)));
-        while !stopping_criterion.is_finished() && !pq.is_empty() {
-            let mut n = pq.pop().unwrap().0.node;
+        self.pq.push(Reverse(GuidedNode::new(root, g_root)));
+    }
+
+    fn step(&mut self, max_expansions:usize) -> StepOutcome {
+        let mut space = self.space.lock();
+        for _ in 0..max_expansions {
+            let mut n = match self.pq.pop() {
+                None => break,
+                Some(e) => e.0.node,
+            };
             // check if goal
             if space.goal(&n) {
                 // compare with best
@@ -62,20 +92,51 @@ where
             }
             // if not, add all its children
             let mut children = space.neighbors(&mut n);
+            self.manager.notify_expansion(children.len());
+            if let Some(b) = space.dual_bound() { self.manager.update_dual_bound(b); }
             while !children.is_empty() {
                 let c = children.pop().unwrap();
                 let g_c = space.guide(&c);
-                pq.push(Reverse(GuidedNode::new(c, g_c)));
+                self.pq.push(Reverse(GuidedNode::new(c, g_c)));
             }
         }
+        if self.pq.is_empty() { StepOutcome::Finished } else { StepOutcome::Running }
+    }
+
+    fn finish(&mut self) {
+        // optimality is proven iff the search was not interrupted and the search space does not
+        // discard nodes heuristically
+        let mut space = self.space.lock();
+        self.manager.finish(self.pq.is_empty() && space.is_exact());
         space.stop_search("".to_string());
-        self.optimal_found = true;
     }
+}
 
-    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
+impl<N, B, G, Space, H:SpaceHandle<Space>> CheckpointableSearch<N, B> for BestFirstSearch<N, B, G, Space, H>
+where
+    N: Clone+Serialize+DeserializeOwned,
+    B: PartialOrd+Copy+Serialize+DeserializeOwned,
+    G: Ord+Clone+Serialize+DeserializeOwned,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N> + CheckpointSpace,
+{
+    fn checkpoint(&self) -> serde_json::Value {
+        let mut space_state = json!({});
+        self.space.lock().save_state(&mut space_state);
+        // the open list is stored in its internal order (restored identically)
+        json!({
+            "manager": self.manager.checkpoint(),
+            "space": space_state,
+            "open": self.pq.iter().map(|e| &e.0).collect::<Vec<_>>(),
+        })
+    }
 
-    /**
-     * returns true if the optimal value is found (thus we can stop the search)
-     */
-    fn is_optimal(&self) -> bool { self.optimal_found }
+    fn resume(&mut self, checkpoint:&serde_json::Value) -> Result<(), serde_json::Error> {
+        let mut space = self.space.lock();
+        self.manager.set_sense(space.sense());
+        self.manager.restore(&checkpoint["manager"])?;
+        space.load_state(&checkpoint["space"])?;
+        let open:Vec<GuidedNode<N, G>> = serde_json::from_value(checkpoint["open"].clone())?;
+        self.pq = open.into_iter().map(Reverse).collect::<Vec<_>>().into();
+        Ok(())
+    }
 }
@@ -3,36 +3,43 @@ use std::marker::PhantomData;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
 
-use crate::search_manager::SearchManager;
-use crate::search_algorithm::{StoppingCriterion, SearchAlgorithm};
-use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration};
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
+use crate::search_algorithm::{StoppingCriterion, SearchAlgorithm, StepwiseSearch, StepOutcome, CheckpointableSearch};
+use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration, CheckpointSpace};
 
 /** defines a Depth First Search structure */
 #[derive(Debug)]
-pub struct DepthFirstSearch<N, B, G, Space> {
+pub struct DepthFirstSearch<N, B, G, Space, H=Rc<RefCell<Space>>> {
     manager: SearchManager<N, B>,
-    space: Rc<RefCell<Space>>,
+    space: H,
+    space_phantom: PhantomData<Space>,
+    /// nodes to explore (the front is explored first)
+    stack: LinkedList<N>,
     g: PhantomData<G>,
-    optimal_found: bool,
 }
 
-impl<Space, N:Clone, B:PartialOrd+Copy, G:Ord> DepthFirstSearch<N, B, G, Space> {
+impl<Space, N:Clone, B:PartialOrd+Copy, G:Ord, H:SpaceHandle<Space>> DepthFirstSearch<N, B, G, Space, H> {
     /**
     creates a best first search given a search space.
     */
-    pub fn new(space: Rc<RefCell<Space>>) -> Self {
+    pub fn new(space: H) -> Self {
         Self {
             manager: SearchManager::default(),
             space,
+            space_phantom: PhantomData,
+            stack: LinkedList::new(),
             g: PhantomData,
-            optimal_found: false,
         }
     }
 }
 
 
-impl<'a, N, B, G, Space> SearchAlgorithm<N, B> for DepthFirstSearch<N, B, G, Space>
+impl<'a, N, B, G, Space, H:SpaceHandle<Space>> SearchAlgorithm<N, B> for DepthFirstSearch<N, B, G, Space, H>
 where
     N: Clone,
     B: PartialOrd+Copy,
@@ -40,11 +47,35 @@ where
     Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N>,
 {
     fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
-        let mut space = self.space.borrow_mut();
-        let mut stack = LinkedList::new();
-        stack.push_back(space.initial());
-        while !stopping_criterion.is_finished() && !stack.is_empty() {
-            let mut n = stack.pop_front().unwrap();
+        self.run_stepwise(stopping_criterion);
+    }
+
+    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
+
+    fn status(&self) -> SearchStatus { self.manager.status() }
+}
+
+impl<N, B, G, Space, H:SpaceHandle<Space>> StepwiseSearch<N, B> for DepthFirstSearch<N, B, G, Space, H>
+where
+    N: Clone,
+    B: PartialOrd+Copy,
+    G: Ord+Clone,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N>,
+{
+    fn init(&mut self) {
+        let mut space = self.space.lock();
+        self.manager.start_search(&mut *space);
+        self.stack = LinkedList::new();
+        self.stack.push_back(space.initial());
+    }
+
+    fn step(&mut self, max_expansions:usize) -> StepOutcome {
+        let mut space = self.space.lock();
+        for _ in 0..max_expansions {
+            let mut n = match self.stack.pop_front() {
+                None => break,
+                Some(n) => n,
+            };
             // check if goal
             if space.goal(&n) {
                 // compare with best
@@ -58,18 +89,48 @@ where
             }
             // if not, add all its children
             let mut children = space.neighbors(&mut n);
+            self.manager.notify_expansion(children.len());
+            if let Some(b) = space.dual_bound() { self.manager.update_dual_bound(b); }
             children.sort_by_key(|e| space.guide(e));
             while !children.is_empty() {
-                stack.push_front(children.pop().unwrap());
+                self.stack.push_front(children.pop().unwrap());
             }
         }
+        if self.stack.is_empty() { StepOutcome::Finished } else { StepOutcome::Running }
+    }
+
+    fn finish(&mut self) {
+        // optimality is proven iff the search was not interrupted and the search space does not
+        // discard nodes heuristically
+        let mut space = self.space.lock();
+        self.manager.finish(self.stack.is_empty() && space.is_exact());
         space.stop_search("".to_string());
     }
+}
 
-    fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
+impl<N, B, G, Space, H:SpaceHandle<Space>> CheckpointableSearch<N, B> for DepthFirstSearch<N, B, G, Space, H>
+where
+    N: Clone+Serialize+DeserializeOwned,
+    B: PartialOrd+Copy+Serialize+DeserializeOwned,
+    G: Ord+Clone,
+    Space: SearchSpace<N,B> + GuidedSpace<N,G> + TotalNeighborGeneration<N> + CheckpointSpace,
+{
+    fn checkpoint(&self) -> serde_json::Value {
+        let mut space_state = json!({});
+        self.space.lock().save_state(&mut space_state);
+        json!({
+            "manager": self.manager.checkpoint(),
+            "space": space_state,
+            "stack": self.stack,
+        })
+    }
 
-    /**
-     * returns true if the optimal value is found (thus we can stop the search)
-     */
-    fn is_optimal(&self) -> bool { self.optimal_found }
+    fn resume(&mut self, checkpoint:&serde_json::Value) -> Result<(), serde_json::Error> {
+        let mut space = self.space.lock();
+        self.manager.set_sense(space.sense());
+        self.manager.restore(&checkpoint["manager"])?;
+        space.load_state(&checkpoint["space"])?;
+        self.stack = serde_json::from_value(checkpoint["stack"].clone())?;
+        Ok(())
+    }
 }
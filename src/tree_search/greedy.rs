@@ -3,37 +3,53 @@ use std::marker::PhantomData;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use rand::prelude::{SliceRandom, ThreadRng};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::prelude::SliceRandom;
 
-use crate::search_manager::SearchManager;
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
 use crate::search_space::{SearchSpace, GuidedSpace, TotalNeighborGeneration};
-use crate::search_algorithm::{SearchAlgorithm, StoppingCriterion};
+use crate::search_algorithm::{SearchAlgorithm, StoppingCriterion, DEFAULT_SEED};
+use crate::search_report::{SearchReport, ReportSection};
 
 /**
 implements a partial expansion greedy algorithm
 */
 #[derive(Debug)]
-pub struct Greedy<N, B, G, Tree> {
+pub struct Greedy<N, B, G, Tree, H=Rc<RefCell<Tree>>> {
     /// search manager
     pub manager: SearchManager<N, B>,
-    tree: Rc<RefCell<Tree>>,
+    tree: H,
+    space_phantom: PhantomData<Tree>,
     g: PhantomData<G>,
-    rng: ThreadRng,
+    /// seed of the random number generator (ties are broken randomly)
+    seed: u64,
+    rng: StdRng,
 }
 
-impl<N:Clone, B:PartialOrd+Copy, G, Tree> Greedy<N, B, G, Tree> {
+impl<N:Clone, B:PartialOrd+Copy, G, Tree, H:SpaceHandle<Tree>> Greedy<N, B, G, Tree, H> {
     /** builds the partial expansion greedy using the search space */
-    pub fn new(tree: Rc<RefCell<Tree>>) -> Self {
+    pub fn new(tree: H) -> Self {
         Self {
             manager: SearchManager::default(),
             tree,
+            space_phantom: PhantomData,
             g: PhantomData,
-            rng: rand::thread_rng(),
+            seed: DEFAULT_SEED,
+            rng: StdRng::seed_from_u64(DEFAULT_SEED),
         }
     }
+
+    /** sets the seed of the random number generator used to break ties */
+    pub fn with_seed(mut self, seed:u64) -> Self {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
 }
 
-impl<'a, N, B, G:Ord+Clone, Tree> SearchAlgorithm<N, B> for Greedy<N, B, G, Tree>
+impl<'a, N, B, G:Ord+Clone, Tree, H:SpaceHandle<Tree>> SearchAlgorithm<N, B> for Greedy<N, B, G, Tree, H>
 where
     N: Clone,
     B: PartialOrd+Copy,
@@ -41,7 +57,8 @@ where
 {
 
     fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) where SC:StoppingCriterion {
-        let mut space = self.tree.borrow_mut();
+        let mut space = self.tree.lock();
+        self.manager.start_search(&mut *space);
         let mut n = space.initial();
         while !stopping_criterion.is_finished() {
             // check if goal
@@ -58,7 +75,10 @@ where
             // get the neighbor with minimum guide (break ties randomly)
             let mut best_val = None;
             let mut bests = Vec::new();
-            for neigh in space.neighbors(&mut n) {
+            let neighbors = space.neighbors(&mut n);
+            self.manager.notify_expansion(neighbors.len());
+            if let Some(b) = space.dual_bound() { self.manager.update_dual_bound(b); }
+            for neigh in neighbors {
                 match &best_val {
                     None => {
                         best_val = Some(space.guide(&neigh));
@@ -91,15 +111,39 @@ where
             //     Some(neigh) => { n = neigh.clone(); }
             // }
         }
+        // the greedy does not prove optimality
+        self.manager.finish(false);
         space.stop_search("".to_string());
     }
 
 
     fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
 
-    /**
-     * returns true if the optimal value is found (thus we can stop the search).
-     * For this greedy, we set it to always false (it is not destined to prove optimality)
-     */
-    fn is_optimal(&self) -> bool { false }
-}
\ No newline at end of file
+    fn status(&self) -> SearchStatus { self.manager.status() }
+
+    fn report(&self, report:&mut SearchReport) {
+        report.status = Some(self.status());
+        report.push(ReportSection::new("greedy").with_parameter("seed", self.seed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_spaces::{Node, tie_tree, seeded_run};
+
+    #[test]
+    fn seeded_ties() {
+        let greedy = |seed| seeded_run(Greedy::<Node, i64, i32, _>::new(tie_tree()).with_seed(seed), 100);
+        // two runs with the same seed are identical
+        for seed in 0..5 {
+            assert_eq!(greedy(seed), greedy(seed));
+        }
+        assert_eq!(greedy(3)["greedy_seed"], serde_json::json!(3));
+        // ties are broken according to the seed
+        let greedy_values:Vec<_> = (0..10).map(|seed| greedy(seed)["best"]["cost"].clone()).collect();
+        assert!(greedy_values.iter().any(|v| *v != greedy_values[0]));
+        // without a seed, the default one is used
+        assert_eq!(seeded_run(Greedy::<Node, i64, i32, _>::new(tie_tree()), 100), greedy(DEFAULT_SEED));
+    }
+}
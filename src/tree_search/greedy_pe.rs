@@ -3,7 +3,8 @@ use std::marker::PhantomData;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::search_manager::SearchManager;
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
 use crate::search_space::{SearchSpace, GuidedSpace, PartialNeighborGeneration};
 use crate::search_algorithm::{SearchAlgorithm, StoppingCriterion};
 
@@ -11,25 +12,27 @@ use crate::search_algorithm::{SearchAlgorithm, StoppingCriterion};
 implements a partial expansion greedy algorithm
 */
 #[derive(Debug)]
-pub struct PEGreedy<N, B, G, Tree> {
+pub struct PEGreedy<N, B, G, Tree, H=Rc<RefCell<Tree>>> {
     /// search manager
     pub manager: SearchManager<N, B>,
-    tree: Rc<RefCell<Tree>>,
+    tree: H,
+    space_phantom: PhantomData<Tree>,
     g: PhantomData<G>,
 }
 
-impl<N:Clone, B:PartialOrd+Copy, G, Tree> PEGreedy<N, B, G, Tree> {
+impl<N:Clone, B:PartialOrd+Copy, G, Tree, H:SpaceHandle<Tree>> PEGreedy<N, B, G, Tree, H> {
     /** builds the partial expansion greedy using the search space */
-    pub fn new(tree: Rc<RefCell<Tree>>) -> Self {
+    pub fn new(tree: H) -> Self {
         Self {
             manager: SearchManager::default(),
             tree,
+            space_phantom: PhantomData,
             g: PhantomData,
         }
     }
 }
 
-impl<'a, N, B, G:Ord+Clone, Tree> SearchAlgorithm<N, B> for PEGreedy<N, B, G, Tree>
+impl<'a, N, B, G:Ord+Clone, Tree, H:SpaceHandle<Tree>> SearchAlgorithm<N, B> for PEGreedy<N, B, G, Tree, H>
 where
     N: Clone,
     B: PartialOrd+Copy,
@@ -38,7 +41,8 @@ where
 
     fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC)
     where SC:StoppingCriterion,  {
-        let mut space = self.tree.borrow_mut();
+        let mut space = self.tree.lock();
+        self.manager.start_search(&mut *space);
         let mut n = space.initial();
         while !stopping_criterion.is_finished() {
             // check if goal
@@ -53,22 +57,23 @@ where
                 break;
             }
             // if not, explore children until a feasible children is found
+            let child = space.next_neighbor(&mut n);
+            self.manager.notify_expansion(usize::from(child.is_some()));
+            if let Some(b) = space.dual_bound() { self.manager.update_dual_bound(b); }
             let tmp;
-            match space.next_neighbor(&mut n) {
+            match child {
                 None => { break; },  // stop if no more children
                 Some(c) => { tmp = c; }
             }
             n = tmp;
         }
+        // the greedy does not prove optimality
+        self.manager.finish(false);
         space.stop_search("".to_string());
     }
 
 
     fn get_manager(&mut self) -> &mut SearchManager<N, B> { &mut self.manager }
 
-    /**
-     * returns true if the optimal value is found (thus we can stop the search).
-     * For this greedy, we set it to always false (it is not destined to prove optimality)
-     */
-    fn is_optimal(&self) -> bool { false }
+    fn status(&self) -> SearchStatus { self.manager.status() }
 }
\ No newline at end of file
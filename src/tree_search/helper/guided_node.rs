@@ -1,11 +1,13 @@
 use std::cmp::Ordering;
 
+use serde::{Serialize, Deserialize};
+
 /**
 implements a guided node.
  - implements the Ord trait for nodes using the guide method from the search space)
  - fast guidance computation if the underlying search-space does not explicitely stores the guide
 */
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GuidedNode<N, G:Ord> {
     /// underlying node
     pub node: N,
@@ -3,52 +3,66 @@
  * (for instance IterativeBeamSearch).
  */
 use std::cell::RefCell;
-use std::rc::{Weak,Rc};
+use std::rc::Rc;
+use std::sync::Weak;
 use std::fmt::Display;
 use std::marker::PhantomData;
 
-use crate::search_space::SearchSpace;
-use crate::search_manager::SearchManager;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::search_space::{SearchSpace, CheckpointSpace};
+use crate::space_handle::SpaceHandle;
+use crate::search_manager::{SearchManager, SearchStatus};
 use crate::metric_logger::{Metric, MetricLogger};
-use crate::search_algorithm::{BuildableWithInteger, StoppingCriterion, SearchAlgorithm};
+use crate::search_algorithm::{BuildableWithInteger, StoppingCriterion, SearchAlgorithm, StepwiseSearch, StepOutcome, CheckpointableSearch};
 
 /**
 An iterative search repetively builds a search algorithm that can be constructed using an integer
 it progressively increases the number that represents the search effort.
 */
 #[derive(Debug)]
-pub struct IterativeSearch<N, B, Algo, Tree> {
+pub struct IterativeSearch<N, B, Algo, Tree, H=Rc<RefCell<Tree>>> {
     /// search manager of the iterative search
     pub manager: SearchManager<N, B>,
-    space: Rc<RefCell<Tree>>,
+    space: H,
+    space_phantom: PhantomData<Tree>,
     dinit: f64,
     growth: f64,
     logger: Weak<MetricLogger>,
     logging_id_msg: Option<usize>,
-    is_optimal: bool,
-    algo_phantom: PhantomData<Algo>,
+    /// effort (integer parameter) of the current (or next) iteration
+    d: f64,
+    /// search of the current iteration (None between two iterations)
+    current: Option<Algo>,
+    /// true iff an iteration proved optimality (or infeasibility)
+    proven: bool,
 }
 
 
-impl<N:Clone, B: PartialOrd + Display + Copy, Algo, Tree> IterativeSearch<N, B, Algo, Tree> {
+impl<N:Clone, B: PartialOrd + Display + Copy, Algo, Tree, H:SpaceHandle<Tree>> IterativeSearch<N, B, Algo, Tree, H> {
     /** constructs an iterated search from the search space, and the integer series defined
     as the initial value (dinit) and the geometric growth factor (growth).
     */
-    pub fn new(space: Rc<RefCell<Tree>>, dinit: f64, growth: f64) -> Self {
+    pub fn new(space: H, dinit: f64, growth: f64) -> Self {
         Self {
             manager: SearchManager::default(),
             space,
+            space_phantom: PhantomData,
             dinit,
             growth,
             logger: Weak::new(),
             logging_id_msg: None,
-            is_optimal: false,
-            algo_phantom: PhantomData,
+            d: dinit,
+            current: None,
+            proven: false,
         }
     }
 
     /**
-    binds the iterative search to a logger (to display the iteration number)
+    binds the iterative search to a logger (to display the iteration number).
+    Takes a `std::sync::Weak` obtained with `Arc::downgrade(&logger)`.
     */
     pub fn bind_logger(mut self, logger_ref:Weak<MetricLogger>) -> Self {
         if let Some(logger) = logger_ref.upgrade() {
@@ -64,44 +78,126 @@ impl<N:Clone, B: PartialOrd + Display + Copy, Algo, Tree> IterativeSearch<N, B,
     }
 }
 
-impl<N, B, Algo, Tree> SearchAlgorithm<N,B> for IterativeSearch<N, B, Algo, Tree>
+impl<N, B, Algo, Tree, H:SpaceHandle<Tree>> SearchAlgorithm<N,B> for IterativeSearch<N, B, Algo, Tree, H>
 where
     N:Clone,
     B:PartialOrd+Display+Copy,
-    Algo:SearchAlgorithm<N, B>+BuildableWithInteger<Tree>,
+    Algo:StepwiseSearch<N, B>+BuildableWithInteger<Tree, H>,
     Tree:SearchSpace<N,B>,
 {
 
     fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
-        let mut d = self.dinit;
-        while !stopping_criterion.is_finished() && !self.is_optimal() {
-            self.space.borrow_mut().restart(format!("Iter D={}", d));
+        self.run_stepwise(stopping_criterion);
+    }
+
+    fn status(&self) -> SearchStatus { self.manager.status() }
+
+    fn get_manager(&mut self) -> &mut SearchManager<N,B> { &mut self.manager }
+
+}
+
+impl<N, B, Algo, Tree, H:SpaceHandle<Tree>> StepwiseSearch<N,B> for IterativeSearch<N, B, Algo, Tree, H>
+where
+    N:Clone,
+    B:PartialOrd+Display+Copy,
+    Algo:StepwiseSearch<N, B>+BuildableWithInteger<Tree, H>,
+    Tree:SearchSpace<N,B>,
+{
+    fn init(&mut self) {
+        self.d = self.dinit;
+        self.current = None;
+        self.proven = false;
+        self.manager.set_sense(self.space.lock().sense());
+    }
+
+    /** a step does not go past the end of an iteration */
+    fn step(&mut self, max_expansions:usize) -> StepOutcome {
+        if self.proven { return StepOutcome::Finished; }
+        if self.current.is_none() {
+            // starts a new iteration
+            let msg = format!("Iter D={}", self.d);
+            self.manager.notify_restart(&msg);
+            self.space.lock().restart(msg);
             // updates logger and display statistics
             if let Some(logger) = self.logger.upgrade() {
                 if let Some(id) = self.logging_id_msg {
-                    logger.update_metric(id, Metric::Text(format!("start D={}", d)));
+                    logger.update_metric(id, Metric::Text(format!("start D={}", self.d)));
                     logger.request_logging();
                     logger.update_metric(id, Metric::Text("".to_string()));
                 }
             }
-            let mut ts:Algo = Algo::create_with_integer(self.space.clone(), d as usize);
+            let mut ts:Algo = Algo::create_with_integer(self.space.clone(), self.d as usize);
+            // the underlying search reports its progress to the iterative search
+            self.manager.share_progress(ts.get_manager());
             // initializes the underlying beam search with best known solution
-            self.manager.give_best(&mut ts.get_manager());
-            ts.run(stopping_criterion.clone());
-            ts.get_manager().give_best(&mut self.manager);
-            // gets best
-            d = (d * self.growth).ceil();
-            if ts.is_optimal() {
-                self.is_optimal = true;
-                break
-            }
+            self.manager.give_best(ts.get_manager());
+            ts.init();
+            self.current = Some(ts);
         }
-        self.space.borrow_mut().stop_search("".to_string());
+        let ts = self.current.as_mut().unwrap();
+        if ts.step(max_expansions) == StepOutcome::Running { return StepOutcome::Running; }
+        // the iteration is over: gets best
+        ts.finish();
+        ts.get_manager().give_best(&mut self.manager);
+        self.manager.notify_iteration_end();
+        self.d = (self.d * self.growth).ceil();
+        // stop if the underlying search proved optimality (or infeasibility)
+        self.proven = matches!(ts.status(), SearchStatus::Optimal | SearchStatus::Infeasible);
+        self.current = None;
+        if self.proven { StepOutcome::Finished } else { StepOutcome::Running }
     }
 
-    fn is_optimal(&self) -> bool { self.is_optimal }
+    fn finish(&mut self) {
+        // ends the interrupted iteration (if any)
+        if let Some(mut ts) = self.current.take() {
+            ts.finish();
+            ts.get_manager().give_best(&mut self.manager);
+        }
+        self.manager.finish(self.proven);
+        self.space.lock().stop_search("".to_string());
+    }
+}
 
-    fn get_manager(&mut self) -> &mut SearchManager<N,B> { &mut self.manager }
+impl<N, B, Algo, Tree, H:SpaceHandle<Tree>> CheckpointableSearch<N,B> for IterativeSearch<N, B, Algo, Tree, H>
+where
+    N:Clone+Serialize+DeserializeOwned,
+    B:PartialOrd+Display+Copy+Serialize+DeserializeOwned,
+    Algo:CheckpointableSearch<N, B>+BuildableWithInteger<Tree, H>,
+    Tree:SearchSpace<N,B>+CheckpointSpace,
+{
+    fn checkpoint(&self) -> serde_json::Value {
+        let mut res = json!({
+            "manager": self.manager.checkpoint(),
+            "d": self.d,
+            "proven": self.proven,
+        });
+        match &self.current {
+            // the current iteration stores the state of the search space
+            Some(ts) => res["current"] = ts.checkpoint(),
+            None => {
+                let mut space_state = json!({});
+                self.space.lock().save_state(&mut space_state);
+                res["space"] = space_state;
+            }
+        }
+        res
+    }
 
+    fn resume(&mut self, checkpoint:&serde_json::Value) -> Result<(), serde_json::Error> {
+        self.manager.set_sense(self.space.lock().sense());
+        self.manager.restore(&checkpoint["manager"])?;
+        self.d = serde_json::from_value(checkpoint["d"].clone())?;
+        self.proven = serde_json::from_value(checkpoint["proven"].clone())?;
+        self.current = None;
+        if checkpoint["current"].is_null() {
+            self.space.lock().load_state(&checkpoint["space"])?;
+        } else {
+            // resumes the current iteration
+            let mut ts:Algo = Algo::create_with_integer(self.space.clone(), self.d as usize);
+            self.manager.share_progress(ts.get_manager());
+            ts.resume(&checkpoint["current"])?;
+            self.current = Some(ts);
+        }
+        Ok(())
+    }
 }
-
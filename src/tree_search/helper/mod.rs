@@ -3,3 +3,6 @@ pub mod guided_node;
 
 /** implements a generic iterative serch procedure */
 pub mod iterative;
+
+/** pheromone table (MAX-MIN Ant System) used by ant colony based algorithms */
+pub mod pheromone;
@@ -0,0 +1,174 @@
+use std::hash::Hash;
+
+use fxhash::FxHashMap;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+/**
+pheromone trail stored for a decision: value at its last update and iteration of this update
+*/
+#[derive(Debug, Clone)]
+struct PheromoneTrail {
+    value: f64,
+    last_update: u64,
+}
+
+/**
+Pheromone table (MAX-MIN Ant System) indexed by decisions.
+Values are bounded in [tau_min, tau_max] and initialized to tau_max.
+Evaporation is performed lazily: a trail that is not reinforced during k iterations is
+multiplied by (1-evaporation)^k when it is read.
+*/
+#[derive(Debug, Clone)]
+pub struct PheromoneTable<D> {
+    trails: FxHashMap<D, PheromoneTrail>,
+    tau_min: f64,
+    tau_max: f64,
+    evaporation: f64,
+    iteration: u64,
+}
+
+impl<D> PheromoneTable<D> where D:Hash+Eq {
+    /**
+    builds a pheromone table given its bounds and its evaporation rate (in [0,1])
+    */
+    pub fn new(tau_min:f64, tau_max:f64, evaporation:f64) -> Self {
+        debug_assert!(tau_min <= tau_max);
+        Self {
+            trails: FxHashMap::default(),
+            tau_min,
+            tau_max,
+            evaporation,
+            iteration: 0,
+        }
+    }
+
+    /** evaporated value of a trail last updated at iteration `last_update` */
+    fn evaporated(&self, value:f64, last_update:u64) -> f64 {
+        let nb_evaporations = (self.iteration - last_update) as i32;
+        (value * (1.-self.evaporation).powi(nb_evaporations)).max(self.tau_min)
+    }
+
+    /**
+    returns the pheromone value of a decision
+    */
+    pub fn get(&self, d:&D) -> f64 {
+        match self.trails.get(d) {
+            None => self.evaporated(self.tau_max, 0),
+            Some(trail) => self.evaporated(trail.value, trail.last_update),
+        }
+    }
+
+    /**
+    ends the current iteration: all trails evaporate
+    */
+    pub fn evaporate(&mut self) {
+        self.iteration += 1;
+    }
+
+    /**
+    reinforces the trails of the given decisions: tau ← tau + evaporation * amount.
+    With amount = tau_max, reinforced trails converge towards tau_max.
+    */
+    pub fn reinforce(&mut self, decisions:&[D], amount:f64) where D:Clone {
+        for d in decisions {
+            let value = (self.get(d) + self.evaporation * amount).min(self.tau_max);
+            self.trails.insert(d.clone(), PheromoneTrail { value, last_update: self.iteration });
+        }
+    }
+
+    /** returns the maximum pheromone value */
+    pub fn tau_max(&self) -> f64 { self.tau_max }
+
+    /** returns the number of decisions stored in the table */
+    pub fn len(&self) -> usize { self.trails.len() }
+
+    /** true iff no decision was reinforced yet */
+    pub fn is_empty(&self) -> bool { self.trails.is_empty() }
+
+    /** exports the trails and the current iteration to a JSON format */
+    pub fn save_state(&self) -> serde_json::Value where D:Serialize {
+        // trails are stored as (decision, value, last update) (decisions may not be valid JSON keys)
+        let trails:Vec<_> = self.trails.iter().map(|(d, t)| (d, t.value, t.last_update)).collect();
+        json!({
+            "trails": trails,
+            "iteration": self.iteration,
+        })
+    }
+
+    /** restores the trails and the current iteration from a JSON format (built by save_state) */
+    pub fn load_state(&mut self, json:&serde_json::Value) -> Result<(), serde_json::Error> where D:DeserializeOwned {
+        let trails:Vec<(D, f64, u64)> = serde_json::from_value(json["trails"].clone())?;
+        self.trails = trails.into_iter().map(|(d, value, last_update)| (d, PheromoneTrail { value, last_update })).collect();
+        self.iteration = serde_json::from_value(json["iteration"].clone())?;
+        Ok(())
+    }
+}
+
+/**
+maps a guide to a positive value while keeping the order of the guides (used by the ant colony
+and the pheromone combinator to mix the guides with the pheromone). Guides of at least 1 are
+kept, smaller guides are mapped to (0,1): 1/(2-guide) (a null guide is mapped to 1/2).
+*/
+pub fn positive_guide(guide:f64) -> f64 {
+    if guide >= 1. { guide } else { 1. / (2. - guide) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positive_guides() {
+        assert_eq!(positive_guide(3.), 3.);
+        assert_eq!(positive_guide(1.), 1.);
+        assert_eq!(positive_guide(0.), 0.5);
+        assert_eq!(positive_guide(-2.), 0.25);
+        // the order of the guides is kept
+        let guides = [-1e9, -3., -0.5, 0., 0.5, 0.999, 1., 1.5, 1e9];
+        assert!(guides.windows(2).all(|w| positive_guide(w[0]) < positive_guide(w[1])));
+        assert!(positive_guide(-1e9) > 0.);
+    }
+
+    #[test]
+    fn initialized_to_tau_max() {
+        let table:PheromoneTable<usize> = PheromoneTable::new(0.1, 1., 0.5);
+        assert_eq!(table.get(&3), 1.);
+    }
+
+    #[test]
+    fn evaporation_bounded_by_tau_min() {
+        let mut table:PheromoneTable<usize> = PheromoneTable::new(0.1, 1., 0.5);
+        table.evaporate();
+        assert_eq!(table.get(&3), 0.5);
+        for _ in 0..10 { table.evaporate(); }
+        assert_eq!(table.get(&3), 0.1);
+    }
+
+    #[test]
+    fn reinforcement_bounded_by_tau_max() {
+        let mut table:PheromoneTable<usize> = PheromoneTable::new(0.1, 1., 0.5);
+        table.evaporate();
+        table.evaporate();
+        table.reinforce(&[1], 1.);
+        assert_eq!(table.get(&1), 0.75);
+        assert_eq!(table.get(&2), 0.25);
+        table.reinforce(&[1], 1.);
+        assert_eq!(table.get(&1), 1.);
+    }
+
+    #[test]
+    fn state_round_trip() {
+        let mut table:PheromoneTable<(usize, i64)> = PheromoneTable::new(0.1, 1., 0.5);
+        table.evaporate();
+        table.reinforce(&[(1, 2)], 1.);
+        table.evaporate();
+        let mut restored:PheromoneTable<(usize, i64)> = PheromoneTable::new(0.1, 1., 0.5);
+        restored.load_state(&table.save_state()).unwrap();
+        assert_eq!(restored.get(&(1, 2)), table.get(&(1, 2)));
+        assert_eq!(restored.get(&(0, 0)), table.get(&(0, 0)));
+        assert_eq!(restored.len(), 1);
+    }
+}
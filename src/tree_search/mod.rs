@@ -20,4 +20,12 @@ pub mod beam_search_pe;
 pub mod greedy_pe;
 
 /** Greedy algorithm using total neighborhood expansion */
-pub mod greedy;
\ No newline at end of file
+pub mod greedy;
+/** Ant Colony Optimization (MAX-MIN Ant System) walking the search tree */
+pub mod ant_colony;
+
+/** Beam-ACO: repeated beam searches guided by the guide and a pheromone table */
+pub mod beam_aco;
+
+/** Bi-objective beam search (Pareto-diverse beam and Pareto archive) */
+pub mod pareto_beam_search;
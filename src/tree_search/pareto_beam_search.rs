@@ -0,0 +1,319 @@
+use std::marker::PhantomData;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde_json::json;
+
+use crate::pareto_manager::ParetoManager;
+use crate::space_handle::SpaceHandle;
+use crate::search_algorithm::StoppingCriterion;
+use crate::search_space::{SearchSpace, TotalNeighborGeneration, ParetoDominanceSpace, BiObjectiveSpace};
+
+/**
+Bi-objective beam search: approximates the Pareto front of a bi-objective search space.
+At each level, the children are sorted by non-dominated sorting (ParetoDominanceSpace) and the
+beam is filled front by front. The last front that fits partially is truncated by decreasing
+crowding distance (BiObjectiveSpace), to keep a diverse beam.
+Goals are inserted in a ParetoManager archive, and the nodes dominated by the archive are pruned.
+
+It is a standalone API: its result is a Pareto archive (get_pareto_manager) instead of a single
+best known solution, so it does not implement SearchAlgorithm, StepwiseSearch or
+CheckpointableSearch, and it has no search observers. Its progress (number of expanded nodes) is
+shared through the ParetoManager (for instance with NodeBudgetStoppingCriterion::expanded_from).
+As the other algorithms, it notifies the search space when the search starts and stops
+(SearchSpace::start_search and SearchSpace::stop_search).
+*/
+#[derive(Debug)]
+pub struct ParetoBeamSearch<N, B, Space, H=Rc<RefCell<Space>>> {
+    manager: ParetoManager<N>,
+    space: H,
+    space_phantom: PhantomData<Space>,
+    d: usize,
+    nb_pruned: u64,
+    heuristic_pruning_done: bool,
+    b: PhantomData<B>,
+}
+
+impl<N, B, Space, H:SpaceHandle<Space>> ParetoBeamSearch<N, B, Space, H> {
+    /** builds the beam search given a search space, a beam width and the reference point used
+    to compute the hypervolume of the archive */
+    pub fn new(space: H, d: usize, reference:[f64; 2]) -> Self {
+        Self {
+            manager: ParetoManager::new(reference),
+            space,
+            space_phantom: PhantomData,
+            d,
+            nb_pruned: 0,
+            heuristic_pruning_done: false,
+            b: PhantomData,
+        }
+    }
+
+    /** returns the Pareto archive */
+    pub fn get_pareto_manager(&mut self) -> &mut ParetoManager<N> { &mut self.manager }
+
+    /** returns true iff the search explored the whole tree (the archive is the exact front) */
+    pub fn is_exact(&self) -> bool { !self.heuristic_pruning_done }
+
+    /** exports the Pareto front, the hypervolume through time and the number of prunings */
+    pub fn json_statistics(&self, json:&mut serde_json::Value) {
+        self.manager.json_statistics(json);
+        json["nb_pruned"] = json!(self.nb_pruned);
+        json["is_exact"] = json!(self.is_exact());
+    }
+}
+
+impl<N, B, Space, H:SpaceHandle<Space>> ParetoBeamSearch<N, B, Space, H>
+where Space: SearchSpace<N,B> + TotalNeighborGeneration<N> + ParetoDominanceSpace<N> + BiObjectiveSpace<N>
+{
+    /**
+     * runs until the stopping_criterion is reached (or the tree is explored)
+     */
+    pub fn run<SC:StoppingCriterion>(&mut self, stopping_criterion:SC) {
+        let space_rc = self.space.clone();
+        let mut space = space_rc.lock();
+        space.start_search(String::new());
+        self.heuristic_pruning_done = false;
+        let root = space.initial();
+        if space.goal(&root) {
+            let objectives = space.objectives(&root);
+            self.manager.insert(root, objectives);
+            space.stop_search("".to_string());
+            return;
+        }
+        let mut beam = vec![root];
+        while !stopping_criterion.is_finished() && !beam.is_empty() {
+            let mut children = Vec::new();
+            while !stopping_criterion.is_finished() {
+                let mut n = match beam.pop() {
+                    None => { break; },
+                    Some(n) => n,
+                };
+                let neighbors = space.neighbors(&mut n);
+                self.manager.notify_expansion(neighbors.len());
+                for c in neighbors {
+                    let objectives = space.objectives(&c);
+                    if space.goal(&c) {
+                        self.manager.insert(c, objectives);
+                    } else if self.manager.is_dominated(&objectives) {
+                        self.nb_pruned += 1;
+                    } else {
+                        children.push(c);
+                    }
+                }
+            }
+            // stop if the level was interrupted by the stopping criterion
+            if !beam.is_empty() {
+                self.heuristic_pruning_done = true;
+                break;
+            }
+            beam = self.select(&space, children);
+        }
+        // the next level was not explored (stopped between two levels)
+        self.heuristic_pruning_done |= !beam.is_empty();
+        space.stop_search("".to_string());
+    }
+
+    /** selects at most d nodes (non-dominated sorting, then crowding distance) */
+    fn select(&mut self, space:&Space, children:Vec<N>) -> Vec<N> {
+        if children.len() <= self.d { return children; }
+        self.heuristic_pruning_done = true;
+        let mut selected:Vec<usize> = Vec::with_capacity(self.d);
+        for front in non_dominated_sort(&children, |a,b| space.dominates(a,b)) {
+            if selected.len() + front.len() <= self.d {
+                selected.extend(front);
+            } else {
+                let objectives:Vec<[f64; 2]> = front.iter().map(|i| space.objectives(&children[*i])).collect();
+                let distances = crowding_distance(&objectives);
+                let mut order:Vec<usize> = (0..front.len()).collect();
+                order.sort_by(|a,b| distances[*b].total_cmp(&distances[*a]));
+                let nb_missing = self.d - selected.len();
+                selected.extend(order.into_iter().take(nb_missing).map(|k| front[k]));
+            }
+            if selected.len() == self.d { break; }
+        }
+        let mut nodes:Vec<Option<N>> = children.into_iter().map(Some).collect();
+        selected.into_iter().filter_map(|i| nodes[i].take()).collect()
+    }
+}
+
+
+/**
+ * non-dominated sorting: returns the successive Pareto fronts (indices of the nodes).
+ * The first front contains the nodes that are not dominated, the second one the nodes only
+ * dominated by the first front, etc.
+ */
+pub fn non_dominated_sort<N, F>(nodes:&[N], dominates:F) -> Vec<Vec<usize>>
+where F: Fn(&N, &N) -> bool {
+    let mut remaining:Vec<usize> = (0..nodes.len()).collect();
+    let mut res = Vec::new();
+    while !remaining.is_empty() {
+        let (front, others):(Vec<usize>, Vec<usize>) = remaining.iter().partition(|i|
+            !remaining.iter().any(|j| j != *i && dominates(&nodes[*j], &nodes[**i]))
+        );
+        if front.is_empty() { // dominance cycle (inconsistent dominance relation)
+            res.push(others);
+            break;
+        }
+        res.push(front);
+        remaining = others;
+    }
+    res
+}
+
+
+/**
+ * crowding distance of each point of a front (the larger, the more isolated).
+ * The extreme points of each objective have an infinite distance.
+ */
+pub fn crowding_distance(objectives:&[[f64; 2]]) -> Vec<f64> {
+    let n = objectives.len();
+    let mut res = vec![0.; n];
+    if n <= 2 { return vec![f64::INFINITY; n]; }
+    for k in [0, 1] {
+        let mut order:Vec<usize> = (0..n).collect();
+        order.sort_by(|a,b| objectives[*a][k].total_cmp(&objectives[*b][k]));
+        let range = objectives[order[n-1]][k] - objectives[order[0]][k];
+        res[order[0]] = f64::INFINITY;
+        res[order[n-1]] = f64::INFINITY;
+        if range <= 0. { continue; }
+        for w in 1..n-1 {
+            res[order[w]] += (objectives[order[w+1]][k] - objectives[order[w-1]][k]) / range;
+        }
+    }
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_algorithm::{NeverStoppingCriterion, NodeBudgetStoppingCriterion};
+    use crate::combinators::bounding::BoundingCombinator;
+    use crate::combinators::neighbor_generation::TotalAdapter;
+    use crate::combinators::solution_pool::SolutionPoolCombinator;
+    use crate::search_combinator::SearchSpaceCombinator;
+    use crate::search_space::{ObjectiveSense, CheckpointSpace};
+
+    /// bi-objective binary tree: the first child costs (0,1), the second one (1,0)
+    #[derive(Debug)]
+    struct BiObjectiveTree {
+        depth: usize,
+    }
+
+    impl SearchSpace<(usize, [f64; 2]), i64> for BiObjectiveTree {
+        fn initial(&mut self) -> (usize, [f64; 2]) { (0, [0., 0.]) }
+
+        fn bound(&mut self, n:&(usize, [f64; 2])) -> i64 { (n.1[0] + n.1[1]) as i64 }
+
+        fn goal(&mut self, n:&(usize, [f64; 2])) -> bool { n.0 == self.depth }
+
+        fn g_cost(&mut self, n:&(usize, [f64; 2])) -> i64 { (n.1[0] + n.1[1]) as i64 }
+    }
+
+    impl TotalNeighborGeneration<(usize, [f64; 2])> for BiObjectiveTree {
+        fn neighbors(&mut self, n:&mut (usize, [f64; 2])) -> Vec<(usize, [f64; 2])> {
+            if n.0 == self.depth { return Vec::new(); }
+            vec![(n.0+1, [n.1[0], n.1[1]+1.]), (n.0+1, [n.1[0]+1., n.1[1]])]
+        }
+    }
+
+    impl ParetoDominanceSpace<(usize, [f64; 2])> for BiObjectiveTree {
+        fn dominates(&self, a:&(usize, [f64; 2]), b:&(usize, [f64; 2])) -> bool {
+            a.1[0] <= b.1[0] && a.1[1] <= b.1[1] && a.1 != b.1
+        }
+    }
+
+    impl BiObjectiveSpace<(usize, [f64; 2])> for BiObjectiveTree {
+        fn objectives(&self, n:&(usize, [f64; 2])) -> [f64; 2] { n.1 }
+    }
+
+    /// bi-objective tree in which the first objective is maximized
+    #[derive(Debug, SearchSpaceCombinator)]
+    #[combinator(overrides(SearchSpace, CheckpointSpace))]
+    struct MaxTree {
+        #[combinator(inner)]
+        tree: TotalAdapter<BiObjectiveTree>,
+    }
+
+    impl SearchSpace<(usize, [f64; 2]), i64> for MaxTree {
+        fn initial(&mut self) -> (usize, [f64; 2]) { self.tree.initial() }
+
+        fn bound(&mut self, n:&(usize, [f64; 2])) -> i64 { n.1[0] as i64 }
+
+        fn goal(&mut self, n:&(usize, [f64; 2])) -> bool { self.tree.goal(n) }
+
+        fn g_cost(&mut self, n:&(usize, [f64; 2])) -> i64 { n.1[0] as i64 }
+
+        fn sense(&self) -> ObjectiveSense { ObjectiveSense::Maximize }
+    }
+
+    impl CheckpointSpace for MaxTree {}
+
+    #[test]
+    fn complete_front() {
+        let mut algo = ParetoBeamSearch::new(Rc::new(RefCell::new(BiObjectiveTree { depth: 3 })), 100, [4., 4.]);
+        algo.run(NeverStoppingCriterion::default());
+        assert!(algo.is_exact());
+        assert_eq!(algo.get_pareto_manager().len(), 4);
+        assert_eq!(algo.get_pareto_manager().hypervolume(), 10.);
+        let mut stats = json!({});
+        algo.json_statistics(&mut stats);
+        assert_eq!(stats["pareto_front"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn bounded_front() {
+        // the beam search runs over a bounding combinator (the nodes are wrapped)
+        let space = Rc::new(RefCell::new(BoundingCombinator::new(TotalAdapter::new(BiObjectiveTree { depth: 3 }))));
+        let mut algo = ParetoBeamSearch::new(space.clone(), 100, [4., 4.]);
+        algo.run(NeverStoppingCriterion::default());
+        assert!(algo.is_exact());
+        assert_eq!(algo.get_pareto_manager().hypervolume(), 10.);
+        let front:Vec<[f64; 2]> = algo.get_pareto_manager().archive().iter().map(|p| p.node.node.1).collect();
+        assert!(front.contains(&[0., 3.]) && front.contains(&[3., 0.]));
+    }
+
+    #[test]
+    fn narrow_beam() {
+        // a narrow beam keeps the extreme nodes of the front
+        let mut algo = ParetoBeamSearch::new(Rc::new(RefCell::new(BiObjectiveTree { depth: 3 })), 2, [4., 4.]);
+        algo.run(NeverStoppingCriterion::default());
+        assert!(!algo.is_exact());
+        let front:Vec<[f64; 2]> = algo.get_pareto_manager().archive().iter().map(|p| p.objectives).collect();
+        assert!(front.contains(&[0., 3.]) && front.contains(&[3., 0.]));
+    }
+
+    #[test]
+    fn stopped_between_levels() {
+        // the root is expanded, then the search stops before expanding the second level
+        let mut algo = ParetoBeamSearch::new(Rc::new(RefCell::new(BiObjectiveTree { depth: 3 })), 100, [4., 4.]);
+        let budget = NodeBudgetStoppingCriterion::expanded_from(algo.get_pareto_manager().progress(), 1);
+        algo.run(budget);
+        assert_eq!(algo.get_pareto_manager().progress().nb_expanded(), 1);
+        assert!(!algo.is_exact());
+        assert!(algo.get_pareto_manager().is_empty());
+    }
+
+    #[test]
+    fn pareto_sorting() {
+        let points = [[1., 1.], [0., 2.], [2., 2.], [3., 0.]];
+        let fronts = non_dominated_sort(&points, |a,b| a[0] <= b[0] && a[1] <= b[1] && a != b);
+        assert_eq!(fronts, vec![vec![0, 1, 3], vec![2]]);
+        let distances = crowding_distance(&[[0., 2.], [1., 1.], [2., 0.], [1.5, 0.5]]);
+        assert!(distances[0].is_infinite() && distances[2].is_infinite());
+        assert!(distances[1] > distances[3]);
+    }
+
+    #[test]
+    fn maximization_pool() {
+        // the search starts: the pool follows the objective sense of the space (keeps the largest values)
+        let tree = MaxTree { tree: TotalAdapter::new(BiObjectiveTree { depth: 3 }) };
+        let space = Rc::new(RefCell::new(SolutionPoolCombinator::new(tree, 2)));
+        let mut algo:ParetoBeamSearch<_, i64, _> = ParetoBeamSearch::new(space.clone(), 100, [4., 4.]);
+        algo.run(NeverStoppingCriterion::default());
+        assert!(algo.is_exact());
+        let values:Vec<i64> = space.borrow().pool().iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![3, 2]);
+    }
+}